@@ -211,6 +211,7 @@ pub struct AssertParamIsEq<T: Eq + ?Sized> { _field: ::marker::PhantomData<T> }
 /// ```
 #[derive(Clone, Copy, PartialEq, Debug, Hash)]
 #[stable(feature = "rust1", since = "1.0.0")]
+#[cfg_attr(not(stage0), lang = "ordering")]
 pub enum Ordering {
     /// An ordering where a compared value is less [than another].
     #[stable(feature = "rust1", since = "1.0.0")]
@@ -876,6 +877,12 @@ mod impls {
             #[stable(feature = "rust1", since = "1.0.0")]
             impl Ord for $t {
                 #[inline]
+                #[cfg(not(stage0))]
+                fn cmp(&self, other: &$t) -> Ordering {
+                    unsafe { ::intrinsics::three_way_compare(*self, *other) }
+                }
+                #[inline]
+                #[cfg(stage0)]
                 fn cmp(&self, other: &$t) -> Ordering {
                     if *self == *other { Equal }
                     else if *self < *other { Less }
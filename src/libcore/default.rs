@@ -91,6 +91,7 @@
 /// }
 /// ```
 #[stable(feature = "rust1", since = "1.0.0")]
+#[cfg_attr(not(stage0), lang = "default")]
 pub trait Default: Sized {
     /// Returns the "default value" for a type.
     ///
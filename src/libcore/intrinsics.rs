@@ -962,6 +962,16 @@ extern "rust-intrinsic" {
     /// value is not necessarily valid to be used to actually access memory.
     pub fn arith_offset<T>(dst: *const T, offset: isize) -> *const T;
 
+    /// Calculates the distance between two pointers, in units of `T`. This is
+    /// the inverse of `offset`.
+    ///
+    /// # Safety
+    ///
+    /// Both pointers must be derived from a pointer into the same allocated
+    /// object, and the distance between them in bytes must be an exact
+    /// multiple of the size of `T`, which must not be zero-sized.
+    pub fn ptr_offset_from<T>(ptr: *const T, base: *const T) -> isize;
+
     /// Copies `count * size_of<T>` bytes from `src` to `dst`. The source
     /// and destination may *not* overlap.
     ///
@@ -1209,6 +1219,11 @@ extern "rust-intrinsic" {
     /// May assume inputs are finite.
     pub fn frem_fast<T>(a: T, b: T) -> T;
 
+    /// Convert a float to an integer, rounding towards zero.
+    /// The float must not be NaN, and the rounded value must be representable
+    /// in the integer type.
+    pub fn float_to_int_unchecked<Float, Int>(value: Float) -> Int;
+
 
     /// Returns the number of bits set in an integer type `T`
     pub fn ctpop<T>(x: T) -> T;
@@ -1357,6 +1372,32 @@ extern "rust-intrinsic" {
     /// [`std::u32::wrapping_mul`](../../std/primitive.u32.html#method.wrapping_mul)
     pub fn overflowing_mul<T>(a: T, b: T) -> T;
 
+    /// Performs rotate left.
+    /// The stabilized versions of this intrinsic are available on the integer
+    /// primitives via the `rotate_left` method. For example,
+    /// [`std::u32::rotate_left`](../../std/primitive.u32.html#method.rotate_left)
+    #[cfg(not(stage0))]
+    pub fn rotate_left<T>(x: T, y: T) -> T;
+    /// Performs rotate right.
+    /// The stabilized versions of this intrinsic are available on the integer
+    /// primitives via the `rotate_right` method. For example,
+    /// [`std::u32::rotate_right`](../../std/primitive.u32.html#method.rotate_right)
+    #[cfg(not(stage0))]
+    pub fn rotate_right<T>(x: T, y: T) -> T;
+
+    /// Computes `a + b`, saturating at the numeric bounds of `T` instead of overflowing.
+    /// The stabilized versions of this intrinsic are available on the integer
+    /// primitives via the `saturating_add` method. For example,
+    /// [`std::u32::saturating_add`](../../std/primitive.u32.html#method.saturating_add)
+    #[cfg(not(stage0))]
+    pub fn saturating_add<T>(a: T, b: T) -> T;
+    /// Computes `a - b`, saturating at the numeric bounds of `T` instead of overflowing.
+    /// The stabilized versions of this intrinsic are available on the integer
+    /// primitives via the `saturating_sub` method. For example,
+    /// [`std::u32::saturating_sub`](../../std/primitive.u32.html#method.saturating_sub)
+    #[cfg(not(stage0))]
+    pub fn saturating_sub<T>(a: T, b: T) -> T;
+
     /// Returns the value of the discriminant for the variant in 'v',
     /// cast to a `u64`; if `T` has no discriminant, returns 0.
     pub fn discriminant_value<T>(v: &T) -> u64;
@@ -1374,4 +1415,50 @@ extern "rust-intrinsic" {
     /// Emits a `!nontemporal` store according to LLVM (see their docs).
     /// Probably will never become stable.
     pub fn nontemporal_store<T>(ptr: *mut T, val: T);
+
+    /// Returns `true` if the two pointers are guaranteed to be equal.
+    ///
+    /// At runtime this is the same as `ptr == other`. During const evaluation,
+    /// pointers into different allocations cannot always be compared, in which
+    /// case this returns `false`. So `false` does not mean the pointers are unequal.
+    #[cfg(not(stage0))]
+    pub fn ptr_guaranteed_eq<T>(ptr: *const T, other: *const T) -> bool;
+
+    /// Returns `true` if the two pointers are guaranteed to be unequal.
+    ///
+    /// At runtime this is the same as `ptr != other`. During const evaluation,
+    /// pointers into different allocations cannot always be compared, in which
+    /// case this returns `false`. So `false` does not mean the pointers are equal.
+    #[cfg(not(stage0))]
+    pub fn ptr_guaranteed_ne<T>(ptr: *const T, other: *const T) -> bool;
+
+    /// Performs a three-way comparison of two integers, `char`s or `bool`s.
+    ///
+    /// This is what `Ord::cmp` does for these types, but as a single operation,
+    /// which backends can turn into better code than a chain of comparisons.
+    /// Using it with any other type is an error.
+    #[cfg(not(stage0))]
+    pub fn three_way_compare<T: Copy>(lhs: T, rhs: T) -> ::cmp::Ordering;
+}
+
+/// Plain Rust implementations of intrinsics, used by the compile-time evaluator
+/// for intrinsics it does not implement natively.
+///
+/// Each function must have the same name and signature as the intrinsic it implements
+/// and be marked `#[rustc_intrinsic_fallback]`.
+#[cfg(not(stage0))]
+pub mod fallback {
+    // Prefetches are only hints, so there is nothing to do at compile time.
+
+    #[rustc_intrinsic_fallback]
+    pub const fn prefetch_read_data<T>(_data: *const T, _locality: i32) {}
+
+    #[rustc_intrinsic_fallback]
+    pub const fn prefetch_write_data<T>(_data: *const T, _locality: i32) {}
+
+    #[rustc_intrinsic_fallback]
+    pub const fn prefetch_read_instruction<T>(_data: *const T, _locality: i32) {}
+
+    #[rustc_intrinsic_fallback]
+    pub const fn prefetch_write_instruction<T>(_data: *const T, _locality: i32) {}
 }
@@ -157,6 +157,7 @@ use pin::PinMut;
 /// The `Option` type. See [the module level documentation](index.html) for more.
 #[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Debug, Hash)]
 #[stable(feature = "rust1", since = "1.0.0")]
+#[cfg_attr(not(stage0), lang = "option")]
 pub enum Option<T> {
     /// No value
     #[stable(feature = "rust1", since = "1.0.0")]
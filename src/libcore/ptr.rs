@@ -62,6 +62,19 @@ pub unsafe fn drop_in_place<T: ?Sized>(to_drop: *mut T) {
     drop_in_place(to_drop);
 }
 
+/// Drops the value behind the trait object `to_drop` by calling the drop glue in its vtable.
+///
+/// The drop glue of `Box<dyn Trait>` calls this for the box contents, so that the vtable
+/// is read in MIR rather than by the backend. `T` must be a trait object type.
+#[cfg(not(stage0))]
+#[lang = "drop_trait_object"]
+unsafe fn drop_trait_object<T: ?Sized>(to_drop: *mut T) {
+    let object: ::raw::TraitObject = mem::transmute_copy(&to_drop);
+    // The first entry of every vtable is the drop glue of the erased type.
+    let drop_glue = *(object.vtable as *const unsafe fn(*mut ()));
+    drop_glue(object.data)
+}
+
 /// Creates a null raw pointer.
 ///
 /// # Examples
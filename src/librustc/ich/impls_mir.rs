@@ -516,6 +516,7 @@ impl_stable_hash_for!(enum mir::BinOp {
     Ne,
     Ge,
     Gt,
+    Cmp,
     Offset
 });
 
@@ -526,7 +527,8 @@ impl_stable_hash_for!(enum mir::UnOp {
 
 impl_stable_hash_for!(enum mir::NullOp {
     Box,
-    SizeOf
+    SizeOf,
+    AlignOf
 });
 
 impl_stable_hash_for!(struct mir::Constant<'tcx> { span, ty, user_ty, literal });
@@ -531,6 +531,7 @@ for ::mir::interpret::EvalErrorKind<'gcx, O> {
             VtableForArgumentlessMethod |
             ModifiedConstantMemory |
             AssumptionNotHeld |
+            Abort |
             InlineAsm |
             ReallocateNonBasePtr |
             DeallocateNonBasePtr |
@@ -544,12 +545,20 @@ for ::mir::interpret::EvalErrorKind<'gcx, O> {
             DerefFunctionPointer |
             ExecuteMemory |
             OverflowNeg |
+            InexactDivision |
             RemainderByZero |
             DivisionByZero |
             GeneratorResumedAfterReturn |
             GeneratorResumedAfterPanic |
             InfiniteLoop => {}
-            InvalidDiscriminant(val) => val.hash_stable(hcx, hasher),
+            InvalidDiscriminant(ty, val) => {
+                ty.hash_stable(hcx, hasher);
+                val.hash_stable(hcx, hasher)
+            },
+            FloatToIntOutOfRange(ref val, ty) => {
+                val.hash_stable(hcx, hasher);
+                ty.hash_stable(hcx, hasher)
+            },
             Panic { ref msg, ref file, line, col } => {
                 msg.hash_stable(hcx, hasher);
                 file.hash_stable(hcx, hasher);
@@ -558,10 +567,18 @@ for ::mir::interpret::EvalErrorKind<'gcx, O> {
             },
             ReferencedConstant(ref err) => err.hash_stable(hcx, hasher),
             MachineError(ref err) => err.hash_stable(hcx, hasher),
+            UnsupportedInConst { ref construct, ref help } => {
+                construct.hash_stable(hcx, hasher);
+                help.hash_stable(hcx, hasher)
+            },
             FunctionPointerTyMismatch(a, b) => {
                 a.hash_stable(hcx, hasher);
                 b.hash_stable(hcx, hasher)
             },
+            FunctionArgMismatch(a, b) => {
+                a.hash_stable(hcx, hasher);
+                b.hash_stable(hcx, hasher)
+            },
             NoMirFor(ref s) => s.hash_stable(hcx, hasher),
             UnterminatedCString(ptr) => ptr.hash_stable(hcx, hasher),
             PointerOutOfBounds {
@@ -574,20 +591,40 @@ for ::mir::interpret::EvalErrorKind<'gcx, O> {
                 allocation_size.hash_stable(hcx, hasher)
             },
             InvalidBoolOp(bop) => bop.hash_stable(hcx, hasher),
+            InvalidCharOp(bop) => bop.hash_stable(hcx, hasher),
+            InvalidUnaryOp(op, ty) => {
+                op.hash_stable(hcx, hasher);
+                ty.hash_stable(hcx, hasher)
+            },
             Unimplemented(ref s) => s.hash_stable(hcx, hasher),
             BoundsCheck { ref len, ref index } => {
                 len.hash_stable(hcx, hasher);
                 index.hash_stable(hcx, hasher)
             },
+            StepLimitReached(limit) => limit.hash_stable(hcx, hasher),
+            ShiftOverflow { amount, signed, bits } => {
+                amount.hash_stable(hcx, hasher);
+                signed.hash_stable(hcx, hasher);
+                bits.hash_stable(hcx, hasher)
+            },
+            UncheckedDivisionByZero(ref name) => name.hash_stable(hcx, hasher),
+            UncheckedOverflow(ref name) => name.hash_stable(hcx, hasher),
+            OffsetFromZeroSized(ty) => ty.hash_stable(hcx, hasher),
+            OffsetFromInexact { bytes, pointee } => {
+                bytes.hash_stable(hcx, hasher);
+                pointee.hash_stable(hcx, hasher)
+            },
             Intrinsic(ref s) => s.hash_stable(hcx, hasher),
             InvalidChar(c) => c.hash_stable(hcx, hasher),
             AbiViolation(ref s) => s.hash_stable(hcx, hasher),
             AlignmentCheckFailed {
                 required,
                 has,
+                ptr,
             } => {
                 required.hash_stable(hcx, hasher);
-                has.hash_stable(hcx, hasher)
+                has.hash_stable(hcx, hasher);
+                ptr.hash_stable(hcx, hasher)
             },
             MemoryLockViolation {
                 ptr,
@@ -633,6 +670,7 @@ for ::mir::interpret::EvalErrorKind<'gcx, O> {
             },
             ValidationFailure(ref s) => s.hash_stable(hcx, hasher),
             TypeNotPrimitive(ty) => ty.hash_stable(hcx, hasher),
+            SizeOfUnsizedType(ty) => ty.hash_stable(hcx, hasher),
             ReallocatedWrongMemoryKind(ref a, ref b) => {
                 a.hash_stable(hcx, hasher);
                 b.hash_stable(hcx, hasher)
@@ -651,6 +689,12 @@ for ::mir::interpret::EvalErrorKind<'gcx, O> {
             HeapAllocNonPowerOfTwoAlignment(n) => n.hash_stable(hcx, hasher),
             PathNotFound(ref v) => v.hash_stable(hcx, hasher),
             Overflow(op) => op.hash_stable(hcx, hasher),
+            OverflowOperands { op, ref left, ref right } => {
+                op.hash_stable(hcx, hasher);
+                left.hash_stable(hcx, hasher);
+                right.hash_stable(hcx, hasher)
+            },
+            OverflowNegOperand(ref operand) => operand.hash_stable(hcx, hasher),
         }
     }
 }
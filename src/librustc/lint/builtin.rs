@@ -32,6 +32,12 @@ declare_lint! {
     "constant evaluation detected erroneous expression"
 }
 
+declare_lint! {
+    pub UNCONDITIONAL_PANIC,
+    Deny,
+    "operation will cause a panic at runtime"
+}
+
 declare_lint! {
     pub UNUSED_IMPORTS,
     Warn,
@@ -377,6 +383,7 @@ impl LintPass for HardwiredLints {
             PUB_USE_OF_PRIVATE_EXTERN_CRATE,
             INVALID_TYPE_PARAM_DEFAULT,
             CONST_ERR,
+            UNCONDITIONAL_PANIC,
             RENAMED_AND_REMOVED_LINTS,
             SAFE_EXTERN_STATICS,
             SAFE_PACKED_BORROWS,
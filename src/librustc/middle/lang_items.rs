@@ -313,6 +313,7 @@ language_item_table! {
     ExchangeMallocFnLangItem,        "exchange_malloc",         exchange_malloc_fn;
     BoxFreeFnLangItem,               "box_free",                box_free_fn;
     DropInPlaceFnLangItem,           "drop_in_place",           drop_in_place_fn;
+    DropTraitObjectFnLangItem,       "drop_trait_object",       drop_trait_object_fn;
     OomLangItem,                     "oom",                     oom;
     AllocLayoutLangItem,             "alloc_layout",            alloc_layout;
 
@@ -332,6 +333,10 @@ language_item_table! {
 
     DebugTraitLangItem,              "debug_trait",             debug_trait;
 
+    OrderingEnumLangItem,            "ordering",                ordering_enum;
+    OptionEnumLangItem,              "option",                  option_enum;
+    DefaultTraitLangItem,            "default",                 default_trait;
+
     // A lang item for each of the 128-bit operators we can optionally lower.
     I128AddFnLangItem,               "i128_add",                i128_add_fn;
     U128AddFnLangItem,               "u128_add",                u128_add_fn;
@@ -25,6 +25,8 @@ pub fn update_limits(sess: &Session, krate: &ast::Crate) {
                  "recursion limit", 64);
     update_limit(sess, krate, &sess.type_length_limit, "type_length_limit",
                  "type length limit", 1048576);
+    update_limit(sess, krate, &sess.const_eval_limit, "const_eval_limit",
+                 "const eval limit", 2_000_000);
 }
 
 fn update_limit(sess: &Session, krate: &ast::Crate, limit: &Once<usize>,
@@ -18,6 +18,7 @@
 
 use ich::{StableHashingContext, NodeIdHashingMode};
 use util::nodemap::{FxHashMap, FxHashSet};
+use session::Session;
 use ty;
 
 use std::fmt;
@@ -25,6 +26,7 @@ use std::mem;
 use rustc_data_structures::sync::Lrc;
 use syntax::source_map;
 use syntax::ast;
+use syntax::edition::Edition;
 use syntax_pos::{Span, DUMMY_SP};
 use ty::TyCtxt;
 use ty::query::Providers;
@@ -425,9 +427,39 @@ pub struct Context {
     parent: Option<(Scope, ScopeDepth)>,
 }
 
+/// The rules that decide in which scope temporaries are dropped, where they
+/// differ between editions or are being experimented with. Region
+/// resolution only consults this table, so a change to the rules can be
+/// made (and tested) by adding an entry here, without touching the scope
+/// handling of either this pass or MIR building.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TemporaryScopeRules {
+    /// Whether the temporaries of a block's tail expression are dropped at
+    /// the end of the block, before the block's locals, instead of at the
+    /// end of the enclosing terminating scope.
+    pub tail_expr_temporaries_in_block: bool,
+}
+
+impl TemporaryScopeRules {
+    pub fn for_session(sess: &Session) -> TemporaryScopeRules {
+        let mut rules = match sess.edition() {
+            Edition::Edition2015 | Edition::Edition2018 => TemporaryScopeRules {
+                tail_expr_temporaries_in_block: false,
+            },
+        };
+        if sess.opts.debugging_opts.tail_expr_temporary_scope {
+            rules.tail_expr_temporaries_in_block = true;
+        }
+        rules
+    }
+}
+
 struct RegionResolutionVisitor<'a, 'tcx: 'a> {
     tcx: TyCtxt<'a, 'tcx, 'tcx>,
 
+    // The rules for the scopes of temporaries.
+    temporary_scope_rules: TemporaryScopeRules,
+
     // The number of expressions and patterns visited in the current body
     expr_and_pat_count: usize,
 
@@ -848,6 +880,10 @@ fn resolve_block<'a, 'tcx>(visitor: &mut RegionResolutionVisitor<'a, 'tcx>, blk:
     // those temporaries will only be cleaned up *after* both
     // `other_argument()` has run and also the call to `quux(..)`
     // itself has returned.
+    //
+    // If `TemporaryScopeRules::tail_expr_temporaries_in_block` is set,
+    // the tail expression is a terminating scope instead, so those
+    // temporaries are cleaned up before the locals of the block.
 
     visitor.enter_node_scope_with_dtor(blk.hir_id.local_id);
     visitor.cx.var_parent = visitor.cx.parent;
@@ -876,7 +912,12 @@ fn resolve_block<'a, 'tcx>(visitor: &mut RegionResolutionVisitor<'a, 'tcx>, blk:
             }
             visitor.visit_stmt(statement)
         }
-        walk_list!(visitor, visit_expr, &blk.expr);
+        if let Some(ref tail_expr) = blk.expr {
+            if visitor.temporary_scope_rules.tail_expr_temporaries_in_block {
+                visitor.terminating_scopes.insert(tail_expr.hir_id.local_id);
+            }
+            visitor.visit_expr(tail_expr);
+        }
     }
 
     visitor.cx = prev_cx;
@@ -1404,6 +1445,7 @@ fn region_scope_tree<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId)
     let scope_tree = if let Some(body_id) = tcx.hir.maybe_body_owned_by(id) {
         let mut visitor = RegionResolutionVisitor {
             tcx,
+            temporary_scope_rules: TemporaryScopeRules::for_session(tcx.sess),
             scope_tree: ScopeTree::default(),
             expr_and_pat_count: 0,
             cx: Context {
@@ -16,7 +16,7 @@ use ty::layout::{Size, Align};
 use rustc_data_structures::sync::Lrc;
 
 use super::{
-    Pointer, Lock, AccessKind
+    Pointer, Lock, AccessKind, Scalar
 };
 
 use backtrace::Backtrace;
@@ -85,14 +85,19 @@ impl<'a, 'gcx, 'tcx> ConstEvalErr<'tcx> {
         message: &str,
         lint_root: Option<ast::NodeId>,
     ) -> Option<DiagnosticBuilder<'tcx>> {
-        match self.error.kind {
+        // The erroneous constant reports its own failure with a full backtrace, so for
+        // dependent uses we only point back at the root cause instead of reporting it again.
+        let mut root_cause = None;
+        let mut cur = self;
+        while let ::mir::interpret::EvalErrorKind::ReferencedConstant(ref inner) = cur.error.kind {
+            cur = &**inner;
+            root_cause = Some(cur);
+        }
+        match cur.error.kind {
             ::mir::interpret::EvalErrorKind::TypeckError |
             ::mir::interpret::EvalErrorKind::TooGeneric |
             ::mir::interpret::EvalErrorKind::CheckMatchError |
             ::mir::interpret::EvalErrorKind::Layout(_) => return None,
-            ::mir::interpret::EvalErrorKind::ReferencedConstant(ref inner) => {
-                inner.struct_generic(tcx, "referenced constant has errors", lint_root)?.emit();
-            },
             _ => {},
         }
         trace!("reporting const eval failure at {:?}", self.span);
@@ -113,11 +118,51 @@ impl<'a, 'gcx, 'tcx> ConstEvalErr<'tcx> {
             struct_error(tcx, message)
         };
         err.span_label(self.span, self.error.to_string());
+        if let Some(help) = self.error.kind.const_eval_help() {
+            err.help(&help);
+        }
         for FrameInfo { span, location, .. } in &self.stacktrace {
             err.span_label(*span, format!("inside call to `{}`", location));
         }
+        if let Some(backtrace) = self.format_backtrace(tcx) {
+            err.note(&backtrace);
+        }
+        if let Some(root_cause) = root_cause {
+            err.span_note(
+                root_cause.span,
+                &format!("the referenced constant failed to evaluate: {}", root_cause.error),
+            );
+        }
         Some(err)
     }
+
+    /// Lists the calls that were being evaluated when the error occurred, innermost first, with
+    /// runs of identical calls (i.e. recursion) collapsed into one line.
+    fn format_backtrace(&self, tcx: TyCtxtAt<'a, 'gcx, 'tcx>) -> Option<String> {
+        if self.stacktrace.is_empty() {
+            return None;
+        }
+        let mut backtrace = String::from("const evaluation backtrace, innermost call first:");
+        let mut frames = self.stacktrace.iter().peekable();
+        while let Some(frame) = frames.next() {
+            let mut repeated = 1;
+            while frames.peek().map_or(false, |next| {
+                next.span == frame.span && next.location == frame.location
+            }) {
+                frames.next();
+                repeated += 1;
+            }
+            let loc = tcx.sess.source_map().lookup_char_pos(frame.span.lo());
+            backtrace.push_str(&format!(
+                "\n`{}` called at {}:{}:{}",
+                frame.location, loc.file.name, loc.line, loc.col.to_usize() + 1,
+            ));
+            if repeated > 1 {
+                backtrace.push_str(&format!(" ({} times)", repeated));
+            }
+        }
+        Some(backtrace)
+    }
 }
 
 pub fn struct_error<'a, 'gcx, 'tcx>(
@@ -182,7 +227,13 @@ pub enum EvalErrorKind<'tcx, O> {
     /// This variant is used by machines to signal their own errors that do not
     /// match an existing variant
     MachineError(String),
+    /// An operation the compile-time evaluator refuses to perform. `construct` names the
+    /// offending construct, `help` optionally points at a feature gate or workaround.
+    UnsupportedInConst { construct: String, help: Option<String> },
     FunctionPointerTyMismatch(FnSig<'tcx>, FnSig<'tcx>),
+    /// An argument was passed to a parameter whose type is passed differently, e.g. after
+    /// transmuting a function pointer. The parameter type comes first.
+    FunctionArgMismatch(Ty<'tcx>, Ty<'tcx>),
     NoMirFor(String),
     UnterminatedCString(Pointer),
     DanglingPointerDeref,
@@ -190,7 +241,11 @@ pub enum EvalErrorKind<'tcx, O> {
     InvalidMemoryAccess,
     InvalidFunctionPointer,
     InvalidBool,
-    InvalidDiscriminant(u128),
+    /// A discriminant that matches no variant was read from a value of the given enum type.
+    InvalidDiscriminant(Ty<'tcx>, u128),
+    /// A float, rendered like a Rust literal, was converted to an integer type `Ty` that
+    /// cannot represent it, e.g. NaN or a value too large for the type.
+    FloatToIntOutOfRange(String, Ty<'tcx>),
     PointerOutOfBounds {
         ptr: Pointer,
         access: bool,
@@ -204,23 +259,51 @@ pub enum EvalErrorKind<'tcx, O> {
     ReadUndefBytes,
     DeadLocal,
     InvalidBoolOp(mir::BinOp),
+    InvalidCharOp(mir::BinOp),
+    /// A unary operation was applied to a type it is not defined for, e.g. `Neg` to an
+    /// unsigned integer.
+    InvalidUnaryOp(mir::UnOp, Ty<'tcx>),
     Unimplemented(String),
     DerefFunctionPointer,
     ExecuteMemory,
     BoundsCheck { len: O, index: O },
     Overflow(mir::BinOp),
     OverflowNeg,
+    /// An `Overflow` assertion that failed during evaluation, with the rendered operands.
+    OverflowOperands { op: mir::BinOp, left: String, right: String },
+    /// An `OverflowNeg` assertion that failed during evaluation, with the rendered operand.
+    OverflowNegOperand(String),
+    /// A shift by a negative amount, or by at least the width of the shifted integer, in a
+    /// machine that treats such shifts as undefined behavior. `amount` holds the bits of the
+    /// shift amount, sign extended if `signed` is set.
+    ShiftOverflow { amount: u128, signed: bool, bits: u64 },
+    /// An `exact_div` whose dividend is not a multiple of its divisor.
+    InexactDivision,
+    /// The named unchecked arithmetic intrinsic, e.g. `unchecked_div`, was called with a
+    /// divisor of zero.
+    UncheckedDivisionByZero(String),
+    /// The named unchecked arithmetic intrinsic overflowed, e.g. `unchecked_div(-128i8, -1)`.
+    UncheckedOverflow(String),
+    /// `ptr_offset_from` on pointers to a zero-sized type.
+    OffsetFromZeroSized(Ty<'tcx>),
+    /// `ptr_offset_from` on pointers whose distance in bytes is not a multiple of the size of
+    /// the pointee type.
+    OffsetFromInexact { bytes: i64, pointee: Ty<'tcx> },
     DivisionByZero,
     RemainderByZero,
     Intrinsic(String),
     InvalidChar(u128),
     StackFrameLimitReached,
+    /// The machine's limit on the number of executed terminators was hit.
+    StepLimitReached(u64),
     OutOfTls,
     TlsOutOfBounds,
     AbiViolation(String),
     AlignmentCheckFailed {
         required: Align,
         has: Align,
+        /// The pointer that was found to be misaligned.
+        ptr: Scalar,
     },
     MemoryLockViolation {
         ptr: Pointer,
@@ -250,8 +333,12 @@ pub enum EvalErrorKind<'tcx, O> {
     VtableForArgumentlessMethod,
     ModifiedConstantMemory,
     AssumptionNotHeld,
+    /// The program called the `abort` intrinsic.
+    Abort,
     InlineAsm,
     TypeNotPrimitive(Ty<'tcx>),
+    /// The size or alignment of an unsized type, e.g. an extern type, was requested.
+    SizeOfUnsizedType(Ty<'tcx>),
     ReallocatedWrongMemoryKind(String, String),
     DeallocatedWrongMemoryKind(String, String),
     ReallocateNonBasePtr,
@@ -285,13 +372,30 @@ pub enum EvalErrorKind<'tcx, O> {
 
 pub type EvalResult<'tcx, T = ()> = Result<T, EvalError<'tcx>>;
 
+fn overflow_description(op: mir::BinOp) -> &'static str {
+    match op {
+        mir::BinOp::Add => "attempt to add with overflow",
+        mir::BinOp::Sub => "attempt to subtract with overflow",
+        mir::BinOp::Mul => "attempt to multiply with overflow",
+        mir::BinOp::Div => "attempt to divide with overflow",
+        mir::BinOp::Rem => "attempt to calculate the remainder with overflow",
+        mir::BinOp::Shr => "attempt to shift right with overflow",
+        mir::BinOp::Shl => "attempt to shift left with overflow",
+        op => bug!("{:?} cannot overflow", op),
+    }
+}
+
 impl<'tcx, O> EvalErrorKind<'tcx, O> {
     pub fn description(&self) -> &str {
         use self::EvalErrorKind::*;
         match *self {
             MachineError(ref inner) => inner,
+            UnsupportedInConst { .. } =>
+                "this operation is not supported during constant evaluation",
             FunctionPointerTyMismatch(..) =>
                 "tried to call a function through a function pointer of a different type",
+            FunctionArgMismatch(..) =>
+                "tried to pass an argument of a type that is incompatible with the parameter",
             InvalidMemoryAccess =>
                 "tried to access memory through an invalid pointer",
             DanglingPointerDeref =>
@@ -304,6 +408,8 @@ impl<'tcx, O> EvalErrorKind<'tcx, O> {
                 "invalid boolean value read",
             InvalidDiscriminant(..) =>
                 "invalid enum discriminant value read",
+            FloatToIntOutOfRange(..) =>
+                "converted a float to an integer type that cannot represent its value",
             PointerOutOfBounds { .. } =>
                 "pointer offset outside bounds of allocation",
             InvalidNullPointerUsage =>
@@ -333,6 +439,10 @@ impl<'tcx, O> EvalErrorKind<'tcx, O> {
                 "tried to access a dead local variable",
             InvalidBoolOp(_) =>
                 "invalid boolean operation",
+            InvalidCharOp(_) =>
+                "invalid operation on chars",
+            InvalidUnaryOp(..) =>
+                "invalid unary operation",
             Unimplemented(ref msg) => msg,
             DerefFunctionPointer =>
                 "tried to dereference a function pointer",
@@ -348,6 +458,8 @@ impl<'tcx, O> EvalErrorKind<'tcx, O> {
                 "tried to interpret an invalid 32-bit value as a char",
             StackFrameLimitReached =>
                 "reached the configured maximum number of stack frames",
+            StepLimitReached(_) =>
+                "constant evaluation took too long",
             OutOfTls =>
                 "reached the maximum number of representable TLS keys",
             TlsOutOfBounds =>
@@ -363,10 +475,14 @@ impl<'tcx, O> EvalErrorKind<'tcx, O> {
                 "tried to modify constant memory",
             AssumptionNotHeld =>
                 "`assume` argument was false",
+            Abort =>
+                "the evaluated program aborted",
             InlineAsm =>
                 "miri does not support inline assembly",
             TypeNotPrimitive(_) =>
                 "expected primitive type, got nonprimitive",
+            SizeOfUnsizedType(_) =>
+                "size of unsized type cannot be known at compile time",
             ReallocatedWrongMemoryKind(_, _) =>
                 "tried to reallocate memory from one kind to another",
             DeallocatedWrongMemoryKind(_, _) =>
@@ -405,21 +521,57 @@ impl<'tcx, O> EvalErrorKind<'tcx, O> {
                 "match checking failed",
             ReferencedConstant(_) =>
                 "referenced constant has errors",
-            Overflow(mir::BinOp::Add) => "attempt to add with overflow",
-            Overflow(mir::BinOp::Sub) => "attempt to subtract with overflow",
-            Overflow(mir::BinOp::Mul) => "attempt to multiply with overflow",
-            Overflow(mir::BinOp::Div) => "attempt to divide with overflow",
-            Overflow(mir::BinOp::Rem) => "attempt to calculate the remainder with overflow",
-            OverflowNeg => "attempt to negate with overflow",
-            Overflow(mir::BinOp::Shr) => "attempt to shift right with overflow",
-            Overflow(mir::BinOp::Shl) => "attempt to shift left with overflow",
-            Overflow(op) => bug!("{:?} cannot overflow", op),
+            Overflow(op) |
+            OverflowOperands { op, .. } => overflow_description(op),
+            OverflowNeg |
+            OverflowNegOperand(_) => "attempt to negate with overflow",
+            ShiftOverflow { .. } =>
+                "shift by an amount out of range for the shifted type",
+            InexactDivision => "exact division with a remainder",
+            UncheckedDivisionByZero(_) =>
+                "division by zero in an unchecked arithmetic intrinsic",
+            UncheckedOverflow(_) =>
+                "overflow in an unchecked arithmetic intrinsic",
+            OffsetFromZeroSized(_) =>
+                "`ptr_offset_from` called on pointers to a zero-sized type",
+            OffsetFromInexact { .. } =>
+                "`ptr_offset_from` called on pointers not a whole number of elements apart",
             DivisionByZero => "attempt to divide by zero",
             RemainderByZero => "attempt to calculate the remainder with a divisor of zero",
             GeneratorResumedAfterReturn => "generator resumed after completion",
             GeneratorResumedAfterPanic => "generator resumed after panicking",
             InfiniteLoop =>
-                "duplicate interpreter state observed here, const evaluation will never terminate",
+                "infinite loop detected in constant evaluation: this state was observed before",
+        }
+    }
+
+    /// A hint for the user on how to avoid this error when it was raised while evaluating a
+    /// constant, e.g. the feature gate or runtime workaround for a refused operation.
+    pub fn const_eval_help(&self) -> Option<String> {
+        use self::EvalErrorKind::*;
+        match *self {
+            UnsupportedInConst { ref help, .. } => help.clone(),
+            ReadPointerAsBytes => Some(
+                "the address of a pointer is not known at compile time, so its bytes cannot be \
+                 inspected through a union field, a transmute or a cast to an integer".to_string()
+            ),
+            ReadBytesAsPointer => Some(
+                "only pointers derived from references or statics can be dereferenced during \
+                 constant evaluation; integers cast to pointers cannot".to_string()
+            ),
+            InvalidPointerMath => Some(
+                "pointers into different allocations cannot be compared or subtracted during \
+                 constant evaluation".to_string()
+            ),
+            DanglingPointerDeref => Some(
+                "the memory behind this pointer was freed, e.g. because it belonged to a local \
+                 variable that went out of scope".to_string()
+            ),
+            ReadUndefBytes => Some(
+                "this can be caused by reading a union field other than the one that was last \
+                 written".to_string()
+            ),
+            _ => None,
         }
     }
 }
@@ -462,6 +614,9 @@ impl<'tcx, O: fmt::Debug> fmt::Debug for EvalErrorKind<'tcx, O> {
             FunctionPointerTyMismatch(sig, got) =>
                 write!(f, "tried to call a function with sig {} through a \
                        function pointer of type {}", sig, got),
+            FunctionArgMismatch(callee_ty, caller_ty) =>
+                write!(f, "calling a function with argument of type {} passing data of type {}",
+                       callee_ty, caller_ty),
             BoundsCheck { ref len, ref index } =>
                 write!(f, "index out of bounds: the len is {:?} but the index is {:?}", len, index),
             ReallocatedWrongMemoryKind(ref old, ref new) =>
@@ -472,25 +627,105 @@ impl<'tcx, O: fmt::Debug> fmt::Debug for EvalErrorKind<'tcx, O> {
                 write!(f, "{}", err),
             InvalidChar(c) =>
                 write!(f, "tried to interpret an invalid 32-bit value as a char: {}", c),
-            AlignmentCheckFailed { required, has } =>
-               write!(f, "tried to access memory with alignment {}, but alignment {} is required",
-                      has.abi(), required.abi()),
+            AlignmentCheckFailed { required, has, ptr } => {
+                write!(f, "tried to access memory with alignment {}, but alignment {} is required",
+                       has.abi(), required.abi())?;
+                match ptr {
+                    Scalar::Ptr(ptr) =>
+                        write!(f, " (pointer to offset {} of allocation {})",
+                               ptr.offset.bytes(), ptr.alloc_id),
+                    Scalar::Bits { bits, .. } =>
+                        write!(f, " (address {:#x})", bits),
+                }
+            }
             TypeNotPrimitive(ty) =>
                 write!(f, "expected primitive type, got {}", ty),
+            SizeOfUnsizedType(ty) =>
+                write!(f, "size of unsized type `{}` cannot be known at compile time", ty),
             Layout(ref err) =>
                 write!(f, "rustc layout computation failed: {:?}", err),
             PathNotFound(ref path) =>
                 write!(f, "Cannot find path {:?}", path),
             MachineError(ref inner) =>
                 write!(f, "{}", inner),
+            UnsupportedInConst { ref construct, .. } =>
+                write!(f, "{} is not supported in constants", construct),
             IncorrectAllocationInformation(size, size2, align, align2) =>
                 write!(f, "incorrect alloc info: expected size {} and align {}, got size {} and \
                        align {}", size.bytes(), align.abi(), size2.bytes(), align2.abi()),
             Panic { ref msg, line, col, ref file } =>
                 write!(f, "the evaluated program panicked at '{}', {}:{}:{}", msg, file, line, col),
-            InvalidDiscriminant(val) =>
-                write!(f, "encountered invalid enum discriminant {}", val),
+            InvalidDiscriminant(ty, val) =>
+                write!(f, "enum value of type `{}` has invalid discriminant: {}", ty, val),
+            FloatToIntOutOfRange(ref val, ty) =>
+                write!(f, "converting `{}` to `{}`, which cannot represent it", val, ty),
+            ShiftOverflow { amount, signed: true, bits } =>
+                write!(f, "shift by {} is out of range for a {}-bit integer",
+                       amount as i128, bits),
+            ShiftOverflow { amount, signed: false, bits } =>
+                write!(f, "shift by {} is out of range for a {}-bit integer", amount, bits),
+            UncheckedDivisionByZero(ref name) =>
+                write!(f, "`{}` by zero", name),
+            UncheckedOverflow(ref name) =>
+                write!(f, "`{}` with overflow", name),
+            OffsetFromZeroSized(ty) =>
+                write!(f, "`ptr_offset_from` called on pointers to the zero-sized type `{}`", ty),
+            OffsetFromInexact { bytes, pointee } =>
+                write!(f, "`ptr_offset_from` called on pointers {} bytes apart, which is not a \
+                           multiple of the size of `{}`", bytes, pointee),
+            StepLimitReached(limit) =>
+                write!(f, "constant evaluation took too long: exceeded the limit of {} steps \
+                           (use `#![feature(const_eval_limit)]` and `#![const_eval_limit=\"N\"]` \
+                           to raise it)", limit),
+            InvalidBoolOp(op) =>
+                write!(f, "invalid boolean operation: {:?}", op),
+            InvalidCharOp(op) =>
+                write!(f, "invalid operation on chars: {:?}", op),
+            InvalidUnaryOp(op, ty) =>
+                write!(f, "invalid unary operation {:?} on a value of type `{}`", op, ty),
+            OverflowOperands { op, ref left, ref right } =>
+                write!(f, "{}: `{} {} {}`", self.description(), left,
+                       op.to_hir_binop().as_str(), right),
+            OverflowNegOperand(ref operand) =>
+                write!(f, "{}: `-({})`", self.description(), operand),
             _ => write!(f, "{}", self.description()),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::EvalErrorKind;
+    use mir::BinOp;
+
+    fn message(err: EvalErrorKind<u64>) -> String {
+        format!("{:?}", err)
+    }
+
+    #[test]
+    fn invalid_ops_show_operation() {
+        assert_eq!(message(EvalErrorKind::InvalidBoolOp(BinOp::Add)),
+                   "invalid boolean operation: Add");
+        assert_eq!(message(EvalErrorKind::InvalidCharOp(BinOp::Shl)),
+                   "invalid operation on chars: Shl");
+    }
+
+    fn shift_overflow(amount: u128, signed: bool, bits: u64) -> String {
+        message(EvalErrorKind::ShiftOverflow { amount, signed, bits })
+    }
+
+    #[test]
+    fn shift_overflow_shows_actual_amount() {
+        assert_eq!(shift_overflow(8, false, 8), "shift by 8 is out of range for a 8-bit integer");
+        // Amounts beyond `i128::MAX` are not clamped
+        assert_eq!(
+            shift_overflow(u128::max_value(), false, 128),
+            concat!("shift by 340282366920938463463374607431768211455 ",
+                    "is out of range for a 128-bit integer"),
+        );
+        assert_eq!(
+            shift_overflow(!0, true, 32),
+            "shift by -1 is out of range for a 32-bit integer",
+        );
+    }
+}
@@ -31,7 +31,7 @@ use hir::def_id::DefId;
 use ty::{self, TyCtxt, Instance};
 use ty::layout::{self, Align, HasDataLayout, Size};
 use middle::region;
-use std::iter;
+use std::cmp;
 use std::io;
 use std::ops::{Deref, DerefMut};
 use std::hash::Hash;
@@ -98,7 +98,10 @@ pub trait PointerArithmetic: layout::HasDataLayout {
             // trickery to ensure that i64::min_value() works fine
             // this formula only works for true negative values, it panics for zero!
             let n = u64::max_value() - (i as u64) + 1;
-            val.overflowing_sub(n)
+            let (res, over1) = val.overflowing_sub(n);
+            // the subtraction wrapped at 64 bits, which is too late for smaller pointers
+            let (res, over2) = self.truncate_to_ptr(res as u128);
+            (res, over1 || over2)
         } else {
             self.overflowing_offset(val, i as u64)
         }
@@ -521,6 +524,11 @@ impl Allocation {
         Allocation::from_bytes(slice, Align::from_bytes(1, 1).unwrap())
     }
 
+    /// Creates a mutable allocation of `size` undefined bytes.
+    ///
+    /// The bytes are zeroed by the allocator, which can defer mapping them until they are
+    /// first written. The interpreter does not store zeros over bytes that are still zero,
+    /// so a large allocation that only ever holds zeros is never materialized.
     pub fn undef(size: Size, align: Align) -> Self {
         assert_eq!(size.bytes() as usize as u64, size.bytes());
         Allocation {
@@ -611,25 +619,37 @@ pub fn truncate(value: u128, size: Size) -> u128 {
 // Undefined byte tracking
 ////////////////////////////////////////////////////////////////////////////////
 
-type Block = u64;
-const BLOCK_SIZE: u64 = 64;
-
+/// Tracks which bytes of an allocation are defined.
+///
+/// The defined bytes are stored as a sorted list of disjoint, non-adjacent ranges, so large
+/// allocations that are entirely defined or entirely undefined take constant space.
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Hash, RustcEncodable, RustcDecodable)]
 pub struct UndefMask {
-    blocks: Vec<Block>,
+    /// The end-exclusive ranges of defined bytes.
+    defined: Vec<(Size, Size)>,
     len: Size,
 }
 
-impl_stable_hash_for!(struct mir::interpret::UndefMask{blocks, len});
+impl_stable_hash_for!(struct mir::interpret::UndefMask{defined, len});
 
 impl UndefMask {
     pub fn new(size: Size) -> Self {
-        let mut m = UndefMask {
-            blocks: vec![],
-            len: Size::ZERO,
-        };
-        m.grow(size, false);
-        m
+        UndefMask {
+            defined: vec![],
+            len: size,
+        }
+    }
+
+    /// The index of the first range that ends after `offset`, or could be extended to cover it
+    /// if `adjacent` is set.
+    fn first_range_reaching(&self, offset: Size, adjacent: bool) -> usize {
+        self.defined.binary_search_by(|&(_, end)| {
+            if end < offset || (!adjacent && end == offset) {
+                cmp::Ordering::Less
+            } else {
+                cmp::Ordering::Greater
+            }
+        }).unwrap_err()
     }
 
     /// Check whether the range `start..end` (end-exclusive) is entirely defined.
@@ -637,12 +657,39 @@ impl UndefMask {
         if end > self.len {
             return false;
         }
-        for i in start.bytes()..end.bytes() {
-            if !self.get(Size::from_bytes(i)) {
-                return false;
+        if start >= end {
+            return true;
+        }
+        match self.defined.get(self.first_range_reaching(start, false)) {
+            Some(&(range_start, range_end)) => range_start <= start && end <= range_end,
+            None => false,
+        }
+    }
+
+    /// Splits `start..end` (end-exclusive) into maximal runs of bytes with the same
+    /// definedness, returned as `(start, end, defined)` triples.
+    pub fn runs(&self, start: Size, end: Size) -> Vec<(Size, Size, bool)> {
+        let mut runs = vec![];
+        let mut pos = start;
+        for &(range_start, range_end) in &self.defined[self.first_range_reaching(start, false)..] {
+            if pos >= end {
+                break;
+            }
+            if range_start > pos {
+                let undef_end = cmp::min(range_start, end);
+                runs.push((pos, undef_end, false));
+                pos = undef_end;
+            }
+            if pos < end {
+                let def_end = cmp::min(range_end, end);
+                runs.push((pos, def_end, true));
+                pos = def_end;
             }
         }
-        true
+        if pos < end {
+            runs.push((pos, end, false));
+        }
+        runs
     }
 
     pub fn set_range(&mut self, start: Size, end: Size, new_state: bool) {
@@ -654,48 +701,137 @@ impl UndefMask {
     }
 
     pub fn set_range_inbounds(&mut self, start: Size, end: Size, new_state: bool) {
-        for i in start.bytes()..end.bytes() {
-            self.set(Size::from_bytes(i), new_state);
+        if start >= end {
+            return;
+        }
+        // All ranges in `lo..hi` touch `start..end` and get replaced.
+        let lo = self.first_range_reaching(start, new_state);
+        let mut hi = lo;
+        while hi < self.defined.len() &&
+            (self.defined[hi].0 < end || (new_state && self.defined[hi].0 == end))
+        {
+            hi += 1;
         }
+        let replacement = if new_state {
+            let mut merged = (start, end);
+            if lo < hi {
+                merged.0 = cmp::min(merged.0, self.defined[lo].0);
+                merged.1 = cmp::max(merged.1, self.defined[hi - 1].1);
+            }
+            vec![merged]
+        } else {
+            let mut kept = vec![];
+            if lo < hi {
+                if self.defined[lo].0 < start {
+                    kept.push((self.defined[lo].0, start));
+                }
+                if self.defined[hi - 1].1 > end {
+                    kept.push((end, self.defined[hi - 1].1));
+                }
+            }
+            kept
+        };
+        self.defined.splice(lo..hi, replacement);
     }
 
     #[inline]
     pub fn get(&self, i: Size) -> bool {
-        let (block, bit) = bit_index(i);
-        (self.blocks[block] & 1 << bit) != 0
+        self.is_range_defined(i, i + Size::from_bytes(1))
     }
 
     #[inline]
     pub fn set(&mut self, i: Size, new_state: bool) {
-        let (block, bit) = bit_index(i);
-        if new_state {
-            self.blocks[block] |= 1 << bit;
-        } else {
-            self.blocks[block] &= !(1 << bit);
-        }
+        self.set_range_inbounds(i, i + Size::from_bytes(1), new_state);
     }
 
     pub fn grow(&mut self, amount: Size, new_state: bool) {
-        let unused_trailing_bits = self.blocks.len() as u64 * BLOCK_SIZE - self.len.bytes();
-        if amount.bytes() > unused_trailing_bits {
-            let additional_blocks = amount.bytes() / BLOCK_SIZE + 1;
-            assert_eq!(additional_blocks as usize as u64, additional_blocks);
-            self.blocks.extend(
-                iter::repeat(0).take(additional_blocks as usize),
-            );
-        }
         let start = self.len;
         self.len += amount;
         self.set_range_inbounds(start, start + amount, new_state);
     }
 }
 
-#[inline]
-fn bit_index(bits: Size) -> (usize, usize) {
-    let bits = bits.bytes();
-    let a = bits / BLOCK_SIZE;
-    let b = bits % BLOCK_SIZE;
-    assert_eq!(a as usize as u64, a);
-    assert_eq!(b as usize as u64, b);
-    (a as usize, b as usize)
+#[cfg(test)]
+mod tests {
+    use super::UndefMask;
+    use ty::layout::Size;
+
+    fn mask(len: u64, defined: &[(u64, u64)]) -> UndefMask {
+        let mut mask = UndefMask::new(Size::from_bytes(len));
+        for &(start, end) in defined {
+            mask.set_range_inbounds(Size::from_bytes(start), Size::from_bytes(end), true);
+        }
+        mask
+    }
+
+    fn ranges(mask: &UndefMask) -> Vec<(u64, u64)> {
+        mask.defined.iter().map(|&(start, end)| (start.bytes(), end.bytes())).collect()
+    }
+
+    fn is_defined(mask: &UndefMask, start: u64, end: u64) -> bool {
+        mask.is_range_defined(Size::from_bytes(start), Size::from_bytes(end))
+    }
+
+    #[test]
+    fn new_mask_is_undefined() {
+        let mask = mask(10, &[]);
+        assert!(ranges(&mask).is_empty());
+        assert!(is_defined(&mask, 3, 3));
+        assert!(!is_defined(&mask, 0, 1));
+        assert!(!is_defined(&mask, 0, 11));
+    }
+
+    #[test]
+    fn adjacent_ranges_merge() {
+        let mut mask = mask(10, &[(2, 4)]);
+        mask.set(Size::from_bytes(4), true);
+        assert_eq!(ranges(&mask), [(2, 5)]);
+        mask.set_range_inbounds(Size::from_bytes(7), Size::from_bytes(9), true);
+        assert_eq!(ranges(&mask), [(2, 5), (7, 9)]);
+        // Filling the gap joins both ranges
+        mask.set_range_inbounds(Size::from_bytes(5), Size::from_bytes(7), true);
+        assert_eq!(ranges(&mask), [(2, 9)]);
+        assert!(is_defined(&mask, 2, 9));
+        assert!(!is_defined(&mask, 1, 9));
+        assert!(!is_defined(&mask, 2, 10));
+    }
+
+    #[test]
+    fn undefining_splits_and_removes_ranges() {
+        let mut mask = mask(10, &[(2, 9)]);
+        mask.set_range_inbounds(Size::from_bytes(4), Size::from_bytes(6), false);
+        assert_eq!(ranges(&mask), [(2, 4), (6, 9)]);
+        assert!(!is_defined(&mask, 3, 5));
+        assert!(is_defined(&mask, 6, 9));
+        mask.set_range_inbounds(Size::from_bytes(2), Size::from_bytes(4), false);
+        assert_eq!(ranges(&mask), [(6, 9)]);
+        mask.set(Size::from_bytes(8), false);
+        assert_eq!(ranges(&mask), [(6, 8)]);
+    }
+
+    #[test]
+    fn runs_alternate() {
+        let mask = mask(10, &[(2, 5), (7, 9)]);
+        let runs: Vec<_> = mask.runs(Size::ZERO, Size::from_bytes(10)).into_iter()
+            .map(|(start, end, defined)| (start.bytes(), end.bytes(), defined))
+            .collect();
+        assert_eq!(runs, [
+            (0, 2, false), (2, 5, true), (5, 7, false), (7, 9, true), (9, 10, false),
+        ]);
+        let runs: Vec<_> = mask.runs(Size::from_bytes(3), Size::from_bytes(4)).into_iter()
+            .map(|(start, end, defined)| (start.bytes(), end.bytes(), defined))
+            .collect();
+        assert_eq!(runs, [(3, 4, true)]);
+    }
+
+    #[test]
+    fn grow_appends_range() {
+        let mut mask = mask(10, &[(6, 9)]);
+        mask.grow(Size::from_bytes(5), true);
+        assert_eq!(ranges(&mask), [(6, 9), (10, 15)]);
+        assert!(!mask.get(Size::from_bytes(9)));
+        assert!(mask.get(Size::from_bytes(14)));
+        mask.grow(Size::from_bytes(1), true);
+        assert_eq!(ranges(&mask), [(6, 9), (10, 16)]);
+    }
 }
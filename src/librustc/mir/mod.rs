@@ -2057,6 +2057,8 @@ pub enum BinOp {
     Ge,
     /// The `>` operator (greater than)
     Gt,
+    /// The three-way comparison of integers, `char`s and `bool`s, producing an `Ordering`
+    Cmp,
     /// The `ptr.offset` operator
     Offset,
 }
@@ -2075,6 +2077,8 @@ impl BinOp {
 pub enum NullOp {
     /// Return the size of a value of that type
     SizeOf,
+    /// Return the minimum alignment of a value of that type
+    AlignOf,
     /// Create a new uninitialized box for a value of that type
     Box,
 }
@@ -207,7 +207,8 @@ impl<'tcx> Rvalue<'tcx> {
                 }
             }
             Rvalue::NullaryOp(NullOp::Box, t) => tcx.mk_box(t),
-            Rvalue::NullaryOp(NullOp::SizeOf, _) => tcx.types.usize,
+            Rvalue::NullaryOp(NullOp::SizeOf, _) |
+            Rvalue::NullaryOp(NullOp::AlignOf, _) => tcx.types.usize,
             Rvalue::Aggregate(ref ak, ref ops) => {
                 match **ak {
                     AggregateKind::Array(ty) => {
@@ -273,6 +274,11 @@ impl<'tcx> BinOp {
             &BinOp::Ne | &BinOp::Ge | &BinOp::Gt => {
                 tcx.types.bool
             }
+            &BinOp::Cmp => {
+                // these should be of the same type
+                assert_eq!(lhs_ty, rhs_ty);
+                tcx.mk_ordering()
+            }
         }
     }
 }
@@ -310,6 +316,7 @@ impl BinOp {
             BinOp::Gt => hir::BinOpKind::Gt,
             BinOp::Le => hir::BinOpKind::Le,
             BinOp::Ge => hir::BinOpKind::Ge,
+            BinOp::Cmp |
             BinOp::Offset => unreachable!()
         }
     }
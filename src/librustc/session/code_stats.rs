@@ -9,7 +9,7 @@
 // except according to those terms.
 
 use rustc_target::abi::{Align, Size};
-use rustc_data_structures::fx::{FxHashSet};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use std::cmp::{self, Ordering};
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
@@ -179,3 +179,68 @@ impl CodeStats {
         }
     }
 }
+
+/// Statistics about what a single MIR pass did, summed over all bodies it ran on.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct MirPassInfo {
+    pub runs: usize,
+    pub statements_removed: usize,
+    pub statements_added: usize,
+    pub statements_rewritten: usize,
+    pub blocks_before: usize,
+    pub blocks_after: usize,
+    pub locals_before: usize,
+    pub locals_after: usize,
+}
+
+impl MirPassInfo {
+    fn changed_anything(&self) -> bool {
+        self.statements_removed != 0 || self.statements_added != 0 ||
+            self.statements_rewritten != 0 || self.blocks_before != self.blocks_after ||
+            self.locals_before != self.locals_after
+    }
+}
+
+/// Per-pass transformation statistics gathered with `-Z mir-pass-stats`.
+#[derive(PartialEq, Eq, Debug)]
+pub struct MirPassStats {
+    passes: FxHashMap<String, MirPassInfo>,
+}
+
+impl MirPassStats {
+    pub fn new() -> Self { MirPassStats { passes: FxHashMap() } }
+
+    pub fn record_pass<S: ToString>(&mut self, pass_name: S, info: MirPassInfo) {
+        let entry = self.passes.entry(pass_name.to_string()).or_default();
+        entry.runs += info.runs;
+        entry.statements_removed += info.statements_removed;
+        entry.statements_added += info.statements_added;
+        entry.statements_rewritten += info.statements_rewritten;
+        entry.blocks_before += info.blocks_before;
+        entry.blocks_after += info.blocks_after;
+        entry.locals_before += info.locals_before;
+        entry.locals_after += info.locals_after;
+    }
+
+    pub fn print_mir_pass_stats(&self) {
+        let mut sorted: Vec<_> = self.passes.iter().collect();
+        sorted.sort_by(|&(name1, _), &(name2, _)| name1.cmp(name2));
+
+        println!("{:<40} {:>8} {:>16} {:>16} {:>16} {:>8}",
+                 "mir-pass-stats pass", "runs", "stmts -/+/~", "blocks old/new",
+                 "locals old/new", "effect");
+        for (name, info) in sorted {
+            let effect = if info.changed_anything() { "yes" } else { "none" };
+            println!("{:<40} {:>8} {:>16} {:>16} {:>16} {:>8}",
+                     format!("mir-pass-stats {}", name),
+                     info.runs,
+                     format!("{}/{}/{}",
+                             info.statements_removed,
+                             info.statements_added,
+                             info.statements_rewritten),
+                     format!("{}/{}", info.blocks_before, info.blocks_after),
+                     format!("{}/{}", info.locals_before, info.locals_after),
+                     effect);
+        }
+    }
+}
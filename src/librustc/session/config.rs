@@ -1242,6 +1242,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
           "print the result of the monomorphization collection pass"),
     mir_opt_level: usize = (1, parse_uint, [TRACKED],
           "set the MIR optimization level (0-3, default: 1)"),
+    move_path_depth_limit: Option<usize> = (None, parse_opt_uint, [TRACKED],
+          "the number of nested projections MIR borrowck tracks moves for; deeper \
+           places are treated as their ancestor at that depth (default: no limit)"),
     mutable_noalias: Option<bool> = (None, parse_opt_bool, [TRACKED],
           "emit noalias metadata for mutable references (default: yes on LLVM >= 6)"),
     arg_align_attributes: bool = (false, parse_bool, [TRACKED],
@@ -1254,6 +1257,10 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
           "in addition to `.mir` files, create graphviz `.dot` files"),
     dump_mir_exclude_pass_number: bool = (false, parse_bool, [UNTRACKED],
           "if set, exclude the pass number when dumping MIR (used in tests)"),
+    trace_const_eval: Option<String> = (None, parse_opt_string, [UNTRACKED],
+          "print every MIR statement and terminator executed during constant evaluation; \
+           `all` traces everything, otherwise only items whose path contains the given string \
+           and the functions they call"),
     mir_emit_validate: usize = (0, parse_uint, [TRACKED],
           "emit Validate MIR statements, interpreted e.g. by miri (0: do not emit; 1: if function \
            contains unsafe block, only validate arguments; 2: always emit full validation)"),
@@ -1263,6 +1270,8 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
           "print some statistics about AST and HIR"),
     mir_stats: bool = (false, parse_bool, [UNTRACKED],
           "print some statistics about MIR"),
+    mir_pass_stats: bool = (false, parse_bool, [UNTRACKED],
+          "print how many statements, blocks and locals each MIR pass removed or rewrote"),
     always_encode_mir: bool = (false, parse_bool, [TRACKED],
           "encode MIR of all functions into the crate metadata"),
     osx_rpath_install_name: bool = (false, parse_bool, [TRACKED],
@@ -1356,6 +1365,21 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
           "run the self profiler"),
     profile_json: bool = (false, parse_bool, [UNTRACKED],
           "output a json file with profiler results"),
+    abort_on_foreign_unwind: bool = (false, parse_bool, [TRACKED],
+          "abort when a panic unwinds out of a function with a non-Rust ABI that is not \
+           marked `#[unwind(allowed)]`"),
+    unleash_the_miri_inside_of_you: bool = (false, parse_bool, [TRACKED],
+          "take the brakes off const evaluation: report static const checks as warnings and \
+           let the evaluator attempt the code anyway. NOTE: this is unsound"),
+    tail_expr_temporary_scope: bool = (false, parse_bool, [TRACKED],
+          "drop the temporaries of a block's tail expression at the end of the block, \
+           before its locals"),
+    unwind_in_const_eval: bool = (false, parse_bool, [TRACKED],
+          "emulate `panic=unwind` during const evaluation: run the cleanup blocks of all frames \
+           before reporting a panic"),
+    validate_const_eval_writes: bool = (false, parse_bool, [TRACKED],
+          "check every value written during const evaluation to be valid at the type it is \
+           written at, not just the final value"),
 }
 
 pub fn default_lib_output() -> CrateType {
@@ -3107,6 +3131,8 @@ mod tests {
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.dump_mir_graphviz = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.trace_const_eval = Some(String::from("abc"));
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
 
         // Make sure changing a [TRACKED] option changes the hash
         opts = reference.clone();
@@ -3157,6 +3183,30 @@ mod tests {
         opts.debugging_opts.mir_opt_level = 3;
         assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
 
+        opts = reference.clone();
+        opts.debugging_opts.move_path_depth_limit = Some(4);
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.unleash_the_miri_inside_of_you = true;
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.abort_on_foreign_unwind = true;
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.tail_expr_temporary_scope = true;
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.unwind_in_const_eval = true;
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.validate_const_eval_writes = true;
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
         opts = reference.clone();
         opts.debugging_opts.relro_level = Some(RelroLevel::Full);
         assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
@@ -8,8 +8,8 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-pub use self::code_stats::{DataTypeKind, SizeKind, FieldInfo, VariantInfo};
-use self::code_stats::CodeStats;
+pub use self::code_stats::{DataTypeKind, SizeKind, FieldInfo, VariantInfo, MirPassInfo};
+use self::code_stats::{CodeStats, MirPassStats};
 
 use hir::def_id::CrateNum;
 use rustc_data_structures::fingerprint::Fingerprint;
@@ -112,6 +112,10 @@ pub struct Session {
     /// The maximum number of stackframes allowed in const eval
     pub const_eval_stack_frame_limit: usize,
 
+    /// The maximum number of terminators a single constant may evaluate, or 0
+    /// for no limit.
+    pub const_eval_limit: Once<usize>,
+
     /// The metadata::creader module may inject an allocator/panic_runtime
     /// dependency if it didn't already find one, and this tracks what was
     /// injected.
@@ -138,6 +142,9 @@ pub struct Session {
     /// Data about code being compiled, gathered during compilation.
     pub code_stats: Lock<CodeStats>,
 
+    /// What each MIR pass changed, gathered with `-Z mir-pass-stats`.
+    pub mir_pass_stats: Lock<MirPassStats>,
+
     next_node_id: OneThread<Cell<ast::NodeId>>,
 
     /// If -zfuel=crate=n is specified, Some(crate).
@@ -1118,6 +1125,7 @@ pub fn build_session_(
         recursion_limit: Once::new(),
         type_length_limit: Once::new(),
         const_eval_stack_frame_limit: 100,
+        const_eval_limit: Once::new(),
         next_node_id: OneThread::new(Cell::new(NodeId::new(1))),
         injected_allocator: Once::new(),
         allocator_kind: Once::new(),
@@ -1134,6 +1142,7 @@ pub fn build_session_(
             normalize_projection_ty: AtomicUsize::new(0),
         },
         code_stats: Lock::new(CodeStats::new()),
+        mir_pass_stats: Lock::new(MirPassStats::new()),
         optimization_fuel_crate,
         optimization_fuel_limit,
         print_fuel_crate,
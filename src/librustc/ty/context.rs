@@ -2408,6 +2408,12 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
         self.mk_imm_ref(self.types.re_static, self.mk_str())
     }
 
+    /// The type `core::cmp::Ordering`, the result of a three-way comparison.
+    pub fn mk_ordering(self) -> Ty<'tcx> {
+        let def_id = self.require_lang_item(lang_items::OrderingEnumLangItem);
+        self.type_of(def_id)
+    }
+
     pub fn mk_adt(self, def: &'tcx AdtDef, substs: &'tcx Substs<'tcx>) -> Ty<'tcx> {
         // take a copy of substs so that we own the vectors inside
         self.mk_ty(Adt(def, substs))
@@ -487,10 +487,18 @@ impl<'a, 'tcx, O: Lift<'tcx>> Lift<'tcx> for interpret::EvalErrorKind<'a, O> {
         use ::mir::interpret::EvalErrorKind::*;
         Some(match *self {
             MachineError(ref err) => MachineError(err.clone()),
+            UnsupportedInConst { ref construct, ref help } => UnsupportedInConst {
+                construct: construct.clone(),
+                help: help.clone(),
+            },
             FunctionPointerTyMismatch(a, b) => FunctionPointerTyMismatch(
                 tcx.lift(&a)?,
                 tcx.lift(&b)?,
             ),
+            FunctionArgMismatch(a, b) => FunctionArgMismatch(
+                tcx.lift(&a)?,
+                tcx.lift(&b)?,
+            ),
             NoMirFor(ref s) => NoMirFor(s.clone()),
             UnterminatedCString(ptr) => UnterminatedCString(ptr),
             DanglingPointerDeref => DanglingPointerDeref,
@@ -498,7 +506,8 @@ impl<'a, 'tcx, O: Lift<'tcx>> Lift<'tcx> for interpret::EvalErrorKind<'a, O> {
             InvalidMemoryAccess => InvalidMemoryAccess,
             InvalidFunctionPointer => InvalidFunctionPointer,
             InvalidBool => InvalidBool,
-            InvalidDiscriminant(val) => InvalidDiscriminant(val),
+            InvalidDiscriminant(ty, val) => InvalidDiscriminant(tcx.lift(&ty)?, val),
+            FloatToIntOutOfRange(ref val, ty) => FloatToIntOutOfRange(val.clone(), tcx.lift(&ty)?),
             PointerOutOfBounds {
                 ptr,
                 access,
@@ -512,6 +521,8 @@ impl<'a, 'tcx, O: Lift<'tcx>> Lift<'tcx> for interpret::EvalErrorKind<'a, O> {
             ReadUndefBytes => ReadUndefBytes,
             DeadLocal => DeadLocal,
             InvalidBoolOp(bop) => InvalidBoolOp(bop),
+            InvalidCharOp(bop) => InvalidCharOp(bop),
+            InvalidUnaryOp(op, ty) => InvalidUnaryOp(op, tcx.lift(&ty)?),
             Unimplemented(ref s) => Unimplemented(s.clone()),
             DerefFunctionPointer => DerefFunctionPointer,
             ExecuteMemory => ExecuteMemory,
@@ -522,13 +533,15 @@ impl<'a, 'tcx, O: Lift<'tcx>> Lift<'tcx> for interpret::EvalErrorKind<'a, O> {
             Intrinsic(ref s) => Intrinsic(s.clone()),
             InvalidChar(c) => InvalidChar(c),
             StackFrameLimitReached => StackFrameLimitReached,
+            StepLimitReached(limit) => StepLimitReached(limit),
             OutOfTls => OutOfTls,
             TlsOutOfBounds => TlsOutOfBounds,
             AbiViolation(ref s) => AbiViolation(s.clone()),
             AlignmentCheckFailed {
                 required,
                 has,
-            } => AlignmentCheckFailed { required, has },
+                ptr,
+            } => AlignmentCheckFailed { required, has, ptr },
             MemoryLockViolation {
                 ptr,
                 len,
@@ -557,8 +570,10 @@ impl<'a, 'tcx, O: Lift<'tcx>> Lift<'tcx> for interpret::EvalErrorKind<'a, O> {
             VtableForArgumentlessMethod => VtableForArgumentlessMethod,
             ModifiedConstantMemory => ModifiedConstantMemory,
             AssumptionNotHeld => AssumptionNotHeld,
+            Abort => Abort,
             InlineAsm => InlineAsm,
             TypeNotPrimitive(ty) => TypeNotPrimitive(tcx.lift(&ty)?),
+            SizeOfUnsizedType(ty) => SizeOfUnsizedType(tcx.lift(&ty)?),
             ReallocatedWrongMemoryKind(ref a, ref b) => {
                 ReallocatedWrongMemoryKind(a.clone(), b.clone())
             },
@@ -587,7 +602,22 @@ impl<'a, 'tcx, O: Lift<'tcx>> Lift<'tcx> for interpret::EvalErrorKind<'a, O> {
             CheckMatchError => CheckMatchError,
             ReferencedConstant(ref err) => ReferencedConstant(tcx.lift(&**err)?.into()),
             OverflowNeg => OverflowNeg,
+            ShiftOverflow { amount, signed, bits } => ShiftOverflow { amount, signed, bits },
+            InexactDivision => InexactDivision,
+            UncheckedDivisionByZero(ref name) => UncheckedDivisionByZero(name.clone()),
+            UncheckedOverflow(ref name) => UncheckedOverflow(name.clone()),
+            OffsetFromZeroSized(ty) => OffsetFromZeroSized(tcx.lift(&ty)?),
+            OffsetFromInexact { bytes, pointee } => OffsetFromInexact {
+                bytes,
+                pointee: tcx.lift(&pointee)?,
+            },
             Overflow(op) => Overflow(op),
+            OverflowOperands { op, ref left, ref right } => OverflowOperands {
+                op,
+                left: left.clone(),
+                right: right.clone(),
+            },
+            OverflowNegOperand(ref operand) => OverflowNegOperand(operand.clone()),
             DivisionByZero => DivisionByZero,
             RemainderByZero => RemainderByZero,
             GeneratorResumedAfterReturn => GeneratorResumedAfterReturn,
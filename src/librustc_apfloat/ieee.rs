@@ -1021,6 +1021,7 @@ impl<S: Semantics> Float for IeeeFloat<S> {
             (_, Category::Zero) => Status::INVALID_OP.and(Self::NAN),
 
             (Category::Normal, Category::Normal) => {
+                let sign = self.sign;
                 while self.is_finite_non_zero() && rhs.is_finite_non_zero() &&
                     self.cmp_abs_normal(rhs) != Ordering::Less
                 {
@@ -1034,6 +1035,11 @@ impl<S: Semantics> Float for IeeeFloat<S> {
                     self = unpack!(status=, self - v);
                     assert_eq!(status, Status::OK);
                 }
+                // Like C fmod, an exact zero result keeps the sign of the dividend,
+                // even though `x - x` would produce +0.
+                if self.is_zero() {
+                    self.sign = sign;
+                }
                 Status::OK.and(self)
             }
         }
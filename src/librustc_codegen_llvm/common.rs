@@ -378,6 +378,22 @@ pub fn build_unchecked_rshift(
     }
 }
 
+/// Compares two integers into an `Ordering`, which is an `i8` with `Less = -1`,
+/// as `(lhs > rhs) - (lhs < rhs)`.
+pub fn build_three_way_compare(
+    bx: &Builder<'a, 'll, 'tcx>, is_signed: bool, lhs: &'ll Value, rhs: &'ll Value
+) -> &'ll Value {
+    let (lt, gt) = if is_signed {
+        (llvm::IntSLT, llvm::IntSGT)
+    } else {
+        (llvm::IntULT, llvm::IntUGT)
+    };
+    let i8_ty = Type::i8(bx.cx);
+    let is_lt = bx.zext(bx.icmp(lt, lhs, rhs), i8_ty);
+    let is_gt = bx.zext(bx.icmp(gt, lhs, rhs), i8_ty);
+    bx.sub(is_gt, is_lt)
+}
+
 fn shift_mask_rhs(bx: &Builder<'a, 'll, 'tcx>, rhs: &'ll Value) -> &'ll Value {
     let rhs_llty = val_ty(rhs);
     bx.and(rhs, shift_mask_val(bx, rhs_llty, rhs_llty, false))
@@ -211,6 +211,19 @@ pub fn codegen_intrinsic_call(
             let offset = args[1].immediate();
             bx.gep(ptr, &[offset])
         }
+        "ptr_offset_from" => {
+            let pointee_size = cx.size_of(substs.type_at(0)).bytes();
+            let ptr = bx.ptrtoint(args[0].immediate(), cx.isize_ty);
+            let base = bx.ptrtoint(args[1].immediate(), cx.isize_ty);
+            bx.exactsdiv(bx.sub(ptr, base), C_usize(cx, pointee_size))
+        }
+        // At runtime, all pointer comparisons are guaranteed.
+        "ptr_guaranteed_eq" => {
+            bx.icmp(llvm::IntEQ, args[0].immediate(), args[1].immediate())
+        }
+        "ptr_guaranteed_ne" => {
+            bx.icmp(llvm::IntNE, args[0].immediate(), args[1].immediate())
+        }
 
         "copy_nonoverlapping" => {
             copy_intrinsic(bx, false, false, substs.type_at(0),
@@ -284,6 +297,7 @@ pub fn codegen_intrinsic_call(
         "ctlz" | "ctlz_nonzero" | "cttz" | "cttz_nonzero" | "ctpop" | "bswap" |
         "bitreverse" | "add_with_overflow" | "sub_with_overflow" |
         "mul_with_overflow" | "overflowing_add" | "overflowing_sub" | "overflowing_mul" |
+        "saturating_add" | "saturating_sub" | "rotate_left" | "rotate_right" |
         "unchecked_div" | "unchecked_rem" | "unchecked_shl" | "unchecked_shr" | "exact_div" => {
             let ty = arg_tys[0];
             match int_type_width_signed(ty, cx) {
@@ -338,6 +352,47 @@ pub fn codegen_intrinsic_call(
                         "overflowing_add" => bx.add(args[0].immediate(), args[1].immediate()),
                         "overflowing_sub" => bx.sub(args[0].immediate(), args[1].immediate()),
                         "overflowing_mul" => bx.mul(args[0].immediate(), args[1].immediate()),
+                        "saturating_add" | "saturating_sub" => {
+                            let is_add = name == "saturating_add";
+                            let intrinsic = format!("llvm.{}{}.with.overflow.i{}",
+                                                    if signed { 's' } else { 'u' },
+                                                    if is_add { "add" } else { "sub" },
+                                                    width);
+                            let llfn = bx.cx.get_intrinsic(&intrinsic);
+                            let lhs = args[0].immediate();
+                            let pair = bx.call(llfn, &[lhs, args[1].immediate()], None);
+                            let val = bx.extract_value(pair, 0);
+                            let overflow = bx.extract_value(pair, 1);
+                            let llty = val_ty(lhs);
+                            let clamped = if signed {
+                                // Overflow always happens in the direction of the sign of `lhs`
+                                let min = 1u128 << (width - 1);
+                                let is_neg = bx.icmp(llvm::IntSLT, lhs, C_null(llty));
+                                bx.select(is_neg, C_uint_big(llty, min), C_uint_big(llty, min - 1))
+                            } else if is_add {
+                                C_uint_big(llty, u128::max_value() >> (128 - width))
+                            } else {
+                                C_null(llty)
+                            };
+                            bx.select(overflow, clamped, val)
+                        }
+                        "rotate_left" | "rotate_right" => {
+                            let is_left = name == "rotate_left";
+                            let val = args[0].immediate();
+                            let raw_shift = args[1].immediate();
+                            let llty = val_ty(val);
+                            // The width is a power of two, so masking the shift amounts with
+                            // `width - 1` takes them modulo the width
+                            let mask = C_uint(llty, width - 1);
+                            let shift = bx.and(raw_shift, mask);
+                            let inv_shift = bx.and(bx.sub(C_uint(llty, width), raw_shift), mask);
+                            let (shift1, shift2) = if is_left {
+                                (bx.shl(val, shift), bx.lshr(val, inv_shift))
+                            } else {
+                                (bx.lshr(val, shift), bx.shl(val, inv_shift))
+                            };
+                            bx.or(shift1, shift2)
+                        }
                         "exact_div" =>
                             if signed {
                                 bx.exactsdiv(args[0].immediate(), args[1].immediate())
@@ -398,6 +453,43 @@ pub fn codegen_intrinsic_call(
 
         },
 
+        "three_way_compare" => {
+            let ty = arg_tys[0];
+            let signed = match ty.sty {
+                ty::Int(_) => true,
+                ty::Uint(_) | ty::Char | ty::Bool => false,
+                _ => {
+                    span_invalid_monomorphization_error(
+                        tcx.sess, span,
+                        &format!("invalid monomorphization of `three_way_compare` intrinsic: \
+                                  expected basic integer, `char` or `bool` type, found `{}`", ty));
+                    return;
+                }
+            };
+            build_three_way_compare(bx, signed, args[0].immediate(), args[1].immediate())
+        }
+
+        "float_to_int_unchecked" => {
+            if float_type_width(&arg_tys[0].sty).is_none() {
+                span_invalid_monomorphization_error(
+                    tcx.sess, span,
+                    &format!("invalid monomorphization of `float_to_int_unchecked` \
+                              intrinsic: expected basic float type, found `{}`", arg_tys[0]));
+                return;
+            }
+            match int_type_width_signed(ret_ty, cx) {
+                Some((_width, true)) => bx.fptosi(args[0].immediate(), llret_ty),
+                Some((_width, false)) => bx.fptoui(args[0].immediate(), llret_ty),
+                None => {
+                    span_invalid_monomorphization_error(
+                        tcx.sess, span,
+                        &format!("invalid monomorphization of `float_to_int_unchecked` \
+                                  intrinsic: expected basic integer type, found `{}`", ret_ty));
+                    return;
+                }
+            }
+        }
+
         "discriminant_value" => {
             args[0].deref(bx.cx).codegen_get_discr(bx, ret_ty)
         }
@@ -486,9 +486,14 @@ impl FunctionCx<'a, 'll, 'tcx> {
                 })
             }
 
-            mir::Rvalue::NullaryOp(mir::NullOp::SizeOf, ty) => {
+            mir::Rvalue::NullaryOp(mir::NullOp::SizeOf, ty) |
+            mir::Rvalue::NullaryOp(mir::NullOp::AlignOf, ty) => {
                 assert!(bx.cx.type_is_sized(ty));
-                let val = C_usize(bx.cx, bx.cx.size_of(ty).bytes());
+                let val = match *rvalue {
+                    mir::Rvalue::NullaryOp(mir::NullOp::SizeOf, _) => bx.cx.size_of(ty).bytes(),
+                    _ => bx.cx.align_of(ty).abi(),
+                };
+                let val = C_usize(bx.cx, val);
                 let tcx = bx.tcx();
                 (bx, OperandRef {
                     val: OperandValue::Immediate(val),
@@ -525,10 +530,27 @@ impl FunctionCx<'a, 'll, 'tcx> {
                 let operand = self.codegen_operand(&bx, operand);
                 (bx, operand)
             }
-            mir::Rvalue::Repeat(..) |
             mir::Rvalue::Aggregate(..) => {
-                // According to `rvalue_creates_operand`, only ZST
+                let ty = rvalue.ty(self.mir, self.cx.tcx);
+                let layout = self.cx.layout_of(self.monomorphize(&ty));
+                // According to `rvalue_creates_operand`, only ZST and scalar pair
                 // aggregate rvalues are allowed to be operands.
+                if let Some((a, b)) = self.scalar_pair_aggregate_operands(rvalue) {
+                    let a = self.codegen_operand(&bx, a).immediate();
+                    let b = self.codegen_operand(&bx, b).immediate();
+                    // HACK(eddyb) have to bitcast pointers until LLVM removes pointee types.
+                    let a = bx.bitcast(a, layout.scalar_pair_element_llvm_type(bx.cx, 0, true));
+                    let b = bx.bitcast(b, layout.scalar_pair_element_llvm_type(bx.cx, 1, true));
+                    return (bx, OperandRef {
+                        val: OperandValue::Pair(a, b),
+                        layout,
+                    });
+                }
+                (bx, OperandRef::new_zst(self.cx, layout))
+            }
+            mir::Rvalue::Repeat(..) => {
+                // According to `rvalue_creates_operand`, only ZST
+                // repeat rvalues are allowed to be operands.
                 let ty = rvalue.ty(self.mir, self.cx.tcx);
                 (bx, OperandRef::new_zst(self.cx,
                     self.cx.layout_of(self.monomorphize(&ty))))
@@ -603,6 +625,11 @@ impl FunctionCx<'a, 'll, 'tcx> {
             mir::BinOp::Offset => bx.inbounds_gep(lhs, &[rhs]),
             mir::BinOp::Shl => common::build_unchecked_lshift(bx, lhs, rhs),
             mir::BinOp::Shr => common::build_unchecked_rshift(bx, input_ty, lhs, rhs),
+            mir::BinOp::Cmp => if is_nil {
+                C_u8(bx.cx, 0)
+            } else {
+                common::build_three_way_compare(bx, is_signed, lhs, rhs)
+            },
             mir::BinOp::Ne | mir::BinOp::Lt | mir::BinOp::Gt |
             mir::BinOp::Eq | mir::BinOp::Le | mir::BinOp::Ge => if is_nil {
                 C_bool(bx.cx, match op {
@@ -737,12 +764,51 @@ impl FunctionCx<'a, 'll, 'tcx> {
             mir::Rvalue::Aggregate(..) => {
                 let ty = rvalue.ty(self.mir, self.cx.tcx);
                 let ty = self.monomorphize(&ty);
-                self.cx.layout_of(ty).is_zst()
+                self.cx.layout_of(ty).is_zst() ||
+                    self.scalar_pair_aggregate_operands(rvalue).is_some()
             }
         }
 
         // (*) this is only true if the type is suitable
     }
+
+    /// If `rvalue` builds a struct, tuple or closure whose layout is a scalar
+    /// pair made of its two non-ZST fields, returns the operands for the two
+    /// halves of the pair, in memory order. Such aggregates are built directly
+    /// as `OperandValue::Pair`, which the Rust ABI passes and returns as two
+    /// immediates, instead of being spilled to a temporary first.
+    fn scalar_pair_aggregate_operands<'r>(&self, rvalue: &'r mir::Rvalue<'tcx>)
+        -> Option<(&'r mir::Operand<'tcx>, &'r mir::Operand<'tcx>)>
+    {
+        let (kind, operands) = match *rvalue {
+            mir::Rvalue::Aggregate(ref kind, ref operands) => (kind, operands),
+            _ => return None,
+        };
+        match **kind {
+            mir::AggregateKind::Adt(adt_def, ..) if adt_def.is_struct() => {}
+            mir::AggregateKind::Tuple |
+            mir::AggregateKind::Closure(..) => {}
+            _ => return None,
+        }
+
+        let ty = self.monomorphize(&rvalue.ty(self.mir, self.cx.tcx));
+        let layout = self.cx.layout_of(ty);
+        if !layout.is_llvm_scalar_pair() {
+            return None;
+        }
+
+        let mut fields = (0..operands.len()).filter(|&i| !layout.field(self.cx, i).is_zst());
+        let (a, b) = match (fields.next(), fields.next(), fields.next()) {
+            (Some(a), Some(b), None) => (a, b),
+            // A newtype around a scalar pair, leave it to the general path.
+            _ => return None,
+        };
+        if layout.fields.offset(a) <= layout.fields.offset(b) {
+            Some((&operands[a], &operands[b]))
+        } else {
+            Some((&operands[b], &operands[a]))
+        }
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -8,7 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use array_vec::ArrayVec;
+use std::cmp;
 use std::fmt;
 use std::iter;
 use std::marker::PhantomData;
@@ -108,8 +108,13 @@ impl<T: Idx> IdxSet<T> {
         // upon creation, due to rounding up to a whole word. That's ok.
         let domain_size = self.bits.len() * BITS_PER_WORD;
 
-        // Note: we currently don't bother trying to make a Sparse set.
-        HybridIdxSet::Dense(self.to_owned(), domain_size)
+        let len: usize = self.bits.iter().map(|word| word.count_ones() as usize).sum();
+        if len <= sparse_max(domain_size) {
+            // `iter` yields the elements in ascending order, as `SparseIdxSet` wants them.
+            HybridIdxSet::Sparse(SparseIdxSet(self.iter().collect()), domain_size)
+        } else {
+            HybridIdxSet::Dense(self.to_owned(), domain_size)
+        }
     }
 
     /// Removes all elements
@@ -241,18 +246,30 @@ impl<'a, T: Idx> Iterator for Iter<'a, T> {
     }
 }
 
+/// Hybrid sets with at most this many elements are always sparse.
 const SPARSE_MAX: usize = 8;
 
-/// A sparse index set with a maximum of SPARSE_MAX elements. Used by
-/// HybridIdxSet; do not use directly.
+/// In large domains, hybrid sets stay sparse as long as at most one in
+/// `SPARSE_DENSITY` possible elements is present. Up to there, the sparse
+/// representation (one index per element) is smaller than the dense one
+/// (one bit per possible element).
+const SPARSE_DENSITY: usize = 64;
+
+/// The maximum number of elements of a sparse set over `domain_size` elements.
+fn sparse_max(domain_size: usize) -> usize {
+    cmp::max(SPARSE_MAX, domain_size / SPARSE_DENSITY)
+}
+
+/// A sparse index set with a maximum of `sparse_max(domain_size)` elements.
+/// Used by HybridIdxSet; do not use directly.
 ///
-/// The elements are stored as an unsorted vector with no duplicates.
+/// The elements are stored as a sorted vector with no duplicates.
 #[derive(Clone, Debug)]
-pub struct SparseIdxSet<T: Idx>(ArrayVec<[T; SPARSE_MAX]>);
+pub struct SparseIdxSet<T: Idx>(Vec<T>);
 
 impl<T: Idx> SparseIdxSet<T> {
     fn new() -> Self {
-        SparseIdxSet(ArrayVec::new())
+        SparseIdxSet(Vec::new())
     }
 
     fn len(&self) -> usize {
@@ -260,28 +277,27 @@ impl<T: Idx> SparseIdxSet<T> {
     }
 
     fn contains(&self, elem: &T) -> bool {
-        self.0.contains(elem)
+        self.0.binary_search(elem).is_ok()
     }
 
     fn add(&mut self, elem: &T) -> bool {
-        // Ensure there are no duplicates.
-        if self.0.contains(elem) {
-            false
-        } else {
-            self.0.push(*elem);
-            true
+        // Ensure there are no duplicates, and keep the elements sorted.
+        match self.0.binary_search(elem) {
+            Ok(_) => false,
+            Err(i) => {
+                self.0.insert(i, *elem);
+                true
+            }
         }
     }
 
     fn remove(&mut self, elem: &T) -> bool {
-        if let Some(i) = self.0.iter().position(|e| e == elem) {
-            // Swap the found element to the end, then pop it.
-            let len = self.0.len();
-            self.0.swap(i, len - 1);
-            self.0.pop();
-            true
-        } else {
-            false
+        match self.0.binary_search(elem) {
+            Ok(i) => {
+                self.0.remove(i);
+                true
+            }
+            Err(_) => false,
         }
     }
 
@@ -336,6 +352,8 @@ impl<'a, T: Idx> Iterator for SparseIter<'a, T> {
 /// elements in the set, but dense when there are many. It's especially
 /// efficient for sets that typically have a small number of elements, but a
 /// large `domain_size`, and are cleared frequently.
+///
+/// How many elements count as "few" grows with the domain size, see `sparse_max`.
 #[derive(Clone, Debug)]
 pub enum HybridIdxSet<T: Idx> {
     Sparse(SparseIdxSet<T>, usize),
@@ -366,7 +384,7 @@ impl<T: Idx> HybridIdxSet<T> {
     /// Adds `elem` to the set `self`.
     pub fn add(&mut self, elem: &T) -> bool {
         match self {
-            HybridIdxSet::Sparse(sparse, _) if sparse.len() < SPARSE_MAX => {
+            HybridIdxSet::Sparse(sparse, size) if sparse.len() < sparse_max(*size) => {
                 // The set is sparse and has space for `elem`.
                 sparse.add(elem)
             }
@@ -496,3 +514,53 @@ fn test_new_filled() {
         assert_eq!(elems, expected);
     }
 }
+
+#[test]
+fn test_hybrid_density() {
+    // Small domains switch to the dense representation after `SPARSE_MAX` elements.
+    let mut set: HybridIdxSet<usize> = HybridIdxSet::new_empty(128);
+    for i in 0..SPARSE_MAX {
+        set.add(&(i * 3));
+    }
+    match set {
+        HybridIdxSet::Sparse(..) => {}
+        HybridIdxSet::Dense(..) => panic!("became dense too early"),
+    }
+    set.add(&1);
+    match set {
+        HybridIdxSet::Sparse(..) => panic!("should have become dense"),
+        HybridIdxSet::Dense(..) => {}
+    }
+
+    // Large domains stay sparse until the density threshold.
+    let domain_size = 64 * 1024;
+    let threshold = domain_size / SPARSE_DENSITY;
+    let mut set: HybridIdxSet<usize> = HybridIdxSet::new_empty(domain_size);
+    for i in (0..threshold).rev() {
+        set.add(&(i * 7));
+    }
+    assert!(!set.add(&0));
+    match set {
+        HybridIdxSet::Sparse(..) => {}
+        HybridIdxSet::Dense(..) => panic!("became dense too early"),
+    }
+    let elems: Vec<usize> = set.iter().collect();
+    let expected: Vec<usize> = (0..threshold).map(|i| i * 7).collect();
+    assert_eq!(elems, expected);
+    assert!(set.remove(&7));
+    assert!(!set.contains(&7));
+    assert!(set.contains(&14));
+
+    // Converting from a dense set picks the representation by density.
+    let mut dense: IdxSet<usize> = IdxSet::new_empty(domain_size);
+    dense.add(&5);
+    dense.add(&500);
+    match dense.to_hybrid() {
+        HybridIdxSet::Sparse(sparse, _) => assert_eq!(sparse.0, vec![5, 500]),
+        HybridIdxSet::Dense(..) => panic!("should be sparse"),
+    }
+    match IdxSet::<usize>::new_filled(domain_size).to_hybrid() {
+        HybridIdxSet::Sparse(..) => panic!("should be dense"),
+        HybridIdxSet::Dense(..) => {}
+    }
+}
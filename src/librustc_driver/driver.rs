@@ -352,6 +352,10 @@ pub fn compile_input(
         sess.print_perf_stats();
     }
 
+    if sess.opts.debugging_opts.mir_pass_stats {
+        sess.mir_pass_stats.borrow().print_mir_pass_stats();
+    }
+
     if sess.opts.debugging_opts.self_profile {
         sess.print_profiler_results();
 
@@ -9,6 +9,10 @@
 // except according to those terms.
 
 use borrow_check::WriteKind;
+use rustc::hir;
+use rustc::hir::def_id::{DefId, LocalDefId};
+use rustc::hir::intravisit::{self, NestedVisitorMap, Visitor};
+use rustc::hir::Node;
 use rustc::middle::region::ScopeTree;
 use rustc::mir::VarBindingForm;
 use rustc::mir::{BindingForm, BorrowKind, ClearCrossCrate, Field, Local};
@@ -18,6 +22,7 @@ use rustc::ty;
 use rustc_data_structures::indexed_vec::Idx;
 use rustc_data_structures::sync::Lrc;
 use rustc_errors::DiagnosticBuilder;
+use syntax::ast;
 use syntax_pos::Span;
 
 use super::borrow_set::BorrowData;
@@ -206,6 +211,9 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
 
         move_spans.var_span_label(&mut err, "move occurs due to use in closure");
 
+        self.note_closure_capture(borrow_spans, &mut err);
+        self.note_closure_capture(move_spans, &mut err);
+
         self.explain_why_borrow_contains_point(context, borrow, None, &mut err);
         err.buffer(&mut self.errors_buffer);
     }
@@ -242,6 +250,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
 
             format!("borrow occurs due to use of `{}` in closure", desc_place)
         });
+        self.note_closure_capture(borrow_spans, &mut err);
 
         self.explain_why_borrow_contains_point(context, borrow, None, &mut err);
         err.buffer(&mut self.errors_buffer);
@@ -368,7 +377,9 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                 &mut err,
                 format!("second borrow occurs due to use of `{}` in closure", desc_place),
             );
+            self.note_closure_capture(issued_spans, &mut err);
         }
+        self.note_closure_capture(borrow_spans, &mut err);
 
         self.explain_why_borrow_contains_point(context, issued_borrow, None, &mut err);
 
@@ -539,6 +550,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         );
 
         loan_spans.var_span_label(&mut err, "borrow occurs due to use in closure");
+        self.note_closure_capture(loan_spans, &mut err);
 
         self.explain_why_borrow_contains_point(context, loan, None, &mut err);
 
@@ -909,7 +921,11 @@ pub(super) enum UseSpans {
         // it's present.
         args_span: Span,
         // The span of the first use of the captured variable inside the closure.
-        var_span: Span
+        var_span: Span,
+        // The closure itself.
+        closure_def_id: DefId,
+        // The captured variable.
+        var_id: ast::NodeId,
     },
     // This access has a single span associated to it: common case.
     OtherUse(Span),
@@ -988,7 +1004,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
 
                 if let Some(node_id) = self.tcx.hir.as_local_node_id(def_id) {
                     if let Closure(_, _, _, args_span, _) = self.tcx.hir.expect_expr(node_id).node {
-                        if let Some(var_span) = self.tcx.with_freevars(node_id, |freevars| {
+                        let captured = self.tcx.with_freevars(node_id, |freevars| {
                             for (v, place) in freevars.iter().zip(places) {
                                 match place {
                                     Operand::Copy(place) | Operand::Move(place)
@@ -998,16 +1014,19 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                                             "find_closure_move_span: found captured local {:?}",
                                             place
                                         );
-                                        return Some(v.span);
+                                        return Some((v.span, v.var_id()));
                                     }
                                     _ => {}
                                 }
                             }
                             None
-                        }) {
+                        });
+                        if let Some((var_span, var_id)) = captured {
                             return ClosureUse {
                                 args_span,
                                 var_span,
+                                closure_def_id: def_id,
+                                var_id,
                             };
                         }
                     }
@@ -1070,15 +1089,17 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                                                  {:?}",
                                                 l
                                             );
-                                            return Some(v.span);
+                                            return Some((v.span, v.var_id()));
                                         }
                                         _ => {}
                                     }
                                 }
                                 None
-                            }).map(|var_span| ClosureUse {
+                            }).map(|(var_span, var_id)| ClosureUse {
                                 args_span,
                                 var_span,
+                                closure_def_id: def_id,
+                                var_id,
                             }).unwrap_or(OtherUse(use_span))
                     } else {
                         OtherUse(use_span)
@@ -1094,6 +1115,74 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         OtherUse(use_span)
     }
 
+    /// Closures capture whole variables, even if their body only uses a field
+    /// of one. When the first use of the captured variable in `spans` is such a
+    /// field access, point at the full path that is used and say how the
+    /// variable is captured, so that it is clear where the conflict comes from.
+    pub(super) fn note_closure_capture(&self, spans: UseSpans, err: &mut DiagnosticBuilder) {
+        let (var_span, closure_def_id, var_id) = match spans {
+            UseSpans::ClosureUse { var_span, closure_def_id, var_id, .. } => {
+                (var_span, closure_def_id, var_id)
+            }
+            UseSpans::OtherUse(_) => return,
+        };
+        let body_id = match self.tcx.hir.as_local_node_id(closure_def_id) {
+            Some(node_id) => match self.tcx.hir.expect_expr(node_id).node {
+                hir::ExprKind::Closure(_, _, body_id, _, _) => body_id,
+                _ => return,
+            },
+            None => return,
+        };
+
+        let mut finder = CapturedVarFinder { var_span, expr_id: None };
+        finder.visit_body(self.tcx.hir.body(body_id));
+        let mut expr_id = match finder.expr_id {
+            Some(expr_id) => expr_id,
+            None => return,
+        };
+
+        // Walk up through the field projections applied to the variable.
+        let var_name = self.tcx.hir.name(var_id).to_string();
+        let mut path = var_name.clone();
+        let mut path_span = var_span;
+        while let Some(Node::Expr(parent)) =
+            self.tcx.hir.find(self.tcx.hir.get_parent_node(expr_id))
+        {
+            match parent.node {
+                hir::ExprKind::Field(ref base, ident) if base.id == expr_id => {
+                    path.push('.');
+                    path.push_str(&ident.as_str());
+                    path_span = parent.span;
+                    expr_id = parent.id;
+                }
+                _ => break,
+            }
+        }
+        if path_span == var_span {
+            return;
+        }
+
+        let upvar_id = ty::UpvarId {
+            var_id: self.tcx.hir.node_to_hir_id(var_id),
+            closure_expr_id: LocalDefId::from_def_id(closure_def_id),
+        };
+        let capture = match self.tcx.typeck_tables_of(closure_def_id).upvar_capture(upvar_id) {
+            ty::UpvarCapture::ByValue => "by value",
+            ty::UpvarCapture::ByRef(borrow) => match borrow.kind {
+                ty::BorrowKind::ImmBorrow => "by reference",
+                ty::BorrowKind::UniqueImmBorrow => "by unique reference",
+                ty::BorrowKind::MutBorrow => "by mutable reference",
+            },
+        };
+        err.span_note(
+            path_span,
+            &format!(
+                "the closure uses `{}`, but captures all of `{}` {}",
+                path, var_name, capture
+            ),
+        );
+    }
+
     /// Helper to retrieve span(s) of given borrow from the current MIR
     /// representation
     pub(super) fn retrieve_borrow_spans(&self, borrow: &BorrowData) -> UseSpans {
@@ -1101,3 +1190,29 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         self.borrow_spans(span, borrow.reserve_location)
     }
 }
+
+/// Finds the path expression of a captured variable at `var_span`, skipping
+/// nested closures.
+struct CapturedVarFinder {
+    var_span: Span,
+    expr_id: Option<ast::NodeId>,
+}
+
+impl<'v> Visitor<'v> for CapturedVarFinder {
+    fn nested_visit_map<'this>(&'this mut self) -> NestedVisitorMap<'this, 'v> {
+        NestedVisitorMap::None
+    }
+
+    fn visit_expr(&mut self, expr: &'v hir::Expr) {
+        if self.expr_id.is_some() {
+            return;
+        }
+        if let hir::ExprKind::Path(_) = expr.node {
+            if expr.span == self.var_span {
+                self.expr_id = Some(expr.id);
+                return;
+            }
+        }
+        intravisit::walk_expr(self, expr);
+    }
+}
@@ -153,7 +153,10 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
 
     let mut errors_buffer = Vec::new();
     let (move_data, move_errors): (MoveData<'tcx>, Option<Vec<(Place<'tcx>, MoveError<'tcx>)>>) =
-        match MoveData::gather_moves(mir, tcx) {
+        match tcx.sess.opts.debugging_opts.move_path_depth_limit.map_or_else(
+            || MoveData::gather_moves(mir, tcx),
+            |limit| MoveData::gather_moves_with_depth_limit(mir, tcx, limit),
+        ) {
             Ok(move_data) => (move_data, None),
             Err((move_data, move_errors)) => (move_data, Some(move_errors)),
         };
@@ -681,7 +684,11 @@ impl<'cx, 'gcx, 'tcx> DataflowResultsConsumer<'cx, 'tcx> for MirBorrowckCtxt<'cx
                     flow_state.with_outgoing_borrows(|borrows| {
                         for i in borrows {
                             let borrow = &borrow_set[i];
-                            self.check_for_local_borrow(borrow, span);
+                            self.check_for_local_borrow(
+                                ContextKind::Yield.new(loc),
+                                borrow,
+                                span,
+                            );
                         }
                     });
                 }
@@ -1529,17 +1536,25 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
 
     /// Reports an error if this is a borrow of local data.
     /// This is called for all Yield statements on movable generators
-    fn check_for_local_borrow(&mut self, borrow: &BorrowData<'tcx>, yield_span: Span) {
+    fn check_for_local_borrow(
+        &mut self,
+        context: Context,
+        borrow: &BorrowData<'tcx>,
+        yield_span: Span,
+    ) {
         debug!("check_for_local_borrow({:?})", borrow);
 
         if borrow_of_local_data(&borrow.borrowed_place) {
-            let err = self.tcx
+            let mut err = self.tcx
                 .cannot_borrow_across_generator_yield(
                     self.retrieve_borrow_spans(borrow).var_or_use(),
                     yield_span,
                     Origin::Mir,
                 );
 
+            // Point at the use after the yield that keeps the borrow alive.
+            self.explain_why_borrow_contains_point(context, borrow, None, &mut err);
+
             err.buffer(&mut self.errors_buffer);
         }
     }
@@ -9,10 +9,13 @@
 // except according to those terms.
 
 use core::unicode::property::Pattern_White_Space;
+use rustc::hir;
 use rustc::mir::*;
+use rustc::traits;
 use rustc::ty;
-use rustc_errors::DiagnosticBuilder;
+use rustc_errors::{Applicability, DiagnosticBuilder};
 use rustc_data_structures::indexed_vec::Idx;
+use syntax::attr;
 use syntax_pos::Span;
 
 use borrow_check::MirBorrowckCtxt;
@@ -322,10 +325,111 @@ impl<'a, 'gcx, 'tcx> MirBorrowckCtxt<'a, 'gcx, 'tcx> {
             )
         };
 
+        self.add_replace_suggestion(&error, &mut err, err_span);
         self.add_move_hints(error, &mut err, err_span);
         err.buffer(&mut self.errors_buffer);
     }
 
+    /// When a field is moved out of a `&mut` reference, suggest putting another value in its
+    /// place: `Option::take` for options, `mem::replace` with the default value for types
+    /// implementing `Default`, and `mem::replace` with a placeholder otherwise.
+    fn add_replace_suggestion(
+        &self,
+        error: &GroupedMoveError<'tcx>,
+        err: &mut DiagnosticBuilder<'a>,
+        span: Span,
+    ) {
+        let original_path = match *error {
+            // `let x = self.field;`, patterns are handled by `add_move_hints`
+            GroupedMoveError::MovesFromPlace {
+                ref original_path,
+                kind: IllegalMoveOriginKind::BorrowedContent { .. },
+                ref binds_to,
+                ..
+            } if binds_to.is_empty() => original_path,
+            GroupedMoveError::OtherIllegalMove {
+                ref original_path,
+                kind: IllegalMoveOriginKind::BorrowedContent { .. },
+                ..
+            } => original_path,
+            _ => return,
+        };
+
+        // Only look at fields of a place `*x` where `x` is a user variable of type `&mut T`.
+        let reference = match *original_path {
+            Place::Projection(box PlaceProjection {
+                base: Place::Projection(box PlaceProjection {
+                    base: Place::Local(local),
+                    elem: ProjectionElem::Deref,
+                }),
+                elem: ProjectionElem::Field(..),
+            }) => local,
+            _ => return,
+        };
+        let reference_decl = &self.mir.local_decls[reference];
+        match reference_decl.ty.sty {
+            ty::Ref(_, _, hir::MutMutable) if reference_decl.is_user_variable.is_some() => {}
+            _ => return,
+        }
+
+        let snippet = match self.tcx.sess.source_map().span_to_snippet(span) {
+            Ok(snippet) => snippet,
+            Err(_) => return,
+        };
+        let ty = original_path.ty(self.mir, self.tcx).to_ty(self.tcx);
+        let is_option = match ty.sty {
+            ty::Adt(def, _) => Some(def.did) == self.tcx.lang_items().option_enum(),
+            _ => false,
+        };
+        // `#![no_std]` crates can only name `mem::replace` through `core`, which need not be
+        // in scope where the move happens.
+        let (replace, applicability) =
+            if attr::contains_name(self.tcx.hir.krate_attrs(), "no_std") {
+                ("core::mem::replace", Applicability::MaybeIncorrect)
+            } else {
+                ("std::mem::replace", Applicability::MachineApplicable)
+            };
+
+        if is_option {
+            err.span_suggestion_with_applicability(
+                span,
+                "consider taking the value out of the `Option`",
+                format!("{}.take()", snippet),
+                Applicability::MachineApplicable,
+            );
+        } else if self.implements_default(ty, span) {
+            err.span_suggestion_with_applicability(
+                span,
+                "consider replacing the value with its default",
+                format!("{}(&mut {}, Default::default())", replace, snippet),
+                applicability,
+            );
+        } else {
+            err.span_suggestion_with_applicability(
+                span,
+                "consider swapping in another value",
+                format!("{}(&mut {}, /* value */)", replace, snippet),
+                Applicability::HasPlaceholders,
+            );
+        }
+    }
+
+    fn implements_default(&self, ty: ty::Ty<'tcx>, span: Span) -> bool {
+        let default_trait = match self.tcx.lang_items().default_trait() {
+            Some(def_id) => def_id,
+            None => return false,
+        };
+        let gcx = self.tcx.global_tcx();
+        let ty = match gcx.lift(&self.tcx.erase_regions(&ty)) {
+            Some(ty) => ty,
+            None => return false,
+        };
+        let param_env = self.param_env;
+        gcx.infer_ctxt().enter(|infcx| {
+            traits::type_known_to_meet_bound(&infcx, param_env, ty, default_trait, span)
+        })
+    }
+
     fn add_move_hints(
         &self,
         error: GroupedMoveError<'tcx>,
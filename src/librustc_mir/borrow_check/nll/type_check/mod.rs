@@ -1451,6 +1451,13 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
             }
 
             Rvalue::Repeat(operand, len) => if *len > 1 {
+                if let Operand::Constant(..) = operand {
+                    // Constants are re-evaluated for every element (RFC 2203), so they do not
+                    // need to be `Copy`. Type checking already made sure this is allowed.
+                    if tcx.features().const_in_array_repeat_expressions {
+                        return;
+                    }
+                }
                 let operand_ty = operand.ty(mir, tcx);
 
                 let trait_ref = ty::TraitRef {
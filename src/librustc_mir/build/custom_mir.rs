@@ -0,0 +1,596 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Building MIR from its textual form, so tests can construct precise MIR shapes.
+//!
+//! A function annotated with `#[rustc_custom_mir = "..."]` gets the MIR given in
+//! the attribute instead of the MIR lowered from its body. The accepted syntax is
+//! the subset of the `-Z dump-mir` output shown here:
+//!
+//! ```text
+//! fn foo(_1: i32, _2: (bool, u8)) -> i32 {   // optional, ignored
+//!     let mut _3: bool;                       // every non-argument local
+//!     bb0: {
+//!         StorageLive(_3);
+//!         _3 = Lt(_1, const 5i32);
+//!         (_2.1: u8) = const 7u8;
+//!         switchInt(move _3) -> [false: bb2, otherwise: bb1];
+//!     }
+//!     bb1: {
+//!         _0 = Add(_1, const 1i32);
+//!         goto -> bb2;
+//!     }
+//!     bb2: {
+//!         return;
+//!     }
+//! }
+//! ```
+//!
+//! The types of the return place and the arguments are taken from the function's
+//! signature. Supported types are `bool`, `char`, the integer types and tuples of
+//! those. Constants need a type suffix unless they are `true`, `false` or `()`.
+
+use rustc::mir::*;
+use rustc::hir::def_id::DefId;
+use rustc::ty::{self, Ty, TyCtxt, ParamEnv};
+use rustc::ty::layout::LayoutOf;
+use rustc::mir::interpret::truncate;
+use rustc_data_structures::indexed_vec::{IndexVec, Idx};
+use std::borrow::Cow;
+use syntax::ast;
+use syntax_pos::Span;
+
+/// Returns the MIR given in the `rustc_custom_mir` attribute of `def_id`, if any.
+pub fn custom_mir<'a, 'tcx>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    id: ast::NodeId,
+    def_id: DefId,
+) -> Option<Mir<'tcx>> {
+    let attr = tcx.get_attrs(def_id).iter()
+        .find(|attr| attr.check_name("rustc_custom_mir"))?
+        .clone();
+    let span = attr.span;
+    let source = match attr.value_str() {
+        Some(source) => source,
+        None => tcx.sess.span_fatal(span, "`#[rustc_custom_mir]` expects a string with MIR"),
+    };
+    match tcx.type_of(def_id).sty {
+        ty::FnDef(..) => {}
+        _ => tcx.sess.span_fatal(span, "`#[rustc_custom_mir]` can only be used on functions"),
+    }
+    let sig = tcx.fn_sig(def_id);
+    let sig = tcx.liberate_late_bound_regions(def_id, &sig);
+
+    let mut parser = Parser {
+        tcx,
+        span,
+        tokens: tokenize(&source.as_str()),
+        pos: 0,
+        max_local: 0,
+    };
+    match parser.parse_mir(id, sig.inputs(), sig.output()) {
+        Ok(mir) => Some(mir),
+        Err(msg) => tcx.sess.span_fatal(span, &format!("invalid custom MIR: {}", msg)),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    /// Identifiers, keywords and number literals (including their type suffix)
+    Word(String),
+    Punct(&'static str),
+}
+
+fn tokenize(source: &str) -> Vec<Token> {
+    const PUNCTS: &[&str] = &["->", "(", ")", "{", "}", "[", "]", ":", ";", ",", "=", ".", "-"];
+
+    let mut tokens = Vec::new();
+    let mut rest = source;
+    'outer: loop {
+        rest = rest.trim_left();
+        if rest.is_empty() {
+            break;
+        }
+        if rest.starts_with("//") {
+            rest = rest.find('\n').map_or("", |end| &rest[end..]);
+            continue;
+        }
+        for &punct in PUNCTS {
+            if rest.starts_with(punct) {
+                tokens.push(Token::Punct(punct));
+                rest = &rest[punct.len()..];
+                continue 'outer;
+            }
+        }
+        let end = rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+        // Unknown characters become one-character words, which the parser then rejects.
+        let end = if end == 0 { rest.chars().next().unwrap().len_utf8() } else { end };
+        tokens.push(Token::Word(rest[..end].to_string()));
+        rest = &rest[end..];
+    }
+    tokens
+}
+
+type PResult<T> = Result<T, String>;
+
+struct Parser<'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    span: Span,
+    tokens: Vec<Token>,
+    pos: usize,
+    /// The highest local mentioned in a statement or terminator
+    max_local: usize,
+}
+
+impl<'a, 'tcx> Parser<'a, 'tcx> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_word(&self) -> Option<&str> {
+        match self.peek() {
+            Some(Token::Word(word)) => Some(word),
+            _ => None,
+        }
+    }
+
+    fn eat(&mut self, punct: &str) -> bool {
+        match self.peek() {
+            Some(&Token::Punct(p)) if p == punct => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        if self.peek_word() == Some(word) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, punct: &str) -> PResult<()> {
+        if self.eat(punct) {
+            Ok(())
+        } else {
+            Err(format!("expected `{}`, found {}", punct, self.describe_next()))
+        }
+    }
+
+    fn word(&mut self) -> PResult<String> {
+        match self.peek().cloned() {
+            Some(Token::Word(word)) => {
+                self.pos += 1;
+                Ok(word)
+            }
+            _ => Err(format!("expected a word, found {}", self.describe_next())),
+        }
+    }
+
+    fn describe_next(&self) -> String {
+        match self.peek() {
+            Some(Token::Word(word)) => format!("`{}`", word),
+            Some(Token::Punct(punct)) => format!("`{}`", punct),
+            None => "end of input".to_string(),
+        }
+    }
+
+    fn parse_mir(
+        &mut self,
+        id: ast::NodeId,
+        inputs: &[Ty<'tcx>],
+        output: Ty<'tcx>,
+    ) -> PResult<Mir<'tcx>> {
+        // Skip the signature of a dumped function, we use the real one.
+        let has_header = self.eat_word("fn");
+        if has_header {
+            while !self.eat("{") {
+                if self.peek().is_none() {
+                    return Err("expected `{` after the function signature".to_string());
+                }
+                self.pos += 1;
+            }
+        }
+
+        let mut local_tys: Vec<Option<Ty<'tcx>>> = Some(output).into_iter()
+            .chain(inputs.iter().cloned())
+            .map(Some)
+            .collect();
+        let mut blocks: Vec<Option<BasicBlockData<'tcx>>> = Vec::new();
+
+        loop {
+            if self.peek().is_none() || (has_header && self.eat("}")) {
+                break;
+            }
+            if self.eat_word("let") {
+                self.eat_word("mut");
+                let local = self.parse_local()?;
+                self.expect(":")?;
+                let ty = self.parse_ty()?;
+                self.expect(";")?;
+                if local.index() <= inputs.len() {
+                    // The return place and arguments are typed by the signature.
+                    continue;
+                }
+                if local_tys.len() <= local.index() {
+                    local_tys.resize(local.index() + 1, None);
+                }
+                local_tys[local.index()] = Some(ty);
+            } else {
+                let block = self.parse_block_name()?;
+                self.expect(":")?;
+                let data = self.parse_block()?;
+                if blocks.len() <= block.index() {
+                    blocks.resize(block.index() + 1, None);
+                }
+                if blocks[block.index()].is_some() {
+                    return Err(format!("{:?} is defined twice", block));
+                }
+                blocks[block.index()] = Some(data);
+            }
+        }
+        if self.peek().is_some() {
+            return Err(format!("unexpected {} after the function body", self.describe_next()));
+        }
+
+        let span = self.span;
+        let local_decls = local_tys.into_iter().enumerate().map(|(i, ty)| match ty {
+            Some(ty) => Ok(LocalDecl::new_temp(ty, span)),
+            None => Err(format!("missing declaration of `_{}`", i)),
+        }).collect::<PResult<IndexVec<Local, _>>>()?;
+        if self.max_local >= local_decls.len() {
+            return Err(format!("missing declaration of `_{}`", self.max_local));
+        }
+        let mut basic_blocks = blocks.into_iter().enumerate().map(|(i, data)| {
+            data.ok_or_else(|| format!("missing definition of `bb{}`", i))
+        }).collect::<PResult<IndexVec<BasicBlock, _>>>()?;
+        self.fix_switch_tys(&mut basic_blocks, &local_decls)?;
+        if basic_blocks.is_empty() {
+            return Err("no basic blocks".to_string());
+        }
+        for data in basic_blocks.iter() {
+            for &target in data.terminator().successors() {
+                if target.index() >= basic_blocks.len() {
+                    return Err(format!("jump to undefined block {:?}", target));
+                }
+            }
+        }
+
+        let mut source_scopes = IndexVec::new();
+        source_scopes.push(SourceScopeData { span, parent_scope: None });
+        let mut source_scope_local_data = IndexVec::new();
+        source_scope_local_data.push(SourceScopeLocalData {
+            lint_root: id,
+            safety: Safety::Safe,
+        });
+
+        Ok(Mir::new(
+            basic_blocks,
+            source_scopes,
+            ClearCrossCrate::Set(source_scope_local_data),
+            IndexVec::new(),
+            None,
+            local_decls,
+            inputs.len(),
+            vec![],
+            span,
+        ))
+    }
+
+    /// Sets the `switch_ty` of every `switchInt` to the type of its operand.
+    fn fix_switch_tys(
+        &self,
+        basic_blocks: &mut IndexVec<BasicBlock, BasicBlockData<'tcx>>,
+        local_decls: &LocalDecls<'tcx>,
+    ) -> PResult<()> {
+        for data in basic_blocks.iter_mut() {
+            if let TerminatorKind::SwitchInt {
+                ref discr, ref mut switch_ty, ref values, ..
+            } = data.terminator_mut().kind {
+                let discr_ty = discr.ty(local_decls, self.tcx);
+                if !values.is_empty() && *switch_ty != discr_ty {
+                    return Err(format!("switch values of type `{}` on `{}`",
+                                       switch_ty, discr_ty));
+                }
+                *switch_ty = discr_ty;
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_block_name(&mut self) -> PResult<BasicBlock> {
+        let word = self.word()?;
+        if word.starts_with("bb") {
+            if let Ok(index) = word[2..].parse::<usize>() {
+                return Ok(BasicBlock::new(index));
+            }
+        }
+        Err(format!("expected a basic block, found `{}`", word))
+    }
+
+    fn parse_local(&mut self) -> PResult<Local> {
+        let word = self.word()?;
+        if word.starts_with('_') {
+            if let Ok(index) = word[1..].parse::<usize>() {
+                return Ok(Local::new(index));
+            }
+        }
+        Err(format!("expected a local, found `{}`", word))
+    }
+
+    fn parse_ty(&mut self) -> PResult<Ty<'tcx>> {
+        if self.eat("(") {
+            let mut tys = Vec::new();
+            while !self.eat(")") {
+                tys.push(self.parse_ty()?);
+                if !self.eat(",") {
+                    self.expect(")")?;
+                    break;
+                }
+            }
+            return Ok(self.tcx.intern_tup(&tys));
+        }
+        let word = self.word()?;
+        self.primitive_ty(&word).ok_or_else(|| format!("unsupported type `{}`", word))
+    }
+
+    fn primitive_ty(&self, name: &str) -> Option<Ty<'tcx>> {
+        let types = &self.tcx.types;
+        Some(match name {
+            "bool" => types.bool,
+            "char" => types.char,
+            "i8" => types.i8,
+            "i16" => types.i16,
+            "i32" => types.i32,
+            "i64" => types.i64,
+            "i128" => types.i128,
+            "isize" => types.isize,
+            "u8" => types.u8,
+            "u16" => types.u16,
+            "u32" => types.u32,
+            "u64" => types.u64,
+            "u128" => types.u128,
+            "usize" => types.usize,
+            _ => return None,
+        })
+    }
+
+    fn parse_block(&mut self) -> PResult<BasicBlockData<'tcx>> {
+        self.expect("{")?;
+        let mut data = BasicBlockData::new(None);
+        loop {
+            let source_info = self.source_info();
+            if let Some(kind) = self.parse_terminator()? {
+                data.terminator = Some(Terminator { source_info, kind });
+                self.expect("}")?;
+                return Ok(data);
+            }
+            let kind = self.parse_statement()?;
+            self.expect(";")?;
+            data.statements.push(Statement { source_info, kind });
+        }
+    }
+
+    fn source_info(&self) -> SourceInfo {
+        SourceInfo { span: self.span, scope: OUTERMOST_SOURCE_SCOPE }
+    }
+
+    fn parse_statement(&mut self) -> PResult<StatementKind<'tcx>> {
+        if self.eat_word("StorageLive") {
+            self.expect("(")?;
+            let local = self.parse_used_local()?;
+            self.expect(")")?;
+            return Ok(StatementKind::StorageLive(local));
+        }
+        if self.eat_word("StorageDead") {
+            self.expect("(")?;
+            let local = self.parse_used_local()?;
+            self.expect(")")?;
+            return Ok(StatementKind::StorageDead(local));
+        }
+        if self.eat_word("nop") {
+            return Ok(StatementKind::Nop);
+        }
+        let place = self.parse_place()?;
+        self.expect("=")?;
+        let rvalue = self.parse_rvalue()?;
+        Ok(StatementKind::Assign(place, rvalue))
+    }
+
+    fn parse_terminator(&mut self) -> PResult<Option<TerminatorKind<'tcx>>> {
+        let kind = match self.peek_word() {
+            Some("return") => TerminatorKind::Return,
+            Some("unreachable") => TerminatorKind::Unreachable,
+            Some("resume") => TerminatorKind::Resume,
+            Some("abort") => TerminatorKind::Abort,
+            Some("goto") => {
+                self.pos += 1;
+                self.expect("->")?;
+                let target = self.parse_block_name()?;
+                self.expect(";")?;
+                return Ok(Some(TerminatorKind::Goto { target }));
+            }
+            Some("switchInt") => {
+                self.pos += 1;
+                self.expect("(")?;
+                let discr = self.parse_operand()?;
+                self.expect(")")?;
+                self.expect("->")?;
+                self.expect("[")?;
+                // The real type is filled in by `fix_switch_tys` once all locals are known.
+                let mut switch_ty = self.tcx.types.err;
+                let mut values = Vec::new();
+                let mut targets = Vec::new();
+                loop {
+                    if self.eat_word("otherwise") {
+                        self.expect(":")?;
+                        targets.push(self.parse_block_name()?);
+                        self.expect("]")?;
+                        break;
+                    }
+                    let value = self.parse_literal()?;
+                    if values.is_empty() {
+                        switch_ty = value.ty;
+                    } else if value.ty != switch_ty {
+                        return Err(format!("switch values of types `{}` and `{}`",
+                                           switch_ty, value.ty));
+                    }
+                    values.push(value.unwrap_bits(self.tcx, ParamEnv::empty().and(value.ty)));
+                    self.expect(":")?;
+                    targets.push(self.parse_block_name()?);
+                    self.expect(",")?;
+                }
+                self.expect(";")?;
+                return Ok(Some(TerminatorKind::SwitchInt {
+                    discr,
+                    switch_ty,
+                    values: Cow::from(values),
+                    targets,
+                }));
+            }
+            _ => return Ok(None),
+        };
+        self.pos += 1;
+        self.expect(";")?;
+        Ok(Some(kind))
+    }
+
+    fn parse_used_local(&mut self) -> PResult<Local> {
+        let local = self.parse_local()?;
+        self.max_local = ::std::cmp::max(self.max_local, local.index());
+        Ok(local)
+    }
+
+    /// Parses `_N` or a field projection `(PLACE.N: TYPE)`.
+    fn parse_place(&mut self) -> PResult<Place<'tcx>> {
+        if self.eat("(") {
+            let base = self.parse_place()?;
+            self.expect(".")?;
+            let field = self.word()?.parse::<usize>()
+                .map_err(|_| "expected a field index".to_string())?;
+            self.expect(":")?;
+            let ty = self.parse_ty()?;
+            self.expect(")")?;
+            return Ok(base.field(Field::new(field), ty));
+        }
+        Ok(Place::Local(self.parse_used_local()?))
+    }
+
+    fn parse_rvalue(&mut self) -> PResult<Rvalue<'tcx>> {
+        if let Some(word) = self.peek_word().map(|word| word.to_string()) {
+            let (checked, name) = if word.starts_with("Checked") {
+                (true, &word["Checked".len()..])
+            } else {
+                (false, &word[..])
+            };
+            if let Some(op) = bin_op(name) {
+                self.pos += 1;
+                self.expect("(")?;
+                let left = self.parse_operand()?;
+                self.expect(",")?;
+                let right = self.parse_operand()?;
+                self.expect(")")?;
+                return Ok(if checked {
+                    Rvalue::CheckedBinaryOp(op, left, right)
+                } else {
+                    Rvalue::BinaryOp(op, left, right)
+                });
+            }
+            let un_op = match &word[..] {
+                "Neg" => Some(UnOp::Neg),
+                "Not" => Some(UnOp::Not),
+                _ => None,
+            };
+            if let Some(op) = un_op {
+                self.pos += 1;
+                self.expect("(")?;
+                let operand = self.parse_operand()?;
+                self.expect(")")?;
+                return Ok(Rvalue::UnaryOp(op, operand));
+            }
+        }
+        Ok(Rvalue::Use(self.parse_operand()?))
+    }
+
+    fn parse_operand(&mut self) -> PResult<Operand<'tcx>> {
+        if self.eat_word("const") {
+            let literal = self.parse_literal()?;
+            let constant = Constant {
+                span: self.span,
+                ty: literal.ty,
+                user_ty: None,
+                literal,
+            };
+            return Ok(Operand::Constant(box constant));
+        }
+        let is_move = self.eat_word("move");
+        let place = self.parse_place()?;
+        Ok(if is_move { Operand::Move(place) } else { Operand::Copy(place) })
+    }
+
+    fn parse_literal(&mut self) -> PResult<&'tcx ty::Const<'tcx>> {
+        let tcx = self.tcx;
+        if self.eat("(") {
+            self.expect(")")?;
+            return Ok(ty::Const::zero_sized(tcx, tcx.mk_nil()));
+        }
+        let negative = self.eat("-");
+        let word = self.word()?;
+        match &word[..] {
+            "true" if !negative => return Ok(ty::Const::from_bool(tcx, true)),
+            "false" if !negative => return Ok(ty::Const::from_bool(tcx, false)),
+            _ => {}
+        }
+        let digits = word.find(|c: char| !c.is_digit(10)).unwrap_or(word.len());
+        let (value, suffix) = word.split_at(digits);
+        let value = value.parse::<u128>()
+            .map_err(|_| format!("expected a literal, found `{}`", word))?;
+        let ty = match self.primitive_ty(suffix) {
+            Some(ty) if ty.is_integral() => ty,
+            _ => return Err(format!("literal `{}` needs an integer type suffix", word)),
+        };
+        if negative && !ty.is_signed() {
+            return Err(format!("negative literal `-{}` of unsigned type", word));
+        }
+        let size = tcx.layout_of(ParamEnv::reveal_all().and(ty))
+            .map_err(|err| err.to_string())?
+            .size;
+        let bits = if negative { (value as i128).wrapping_neg() as u128 } else { value };
+        Ok(ty::Const::from_bits(tcx, truncate(bits, size), ParamEnv::reveal_all().and(ty)))
+    }
+}
+
+fn bin_op(name: &str) -> Option<BinOp> {
+    Some(match name {
+        "Add" => BinOp::Add,
+        "Sub" => BinOp::Sub,
+        "Mul" => BinOp::Mul,
+        "Div" => BinOp::Div,
+        "Rem" => BinOp::Rem,
+        "BitXor" => BinOp::BitXor,
+        "BitAnd" => BinOp::BitAnd,
+        "BitOr" => BinOp::BitOr,
+        "Shl" => BinOp::Shl,
+        "Shr" => BinOp::Shr,
+        "Eq" => BinOp::Eq,
+        "Lt" => BinOp::Lt,
+        "Le" => BinOp::Le,
+        "Ne" => BinOp::Ne,
+        "Ge" => BinOp::Ge,
+        "Gt" => BinOp::Gt,
+        "Cmp" => BinOp::Cmp,
+        "Offset" => BinOp::Offset,
+        _ => return None,
+    })
+}
@@ -61,6 +61,14 @@ When processing the `let x`, we will add one drop to the scope for
 y`, we will add another drop (in fact, to a subscope, but let's ignore
 that for now); any later drops would also drop `y`.
 
+### Temporaries
+
+Temporaries are scheduled for dropping in the scope recorded as their
+`temp_lifetime` in the HAIR, which comes from the region scope tree. The
+rules deciding that scope, including those that differ between editions,
+are collected in `region::TemporaryScopeRules`, so nothing here has to
+change when they do.
+
 ### Early exit
 
 There are numerous "normal" ways to early exit a scope: `break`,
@@ -612,16 +620,6 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
         }
     }
 
-    // Schedule an abort block - this is used for some ABIs that cannot unwind
-    pub fn schedule_abort(&mut self) -> BasicBlock {
-        self.scopes[0].needs_cleanup = true;
-        let abortblk = self.cfg.start_new_cleanup_block();
-        let source_info = self.scopes[0].source_info(self.fn_span);
-        self.cfg.terminate(abortblk, source_info, TerminatorKind::Abort);
-        self.cached_resume_block = Some(abortblk);
-        abortblk
-    }
-
     pub fn schedule_drop_storage_and_value(
         &mut self,
         span: Span,
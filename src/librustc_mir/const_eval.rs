@@ -16,7 +16,7 @@ use std::error::Error;
 use rustc::hir::{self, def_id::DefId};
 use rustc::mir::interpret::ConstEvalErr;
 use rustc::mir;
-use rustc::ty::{self, TyCtxt, Instance, query::TyCtxtAt};
+use rustc::ty::{self, Ty, TyCtxt, Instance, query::TyCtxtAt};
 use rustc::ty::layout::{LayoutOf, TyLayout};
 use rustc::ty::subst::Subst;
 use rustc_data_structures::indexed_vec::{IndexVec, Idx};
@@ -26,11 +26,11 @@ use syntax::source_map::Span;
 
 use rustc::mir::interpret::{
     EvalResult, EvalError, EvalErrorKind, GlobalId,
-    Scalar, Allocation, ConstValue,
+    Scalar, Allocation, ConstValue, Pointer,
 };
 use interpret::{self,
     Place, PlaceTy, MemPlace, OpTy, Operand, Value,
-    EvalContext, StackPopCleanup, MemoryKind,
+    EvalContext, StackPopCleanup, MemoryKind, FnCall, UnwindPolicy,
 };
 
 pub fn mk_borrowck_eval_cx<'a, 'mir, 'tcx>(
@@ -52,6 +52,7 @@ pub fn mk_borrowck_eval_cx<'a, 'mir, 'tcx>(
         return_place: Place::null(tcx),
         return_to_block: StackPopCleanup::Goto(None), // never pop
         stmt: 0,
+        extra: (),
     });
     Ok(ecx)
 }
@@ -192,26 +193,40 @@ pub struct CompileTimeEvaluator;
 
 impl<'tcx> Into<EvalError<'tcx>> for ConstEvalError {
     fn into(self) -> EvalError<'tcx> {
-        EvalErrorKind::MachineError(self.to_string()).into()
+        match self {
+            ConstEvalError::Unsupported { construct, help } => EvalErrorKind::UnsupportedInConst {
+                construct,
+                help: help.map(|help| help.to_string()),
+            }.into(),
+            ConstEvalError::NotConst(msg) => EvalErrorKind::MachineError(msg).into(),
+        }
     }
 }
 
 #[derive(Clone, Debug)]
 enum ConstEvalError {
-    NeedsRfc(String),
+    /// An operation that is not supported during const evaluation. `help` tells the user
+    /// how to get around it, if there is a way.
+    Unsupported { construct: String, help: Option<&'static str> },
     NotConst(String),
 }
 
+impl ConstEvalError {
+    fn unsupported_ptr_op() -> Self {
+        ConstEvalError::Unsupported {
+            construct: "pointer arithmetic or comparison".to_string(),
+            help: Some("the addresses of allocations are not known at compile time, so \
+                        pointers into them cannot be compared, offset or used as integers"),
+        }
+    }
+}
+
 impl fmt::Display for ConstEvalError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use self::ConstEvalError::*;
         match *self {
-            NeedsRfc(ref msg) => {
-                write!(
-                    f,
-                    "\"{}\" needs an rfc before being allowed inside constants",
-                    msg
-                )
+            Unsupported { ref construct, .. } => {
+                write!(f, "{} is not supported in constants", construct)
             }
             NotConst(ref msg) => write!(f, "{}", msg),
         }
@@ -222,7 +237,7 @@ impl Error for ConstEvalError {
     fn description(&self) -> &str {
         use self::ConstEvalError::*;
         match *self {
-            NeedsRfc(_) => "this feature needs an rfc before being allowed inside constants",
+            Unsupported { .. } => "this operation is not supported during constant evaluation",
             NotConst(_) => "this feature is not compatible with constant evaluation",
         }
     }
@@ -235,57 +250,88 @@ impl Error for ConstEvalError {
 impl<'mir, 'tcx> interpret::Machine<'mir, 'tcx> for CompileTimeEvaluator {
     type MemoryData = ();
     type MemoryKinds = !;
+    type FrameExtra = ();
 
     const MUT_STATIC_KIND: Option<!> = None; // no mutating of statics allowed
 
+    const SINGLE_THREADED_ATOMICS: bool = true; // there are no other threads to race with
+
     fn find_fn<'a>(
         ecx: &mut EvalContext<'a, 'mir, 'tcx, Self>,
         instance: ty::Instance<'tcx>,
         args: &[OpTy<'tcx>],
         dest: Option<PlaceTy<'tcx>>,
-        ret: Option<mir::BasicBlock>,
-    ) -> EvalResult<'tcx, Option<&'mir mir::Mir<'tcx>>> {
+        _ret: Option<mir::BasicBlock>,
+    ) -> EvalResult<'tcx, FnCall<'mir, 'tcx>> {
         debug!("eval_fn_call: {:?}", instance);
-        if !ecx.tcx.is_const_fn(instance.def_id()) {
+        let is_const = match instance.def {
+            // These shims only forward to the closure or function they wrap, which is
+            // checked when they call it.
+            ty::InstanceDef::ClosureOnceShim { .. } |
+            ty::InstanceDef::FnPtrShim(..) => true,
+            // Drop glue only runs user code through `Drop` impls, which `before_drop` rejects.
+            ty::InstanceDef::DropGlue(..) => true,
+            // Closures are not `const fn`, but any closure we encounter was created during
+            // const evaluation, and everything it does is checked while running it.
+            _ => ecx.tcx.is_const_fn(instance.def_id()) || ecx.tcx.is_closure(instance.def_id()),
+        };
+        if !is_const {
             // Some functions we support even if they are non-const -- but avoid testing
             // that for const fn!
             if ecx.hook_fn(instance, args, dest)? {
-                ecx.goto_block(ret)?; // fully evaluated and done
-                return Ok(None);
+                return Ok(FnCall::Emulated); // fully evaluated and done
+            }
+            // With the const checks skipped, run whatever MIR there is to see what breaks
+            if !ecx.tcx.sess.opts.debugging_opts.unleash_the_miri_inside_of_you {
+                return Err(
+                    ConstEvalError::NotConst(format!("calling non-const fn `{}`", instance)).into(),
+                );
             }
-            return Err(
-                ConstEvalError::NotConst(format!("calling non-const fn `{}`", instance)).into(),
-            );
         }
-        // This is a const fn. Call it.
-        Ok(Some(match ecx.load_mir(instance.def) {
+        // This is a const fn (or we were told to pretend it is). Call it.
+        Ok(FnCall::Mir(match ecx.load_mir(instance.def) {
             Ok(mir) => mir,
             Err(err) => {
                 if let EvalErrorKind::NoMirFor(ref path) = err.kind {
-                    return Err(
-                        ConstEvalError::NeedsRfc(format!("calling extern function `{}`", path))
-                            .into(),
-                    );
+                    return Err(ConstEvalError::Unsupported {
+                        construct: format!("calling extern function `{}`", path),
+                        help: Some("foreign functions cannot be evaluated at compile time, \
+                                    call them at runtime instead"),
+                    }.into());
                 }
                 return Err(err);
             }
         }))
     }
 
+    fn before_drop<'a>(
+        _ecx: &EvalContext<'a, 'mir, 'tcx, Self>,
+        ty: Ty<'tcx>,
+    ) -> EvalResult<'tcx> {
+        Err(ConstEvalError::Unsupported {
+            construct: format!("running the destructor of `{}`", ty),
+            help: Some("destructors cannot be evaluated at compile time"),
+        }.into())
+    }
+
     fn call_intrinsic<'a>(
         ecx: &mut EvalContext<'a, 'mir, 'tcx, Self>,
         instance: ty::Instance<'tcx>,
         args: &[OpTy<'tcx>],
         dest: PlaceTy<'tcx>,
-    ) -> EvalResult<'tcx> {
+    ) -> EvalResult<'tcx, bool> {
         if ecx.emulate_intrinsic(instance, args, dest)? {
-            return Ok(());
+            return Ok(true);
+        }
+        // An intrinsic that we do not support natively, use its fallback body if it has one
+        if ecx.intrinsic_fallback(instance).is_some() {
+            return Ok(false);
         }
-        // An intrinsic that we do not support
         let intrinsic_name = &ecx.tcx.item_name(instance.def_id()).as_str()[..];
-        Err(
-            ConstEvalError::NeedsRfc(format!("calling intrinsic `{}`", intrinsic_name)).into()
-        )
+        Err(ConstEvalError::Unsupported {
+            construct: format!("calling intrinsic `{}`", intrinsic_name),
+            help: None,
+        }.into())
     }
 
     fn try_ptr_op<'a>(
@@ -299,9 +345,50 @@ impl<'mir, 'tcx> interpret::Machine<'mir, 'tcx> for CompileTimeEvaluator {
         if left.is_bits() && right.is_bits() {
             Ok(None)
         } else {
-            Err(
-                ConstEvalError::NeedsRfc("pointer arithmetic or comparison".to_string()).into(),
-            )
+            Err(ConstEvalError::unsupported_ptr_op().into())
+        }
+    }
+
+    fn unwind_policy<'a>(ecx: &EvalContext<'a, 'mir, 'tcx, Self>) -> UnwindPolicy {
+        if ecx.tcx.sess.opts.debugging_opts.unwind_in_const_eval {
+            UnwindPolicy::Unwind
+        } else {
+            UnwindPolicy::Abort
+        }
+    }
+
+    fn enforce_validity<'a>(ecx: &EvalContext<'a, 'mir, 'tcx, Self>) -> bool {
+        ecx.tcx.sess.opts.debugging_opts.validate_const_eval_writes
+    }
+
+    fn step_limit<'a>(ecx: &EvalContext<'a, 'mir, 'tcx, Self>) -> Option<u64> {
+        match *ecx.tcx.sess.const_eval_limit.get() {
+            0 => None,
+            limit => Some(limit as u64),
+        }
+    }
+
+    fn ptr_eq_cross_alloc<'a>(
+        _ecx: &EvalContext<'a, 'mir, 'tcx, Self>,
+        _left: Pointer,
+        _right: Pointer,
+    ) -> EvalResult<'tcx, bool> {
+        // A pointer one past the end of an allocation may well be equal to a pointer
+        // to the start of another one, we cannot tell.
+        Err(ConstEvalError::unsupported_ptr_op().into())
+    }
+
+    fn ptr_int_eq<'a>(
+        ecx: &EvalContext<'a, 'mir, 'tcx, Self>,
+        ptr: Pointer,
+        int: u128,
+    ) -> EvalResult<'tcx, Option<bool>> {
+        // No allocation is ever placed at address zero, so null checks have a definite answer.
+        // Comparisons with any other integer are left to `try_ptr_op`, which rejects them.
+        if int == 0 {
+            ecx.ptr_int_eq_known(ptr, int)
+        } else {
+            Ok(None)
         }
     }
 
@@ -316,9 +403,26 @@ impl<'mir, 'tcx> interpret::Machine<'mir, 'tcx> for CompileTimeEvaluator {
         _ecx: &mut EvalContext<'a, 'mir, 'tcx, Self>,
         _dest: PlaceTy<'tcx>,
     ) -> EvalResult<'tcx> {
-        Err(
-            ConstEvalError::NeedsRfc("heap allocations via `box` keyword".to_string()).into(),
-        )
+        Err(ConstEvalError::Unsupported {
+            construct: "heap allocation via the `box` keyword".to_string(),
+            help: Some("use a reference to a `static` or a constant array instead of \
+                        allocating on the heap"),
+        }.into())
+    }
+
+    #[inline]
+    fn stack_push<'a>(
+        _ecx: &mut EvalContext<'a, 'mir, 'tcx, Self>,
+    ) -> EvalResult<'tcx> {
+        Ok(())
+    }
+
+    #[inline]
+    fn stack_pop<'a>(
+        _ecx: &mut EvalContext<'a, 'mir, 'tcx, Self>,
+        _extra: (),
+    ) -> EvalResult<'tcx> {
+        Ok(())
     }
 }
 
@@ -385,6 +489,32 @@ pub fn const_to_allocation_provider<'a, 'tcx>(
     }
 }
 
+/// Reports that the final value of the constant or static `def_id` is not valid at its type.
+fn report_invalid_value<'a, 'tcx>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    def_id: DefId,
+    error: EvalError<'tcx>,
+) {
+    let what = if tcx.is_static(def_id).is_some() { "static" } else { "constant" };
+    let span = tcx.def_span(def_id);
+    let err = ConstEvalErr {
+        error,
+        stacktrace: vec![],
+        span,
+    };
+    let err = err.struct_error(
+        tcx.at(span),
+        &format!("this {} likely exhibits undefined behavior", what),
+    );
+    if let Some(mut err) = err {
+        err.note("The rules on what exactly is undefined behavior aren't clear, \
+            so this check might be overzealous. Please open an issue on the rust compiler \
+            repository if you believe it should not be considered undefined behavior",
+        );
+        err.emit();
+    }
+}
+
 pub fn const_eval_provider<'a, 'tcx>(
     tcx: TyCtxt<'a, 'tcx, 'tcx>,
     key: ty::ParamEnvAnd<'tcx, GlobalId<'tcx>>,
@@ -430,6 +560,13 @@ pub fn const_eval_provider<'a, 'tcx>(
                 Operand::Immediate(_) => bug!("const eval gave us an Immediate"),
             }
         }
+        // Unions and `transmute` can produce any bit pattern, make sure that nothing
+        // invalid escapes into patterns, statics or other constants.
+        if let Err(err) = ecx.validate_operand_deep(op) {
+            report_invalid_value(tcx, def_id, err);
+            // Users of the value only need to know that it is broken
+            return err!(TypeckError);
+        }
         op_to_const(&ecx, op, normalize)
     }).map_err(|err| {
         let (trace, span) = ecx.generate_stacktrace(None);
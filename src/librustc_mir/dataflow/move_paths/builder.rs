@@ -28,10 +28,15 @@ struct MoveDataBuilder<'a, 'gcx: 'tcx, 'tcx: 'a> {
     tcx: TyCtxt<'a, 'gcx, 'tcx>,
     data: MoveData<'tcx>,
     errors: Vec<(Place<'tcx>, MoveError<'tcx>)>,
+    depth_limit: Option<usize>,
 }
 
 impl<'a, 'gcx, 'tcx> MoveDataBuilder<'a, 'gcx, 'tcx> {
-    fn new(mir: &'a Mir<'tcx>, tcx: TyCtxt<'a, 'gcx, 'tcx>) -> Self {
+    fn new(
+        mir: &'a Mir<'tcx>,
+        tcx: TyCtxt<'a, 'gcx, 'tcx>,
+        depth_limit: Option<usize>,
+    ) -> Self {
         let mut move_paths = IndexVec::new();
         let mut path_map = IndexVec::new();
         let mut init_path_map = IndexVec::new();
@@ -40,6 +45,7 @@ impl<'a, 'gcx, 'tcx> MoveDataBuilder<'a, 'gcx, 'tcx> {
             mir,
             tcx,
             errors: Vec::new(),
+            depth_limit,
             data: MoveData {
                 moves: IndexVec::new(),
                 loc_map: LocationMap::new(mir),
@@ -168,6 +174,15 @@ impl<'b, 'a, 'gcx, 'tcx> Gatherer<'b, 'a, 'gcx, 'tcx> {
             },
             _ => {}
         };
+        if let Some(limit) = self.builder.depth_limit {
+            // Too deep to track precisely: use the ancestor at the limit instead.
+            // Moving out of `place` then counts as moving out of the whole ancestor,
+            // which is conservative, and initializing `place` is not tracked at all
+            // since there is no exact move path for it.
+            if self.builder.data.move_paths[base].depth(&self.builder.data.move_paths) >= limit {
+                return Ok(base);
+            }
+        }
         match self.builder.data.rev_lookup.projections.entry((base, proj.elem.lift())) {
             Entry::Occupied(ent) => Ok(*ent.get()),
             Entry::Vacant(ent) => {
@@ -211,9 +226,10 @@ impl<'a, 'gcx, 'tcx> MoveDataBuilder<'a, 'gcx, 'tcx> {
 
 pub(super) fn gather_moves<'a, 'gcx, 'tcx>(
     mir: &Mir<'tcx>,
-    tcx: TyCtxt<'a, 'gcx, 'tcx>
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    depth_limit: Option<usize>,
 ) -> Result<MoveData<'tcx>, (MoveData<'tcx>, Vec<(Place<'tcx>, MoveError<'tcx>)>)> {
-    let mut builder = MoveDataBuilder::new(mir, tcx);
+    let mut builder = MoveDataBuilder::new(mir, tcx, depth_limit);
 
     builder.gather_args();
 
@@ -332,6 +348,7 @@ impl<'b, 'a, 'gcx, 'tcx> Gatherer<'b, 'a, 'gcx, 'tcx> {
             Rvalue::Discriminant(..) |
             Rvalue::Len(..) |
             Rvalue::NullaryOp(NullOp::SizeOf, _) |
+            Rvalue::NullaryOp(NullOp::AlignOf, _) |
             Rvalue::NullaryOp(NullOp::Box, _) => {
                 // This returns an rvalue with uninitialized contents. We can't
                 // move out of it here because it is an rvalue - assignments always
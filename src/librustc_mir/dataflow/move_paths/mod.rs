@@ -97,6 +97,19 @@ pub struct MovePath<'tcx> {
     pub place: Place<'tcx>,
 }
 
+impl<'tcx> MovePath<'tcx> {
+    /// The number of projections between this path and the local it is based on.
+    pub fn depth(&self, move_paths: &IndexVec<MovePathIndex, MovePath<'tcx>>) -> usize {
+        let mut depth = 0;
+        let mut parent = self.parent;
+        while let Some(p) = parent {
+            depth += 1;
+            parent = move_paths[p].parent;
+        }
+        depth
+    }
+}
+
 impl<'tcx> fmt::Debug for MovePath<'tcx> {
     fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
         write!(w, "MovePath {{")?;
@@ -314,6 +327,23 @@ impl<'tcx> MoveError<'tcx> {
 impl<'a, 'gcx, 'tcx> MoveData<'tcx> {
     pub fn gather_moves(mir: &Mir<'tcx>, tcx: TyCtxt<'a, 'gcx, 'tcx>)
                         -> Result<Self, (Self, Vec<(Place<'tcx>, MoveError<'tcx>)>)> {
-        builder::gather_moves(mir, tcx)
+        builder::gather_moves(mir, tcx, None)
+    }
+
+    /// Like `gather_moves`, but places with more than `depth_limit` projections
+    /// get no move path of their own: moves out of them are recorded as moves
+    /// out of their ancestor with `depth_limit` projections.
+    ///
+    /// This over-approximates moves: after moving out of a deep place, borrowck
+    /// considers its siblings moved as well and reports false "use of moved value"
+    /// errors for them. It only trades precision for speed on pathological code when
+    /// asked to with `-Z move-path-depth-limit`, and must never be used for drop
+    /// elaboration, which would then fail to drop the rest of the ancestor.
+    pub fn gather_moves_with_depth_limit(
+        mir: &Mir<'tcx>,
+        tcx: TyCtxt<'a, 'gcx, 'tcx>,
+        depth_limit: usize,
+    ) -> Result<Self, (Self, Vec<(Place<'tcx>, MoveError<'tcx>)>)> {
+        builder::gather_moves(mir, tcx, Some(depth_limit))
     }
 }
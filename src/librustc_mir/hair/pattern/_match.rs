@@ -170,7 +170,7 @@ use self::Constructor::*;
 use self::Usefulness::*;
 use self::WitnessPreference::*;
 
-use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::indexed_vec::Idx;
 
 use super::{FieldPattern, Pattern, PatternKind};
@@ -311,6 +311,9 @@ pub struct MatchCheckCtxt<'a, 'tcx: 'a> {
     pub module: DefId,
     pub pattern_arena: &'a TypedArena<Pattern<'tcx>>,
     pub byte_array_map: FxHashMap<*const Pattern<'tcx>, Vec<&'a Pattern<'tcx>>>,
+    /// The constructors of the enums seen so far. Computing them requires an
+    /// inhabitedness check per variant, which adds up for big enums.
+    enum_ctors_cache: FxHashMap<Ty<'tcx>, Vec<Constructor<'tcx>>>,
 }
 
 impl<'a, 'tcx> MatchCheckCtxt<'a, 'tcx> {
@@ -327,6 +330,7 @@ impl<'a, 'tcx> MatchCheckCtxt<'a, 'tcx> {
             module,
             pattern_arena: &pattern_arena,
             byte_array_map: FxHashMap::default(),
+            enum_ctors_cache: FxHashMap::default(),
         })
     }
 
@@ -406,7 +410,7 @@ impl<'a, 'tcx> MatchCheckCtxt<'a, 'tcx> {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Constructor<'tcx> {
     /// The constructor of all patterns that don't vary by constructor,
     /// e.g. struct patterns and fixed-length arrays.
@@ -642,10 +646,15 @@ fn all_constructors<'a, 'tcx: 'a>(cx: &mut MatchCheckCtxt<'a, 'tcx>,
             }
         }
         ty::Adt(def, substs) if def.is_enum() => {
-            def.variants.iter()
+            if let Some(ctors) = cx.enum_ctors_cache.get(&pcx.ty) {
+                return ctors.clone();
+            }
+            let ctors: Vec<_> = def.variants.iter()
                 .filter(|v| !cx.is_variant_uninhabited(v, substs))
                 .map(|v| Variant(v.did))
-                .collect()
+                .collect();
+            cx.enum_ctors_cache.insert(pcx.ty, ctors.clone());
+            ctors
         }
         ty::Char if exhaustive_integer_patterns => {
             let endpoint = |c: char| {
@@ -933,28 +942,61 @@ impl<'tcx> IntRange<'tcx> {
     }
 }
 
-// Return a set of constructors equivalent to `all_ctors \ used_ctors`.
+// A request for missing constructor data in terms of either:
+// - whether or not there any missing constructors; or
+// - the actual set of missing constructors.
+#[derive(PartialEq)]
+enum MissingCtorsInfo {
+    Emptiness,
+    Ctors,
+}
+
+// Used by `compute_missing_ctors`.
+#[derive(Debug, PartialEq)]
+enum MissingCtors<'tcx> {
+    Empty,
+    NonEmpty,
+
+    // Note that the Vec can be empty.
+    Ctors(Vec<Constructor<'tcx>>),
+}
+
+// When `info` is `MissingCtorsInfo::Ctors`, compute a set of constructors
+// equivalent to `all_ctors \ used_ctors`. When `info` is
+// `MissingCtorsInfo::Emptiness`, just determines if that set is empty or not.
+// (The split logic gives a performance win, because we always need to know if
+// the set is empty, but we rarely need the full set, and it can be expensive
+// to compute the full set.)
+//
+// `used_ctors` is expected to be free of duplicates.
 fn compute_missing_ctors<'a, 'tcx: 'a>(
+    info: MissingCtorsInfo,
     tcx: TyCtxt<'a, 'tcx, 'tcx>,
     all_ctors: &Vec<Constructor<'tcx>>,
     used_ctors: &Vec<Constructor<'tcx>>,
-) -> Vec<Constructor<'tcx>> {
+) -> MissingCtors<'tcx> {
     let mut missing_ctors = vec![];
 
+    // If a constructor appears in a `match` arm, we can eliminate it straight
+    // away, so look those up in a set rather than scanning all the arms for
+    // each constructor of the type.
+    let used_set: FxHashSet<&Constructor<'tcx>> = used_ctors.iter().collect();
+    let used_ranges: Vec<IntRange<'tcx>> = if tcx.features().exhaustive_integer_patterns {
+        used_ctors.iter().filter_map(|ctor| IntRange::from_ctor(tcx, ctor)).collect()
+    } else {
+        vec![]
+    };
+
     for req_ctor in all_ctors {
+        if used_set.contains(req_ctor) {
+            continue;
+        }
+
         let mut refined_ctors = vec![req_ctor.clone()];
-        for used_ctor in used_ctors {
-            if used_ctor == req_ctor {
-                // If a constructor appears in a `match` arm, we can
-                // eliminate it straight away.
-                refined_ctors = vec![]
-            } else if tcx.features().exhaustive_integer_patterns {
-                if let Some(interval) = IntRange::from_ctor(tcx, used_ctor) {
-                    // Refine the required constructors for the type by subtracting
-                    // the range defined by the current constructor pattern.
-                    refined_ctors = interval.subtract_from(tcx, refined_ctors);
-                }
-            }
+        for interval in &used_ranges {
+            // Refine the required constructors for the type by subtracting
+            // the range defined by the current constructor pattern.
+            refined_ctors = interval.clone().subtract_from(tcx, refined_ctors);
 
             // If the constructor patterns that have been considered so far
             // already cover the entire range of values, then we the
@@ -967,10 +1009,22 @@ fn compute_missing_ctors<'a, 'tcx: 'a>(
         // We add `refined_ctors` instead of `req_ctor`, because then we can
         // provide more detailed error information about precisely which
         // ranges have been omitted.
-        missing_ctors.extend(refined_ctors);
+        if info == MissingCtorsInfo::Emptiness {
+            if !refined_ctors.is_empty() {
+                // The set is non-empty; return early.
+                return MissingCtors::NonEmpty;
+            }
+        } else {
+            missing_ctors.extend(refined_ctors);
+        }
     }
 
-    missing_ctors
+    if info == MissingCtorsInfo::Emptiness {
+        // If we reached here, the set is empty.
+        MissingCtors::Empty
+    } else {
+        MissingCtors::Ctors(missing_ctors)
+    }
 }
 
 /// Algorithm from http://moscova.inria.fr/~maranget/papers/warn/index.html
@@ -1055,9 +1109,18 @@ pub fn is_useful<'p, 'a: 'p, 'tcx: 'a>(cx: &mut MatchCheckCtxt<'a, 'tcx>,
     } else {
         debug!("is_useful - expanding wildcard");
 
-        let used_ctors: Vec<Constructor> = rows.iter().flat_map(|row| {
-            pat_constructors(cx, row[0], pcx).unwrap_or(vec![])
-        }).collect();
+        // Many rows usually share a constructor (e.g. `(A, _)` and `(A, B)`),
+        // so deduplicate them here instead of repeatedly in the computations
+        // below.
+        let mut used_ctors: Vec<Constructor> = vec![];
+        let mut seen_ctors = FxHashSet::default();
+        for row in rows {
+            for ctor in pat_constructors(cx, row[0], pcx).unwrap_or(vec![]) {
+                if seen_ctors.insert(ctor.clone()) {
+                    used_ctors.push(ctor);
+                }
+            }
+        }
         debug!("used_ctors = {:#?}", used_ctors);
         // `all_ctors` are all the constructors for the given type, which
         // should all be represented (or caught with the wild pattern `_`).
@@ -1084,8 +1147,10 @@ pub fn is_useful<'p, 'a: 'p, 'tcx: 'a>(cx: &mut MatchCheckCtxt<'a, 'tcx>,
         // needed for that case.
 
         // Find those constructors that are not matched by any non-wildcard patterns in the
-        // current column.
-        let missing_ctors = compute_missing_ctors(cx.tcx, &all_ctors, &used_ctors);
+        // current column. We only fully construct them on-demand, because they're rarely used
+        // and can be big.
+        let missing_ctors = compute_missing_ctors(
+            MissingCtorsInfo::Emptiness, cx.tcx, &all_ctors, &used_ctors);
 
         let is_privately_empty = all_ctors.is_empty() && !cx.is_uninhabited(pcx.ty);
         let is_declared_nonexhaustive = cx.is_non_exhaustive_enum(pcx.ty) && !cx.is_local(pcx.ty);
@@ -1096,7 +1161,7 @@ pub fn is_useful<'p, 'a: 'p, 'tcx: 'a>(cx: &mut MatchCheckCtxt<'a, 'tcx>,
         // `_` constructor for the type, so we can never match over all constructors.
         let is_non_exhaustive = is_privately_empty || is_declared_nonexhaustive;
 
-        if missing_ctors.is_empty() && !is_non_exhaustive {
+        if missing_ctors == MissingCtors::Empty && !is_non_exhaustive {
             split_grouped_constructors(cx.tcx, all_ctors, matrix, pcx.ty).into_iter().map(|c| {
                 is_useful_specialized(cx, matrix, v, c.clone(), pcx.ty, witness)
             }).find(|result| result.is_useful()).unwrap_or(NotUseful)
@@ -1167,6 +1232,12 @@ pub fn is_useful<'p, 'a: 'p, 'tcx: 'a>(cx: &mut MatchCheckCtxt<'a, 'tcx>,
                             witness
                         }).collect()
                     } else {
+                        let missing_ctors = match compute_missing_ctors(
+                            MissingCtorsInfo::Ctors, cx.tcx, &all_ctors, &used_ctors
+                        ) {
+                            MissingCtors::Ctors(ctors) => ctors,
+                            _ => bug!("missing constructors requested but not computed"),
+                        };
                         pats.into_iter().flat_map(|witness| {
                             missing_ctors.iter().map(move |ctor| {
                                 // Extends the witness with a "wild" version of this
@@ -1469,6 +1540,9 @@ fn split_grouped_constructors<'p, 'a: 'p, 'tcx: 'a>(
                 let ctor_borders = range_borders(ctor_range.clone());
                 let mut borders: Vec<_> = row_borders.chain(ctor_borders).collect();
                 borders.sort_unstable();
+                // Many rows can share a border, which would otherwise only produce
+                // empty intervals below.
+                borders.dedup();
 
                 // We're going to iterate through every pair of borders, making sure that each
                 // represents an interval of nonnegative length, and convert each such interval
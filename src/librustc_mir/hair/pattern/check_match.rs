@@ -496,11 +496,17 @@ fn check_exhaustive<'a, 'tcx>(cx: &mut MatchCheckCtxt<'a, 'tcx>,
                 1 => format!("pattern {} not covered", joined_patterns),
                 _ => format!("patterns {} not covered", joined_patterns)
             };
-            create_e0004(cx.tcx.sess, sp,
-                            format!("non-exhaustive patterns: {} not covered",
-                                    joined_patterns))
-                .span_label(sp, label_text)
-                .emit();
+            let mut err = create_e0004(cx.tcx.sess, sp,
+                                       format!("non-exhaustive patterns: {} not covered",
+                                               joined_patterns));
+            err.span_label(sp, label_text);
+            if witnesses.len() > LIMIT {
+                // Listing every missing pattern would be of little use, so
+                // point towards catching all of them at once instead.
+                err.help("consider adding a wildcard arm `_ => ...` to handle the remaining \
+                          patterns");
+            }
+            err.emit();
         }
         NotUseful => {
             // This is good, wildcard pattern isn't reachable
@@ -9,10 +9,12 @@
 // except according to those terms.
 
 use rustc::ty::{self, Ty, TypeAndMut};
-use rustc::ty::layout::{self, TyLayout, Size};
+use rustc::ty::layout::{self, TyLayout, Size, LayoutOf};
+use rustc::ty::util::IntTypeExt;
 use syntax::ast::{FloatTy, IntTy, UintTy};
 
 use rustc_apfloat::ieee::{Single, Double};
+use rustc_apfloat::{Round, Status};
 use rustc::mir::interpret::{
     Scalar, EvalResult, Pointer, PointerArithmetic, EvalErrorKind,
     truncate, sign_extend
@@ -77,7 +79,21 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                                     dest);
                             }
                         }
-                        layout::Variants::Tagged { .. } |
+                        layout::Variants::Tagged { .. } => {
+                            if let Some(def) = src_layout.ty.ty_adt_def() {
+                                // Go through the discriminant so that casting an enum with an
+                                // invalid tag is reported right here.
+                                let discr_val = self.read_discriminant(src.into())?.0;
+                                let discr_ty = def.repr.discr_type().to_ty(*self.tcx);
+                                let discr_layout = self.layout_of(discr_ty)?;
+                                let discr = Scalar::Bits {
+                                    bits: discr_val,
+                                    size: discr_layout.size.bytes() as u8,
+                                };
+                                let dest_val = self.cast_scalar(discr, discr_layout, dest.layout)?;
+                                return self.write_scalar(dest_val, dest);
+                            }
+                        }
                         layout::Variants::NicheFilling { .. } => {},
                     }
 
@@ -240,6 +256,34 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
         }
     }
 
+    /// Converts a float to the integer type `dest_ty` like `as` does, but reports NaN and
+    /// values that `dest_ty` cannot represent instead of saturating them. This is what
+    /// `float_to_int_unchecked` does, so its errors are reported for that intrinsic.
+    pub fn float_to_int_checked(
+        &self,
+        bits: u128,
+        fty: FloatTy,
+        dest_ty: Ty<'tcx>,
+    ) -> EvalResult<'tcx, Scalar> {
+        let pointer_bits = self.memory.pointer_size().bits() as usize;
+        let (width, signed) = match dest_ty.sty {
+            ty::Int(t) => (t.bit_width().unwrap_or(pointer_bits), true),
+            ty::Uint(t) => (t.bit_width().unwrap_or(pointer_bits), false),
+            _ => return err!(Intrinsic(format!(
+                "invalid monomorphization of `float_to_int_unchecked` intrinsic: \
+                 expected basic integer type, found `{}`", dest_ty,
+            ))),
+        };
+        let rendered = match fty {
+            FloatTy::F32 => float_out_of_range(Single::from_bits(bits), fty, width, signed),
+            FloatTy::F64 => float_out_of_range(Double::from_bits(bits), fty, width, signed),
+        };
+        if let Some(rendered) = rendered {
+            return err!(FloatToIntOutOfRange(rendered, dest_ty));
+        }
+        self.cast_from_float(bits, fty, dest_ty)
+    }
+
     fn cast_from_float(
         &self,
         bits: u128,
@@ -425,3 +469,25 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
         }
     }
 }
+
+/// If `f`, rounded towards zero, does not fit into an integer of the given width and
+/// signedness, returns `f` rendered like a Rust expression, e.g. `1e10f32` or `f64::NAN`.
+fn float_out_of_range<F: Float>(f: F, fty: FloatTy, width: usize, signed: bool) -> Option<String> {
+    let status = if signed {
+        f.to_i128_r(width, Round::TowardZero, &mut true).status
+    } else {
+        f.to_u128_r(width, Round::TowardZero, &mut true).status
+    };
+    if !status.contains(Status::INVALID_OP) {
+        return None;
+    }
+    Some(if f.is_nan() {
+        format!("{}::NAN", fty)
+    } else if f.is_infinite() && f.is_negative() {
+        format!("{}::NEG_INFINITY", fty)
+    } else if f.is_infinite() {
+        format!("{}::INFINITY", fty)
+    } else {
+        format!("{}{}", f, fty)
+    })
+}
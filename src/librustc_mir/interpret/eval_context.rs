@@ -9,11 +9,9 @@
 // except according to those terms.
 
 use std::fmt::Write;
-use std::hash::{Hash, Hasher};
 use std::mem;
 
 use rustc::hir::def_id::DefId;
-use rustc::hir::def::Def;
 use rustc::hir::map::definitions::DefPathData;
 use rustc::mir;
 use rustc::ty::layout::{
@@ -22,11 +20,11 @@ use rustc::ty::layout::{
 use rustc::ty::subst::{Subst, Substs};
 use rustc::ty::{self, Ty, TyCtxt, TypeFoldable};
 use rustc::ty::query::TyCtxtAt;
-use rustc_data_structures::fx::{FxHashSet, FxHasher};
+use rustc_data_structures::fx::FxHashMap;
 use rustc_data_structures::indexed_vec::IndexVec;
 use rustc::mir::interpret::{
-    GlobalId, Scalar, FrameInfo,
-    EvalResult, EvalErrorKind,
+    GlobalId, Scalar, FrameInfo, Pointer,
+    EvalError, EvalResult, EvalErrorKind,
     ScalarMaybeUndef,
     truncate, sign_extend,
 };
@@ -37,6 +35,7 @@ use super::{
     Value, Operand, MemPlace, MPlaceTy, Place,
     Memory, Machine
 };
+use super::snapshot::InfiniteLoopDetector;
 
 pub struct EvalContext<'a, 'mir, 'tcx: 'a + 'mir, M: Machine<'mir, 'tcx>> {
     /// Stores the `Machine` instance.
@@ -52,7 +51,7 @@ pub struct EvalContext<'a, 'mir, 'tcx: 'a + 'mir, M: Machine<'mir, 'tcx>> {
     pub memory: Memory<'a, 'mir, 'tcx, M>,
 
     /// The virtual call stack.
-    pub(crate) stack: Vec<Frame<'mir, 'tcx>>,
+    pub(crate) stack: Vec<Frame<'mir, 'tcx, M::FrameExtra>>,
 
     /// The maximum number of stack frames allowed
     pub(super) stack_limit: usize,
@@ -63,12 +62,24 @@ pub struct EvalContext<'a, 'mir, 'tcx: 'a + 'mir, M: Machine<'mir, 'tcx>> {
     /// detector period.
     pub(super) steps_since_detector_enabled: isize,
 
-    pub(super) loop_detector: InfiniteLoopDetector<'a, 'mir, 'tcx, M>,
+    pub(super) loop_detector: InfiniteLoopDetector<'mir, 'tcx, M>,
+
+    /// The number of terminators executed so far, checked against `Machine::step_limit`.
+    pub(super) steps_taken: u64,
+
+    /// The panic currently being unwound, if any, and the span it started at. It becomes the
+    /// result of the evaluation, reported at that span, if it propagates out of the outermost
+    /// frame.
+    pub(super) unwinding: Option<(EvalError<'tcx>, Span)>,
+
+    /// The vtables created so far, so that unsizing the same type to the same trait object
+    /// again reuses the existing vtable.
+    pub(super) vtables: FxHashMap<(Ty<'tcx>, ty::PolyTraitRef<'tcx>), Pointer>,
 }
 
 /// A stack frame.
 #[derive(Clone)]
-pub struct Frame<'mir, 'tcx: 'mir> {
+pub struct Frame<'mir, 'tcx: 'mir, Extra=()> {
     ////////////////////////////////////////////////////////////////////////////////
     // Function and callsite information
     ////////////////////////////////////////////////////////////////////////////////
@@ -106,53 +117,27 @@ pub struct Frame<'mir, 'tcx: 'mir> {
 
     /// The index of the currently evaluated statement.
     pub stmt: usize,
-}
 
-impl<'mir, 'tcx: 'mir> Eq for Frame<'mir, 'tcx> {}
+    ////////////////////////////////////////////////////////////////////////////////
+    // Extra machine state
+    ////////////////////////////////////////////////////////////////////////////////
+    /// Data the machine attached to this frame in `Machine::stack_push`.
+    pub extra: Extra,
+}
 
-impl<'mir, 'tcx: 'mir> PartialEq for Frame<'mir, 'tcx> {
-    fn eq(&self, other: &Self) -> bool {
-        let Frame {
-            mir: _,
-            instance,
-            span: _,
-            return_to_block,
-            return_place,
-            locals,
-            block,
-            stmt,
-        } = self;
-
-        // Some of these are constant during evaluation, but are included
-        // anyways for correctness.
-        *instance == other.instance
-            && *return_to_block == other.return_to_block
-            && *return_place == other.return_place
-            && *locals == other.locals
-            && *block == other.block
-            && *stmt == other.stmt
+impl<'mir, 'tcx: 'mir, Extra> Frame<'mir, 'tcx, Extra> {
+    /// The location of the statement or terminator this frame executes next, or is executing
+    /// if it is not the topmost frame (i.e., the call it is waiting on).
+    pub fn current_location(&self) -> mir::Location {
+        mir::Location {
+            block: self.block,
+            statement_index: self.stmt,
+        }
     }
-}
 
-impl<'mir, 'tcx: 'mir> Hash for Frame<'mir, 'tcx> {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        let Frame {
-            mir: _,
-            instance,
-            span: _,
-            return_to_block,
-            return_place,
-            locals,
-            block,
-            stmt,
-        } = self;
-
-        instance.hash(state);
-        return_to_block.hash(state);
-        return_place.hash(state);
-        locals.hash(state);
-        block.hash(state);
-        stmt.hash(state);
+    /// The source location of `current_location`.
+    pub fn current_source_info(&self) -> &'mir mir::SourceInfo {
+        self.mir.source_info(self.current_location())
     }
 }
 
@@ -195,73 +180,6 @@ impl<'tcx> LocalValue {
     }
 }
 
-/// The virtual machine state during const-evaluation at a given point in time.
-type EvalSnapshot<'a, 'mir, 'tcx, M>
-    = (M, Vec<Frame<'mir, 'tcx>>, Memory<'a, 'mir, 'tcx, M>);
-
-pub(super) struct InfiniteLoopDetector<'a, 'mir, 'tcx: 'a + 'mir, M: Machine<'mir, 'tcx>> {
-    /// The set of all `EvalSnapshot` *hashes* observed by this detector.
-    ///
-    /// When a collision occurs in this table, we store the full snapshot in
-    /// `snapshots`.
-    hashes: FxHashSet<u64>,
-
-    /// The set of all `EvalSnapshot`s observed by this detector.
-    ///
-    /// An `EvalSnapshot` will only be fully cloned once it has caused a
-    /// collision in `hashes`. As a result, the detector must observe at least
-    /// *two* full cycles of an infinite loop before it triggers.
-    snapshots: FxHashSet<EvalSnapshot<'a, 'mir, 'tcx, M>>,
-}
-
-impl<'a, 'mir, 'tcx, M> Default for InfiniteLoopDetector<'a, 'mir, 'tcx, M>
-    where M: Machine<'mir, 'tcx>,
-          'tcx: 'a + 'mir,
-{
-    fn default() -> Self {
-        InfiniteLoopDetector {
-            hashes: FxHashSet::default(),
-            snapshots: FxHashSet::default(),
-        }
-    }
-}
-
-impl<'a, 'mir, 'tcx, M> InfiniteLoopDetector<'a, 'mir, 'tcx, M>
-    where M: Machine<'mir, 'tcx>,
-          'tcx: 'a + 'mir,
-{
-    /// Returns `true` if the loop detector has not yet observed a snapshot.
-    pub fn is_empty(&self) -> bool {
-        self.hashes.is_empty()
-    }
-
-    pub fn observe_and_analyze(
-        &mut self,
-        machine: &M,
-        stack: &Vec<Frame<'mir, 'tcx>>,
-        memory: &Memory<'a, 'mir, 'tcx, M>,
-    ) -> EvalResult<'tcx, ()> {
-        let snapshot = (machine, stack, memory);
-
-        let mut fx = FxHasher::default();
-        snapshot.hash(&mut fx);
-        let hash = fx.finish();
-
-        if self.hashes.insert(hash) {
-            // No collision
-            return Ok(())
-        }
-
-        if self.snapshots.insert((machine.clone(), stack.clone(), memory.clone())) {
-            // Spurious collision or first cycle
-            return Ok(())
-        }
-
-        // Second cycle
-        Err(EvalErrorKind::InfiniteLoop.into())
-    }
-}
-
 impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> HasDataLayout for &'a EvalContext<'a, 'mir, 'tcx, M> {
     #[inline]
     fn data_layout(&self) -> &layout::TargetDataLayout {
@@ -334,6 +252,9 @@ impl<'a, 'mir, 'tcx: 'mir, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M
             stack_limit: tcx.sess.const_eval_stack_frame_limit,
             loop_detector: Default::default(),
             steps_since_detector_enabled: -STEPS_UNTIL_DETECTOR_ENABLED,
+            steps_taken: 0,
+            unwinding: None,
+            vtables: FxHashMap::default(),
         }
     }
 
@@ -355,7 +276,7 @@ impl<'a, 'mir, 'tcx: 'mir, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M
         &mut self.memory
     }
 
-    pub fn stack(&self) -> &[Frame<'mir, 'tcx>] {
+    pub fn stack(&self) -> &[Frame<'mir, 'tcx, M::FrameExtra>] {
         &self.stack
     }
 
@@ -470,7 +391,11 @@ impl<'a, 'mir, 'tcx: 'mir, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M
     ) -> EvalResult<'tcx, (Size, Align)> {
         let metadata = match metadata {
             None => {
-                assert!(!layout.is_unsized());
+                // Extern types, and structs ending in one, are unsized but have no metadata
+                // their size could be computed from.
+                if layout.is_unsized() {
+                    return err!(SizeOfUnsizedType(layout.ty));
+                }
                 return Ok(layout.size_and_align())
             }
             Some(metadata) => {
@@ -561,6 +486,8 @@ impl<'a, 'mir, 'tcx: 'mir, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M
     ) -> EvalResult<'tcx> {
         ::log_settings::settings().indentation += 1;
 
+        let extra = M::stack_push(self)?;
+
         // first push a stack frame so we have access to the local substs
         self.stack.push(Frame {
             mir,
@@ -573,6 +500,7 @@ impl<'a, 'mir, 'tcx: 'mir, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M
             span,
             instance,
             stmt: 0,
+            extra,
         });
 
         // don't allocate at all for trivial constants
@@ -583,25 +511,21 @@ impl<'a, 'mir, 'tcx: 'mir, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M
             let dummy =
                 LocalValue::Live(Operand::Immediate(Value::Scalar(ScalarMaybeUndef::Undef)));
             let mut locals = IndexVec::from_elem(dummy, &mir.local_decls);
-            // Now mark those locals as dead that we do not want to initialize
-            match self.tcx.describe_def(instance.def_id()) {
-                // statics and constants don't have `Storage*` statements, no need to look for them
-                Some(Def::Static(..)) | Some(Def::Const(..)) | Some(Def::AssociatedConst(..)) => {},
-                _ => {
-                    trace!("push_stack_frame: {:?}: num_bbs: {}", span, mir.basic_blocks().len());
-                    for block in mir.basic_blocks() {
-                        for stmt in block.statements.iter() {
-                            use rustc::mir::StatementKind::{StorageDead, StorageLive};
-                            match stmt.kind {
-                                StorageLive(local) |
-                                StorageDead(local) => {
-                                    locals[local] = LocalValue::Dead;
-                                }
-                                _ => {}
-                            }
+            // Now mark those locals as dead that we do not want to initialize: everything
+            // with `Storage*` statements only lives between `StorageLive` and `StorageDead`.
+            // This includes the `let` bindings of constants and statics.
+            trace!("push_stack_frame: {:?}: num_bbs: {}", span, mir.basic_blocks().len());
+            for block in mir.basic_blocks() {
+                for stmt in block.statements.iter() {
+                    use rustc::mir::StatementKind::{StorageDead, StorageLive};
+                    match stmt.kind {
+                        StorageLive(local) |
+                        StorageDead(local) => {
+                            locals[local] = LocalValue::Dead;
                         }
+                        _ => {}
                     }
-                },
+                }
             }
             // Finally, properly initialize all those that still have the dummy value
             for (local, decl) in locals.iter_mut().zip(mir.local_decls.iter()) {
@@ -628,13 +552,51 @@ impl<'a, 'mir, 'tcx: 'mir, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M
     }
 
     pub(super) fn pop_stack_frame(&mut self) -> EvalResult<'tcx> {
+        self.pop_stack_frame_inner(false)
+    }
+
+    /// Pops a frame while unwinding, i.e. without returning to the caller's return block.
+    /// If the caller is not interpreted, the panic being unwound is returned as an error.
+    pub(super) fn pop_stack_frame_unwinding(&mut self) -> EvalResult<'tcx> {
+        let escapes = match self.frame().return_to_block {
+            StackPopCleanup::Goto(_) => false,
+            StackPopCleanup::None { .. } => true,
+        };
+        self.pop_stack_frame_inner(true)?;
+        if escapes || self.stack.is_empty() {
+            let (payload, span) = self.unwinding.take().expect("unwinding without a panic");
+            self.tcx.span = span;
+            self.memory.tcx.span = span;
+            return Err(payload);
+        }
+        Ok(())
+    }
+
+    /// Pops a frame to make room for a tail call from it, i.e. without returning to the
+    /// caller. Returns where the frame would have returned to, for the frame of the callee.
+    pub(super) fn pop_stack_frame_for_tail_call(&mut self) -> EvalResult<'tcx, StackPopCleanup> {
         ::log_settings::settings().indentation -= 1;
         let frame = self.stack.pop().expect(
             "tried to pop a stack frame, but there were none",
         );
+        M::stack_pop(self, frame.extra)?;
+        for local in frame.locals {
+            self.deallocate_local(local)?;
+        }
+        Ok(frame.return_to_block)
+    }
+
+    fn pop_stack_frame_inner(&mut self, unwinding: bool) -> EvalResult<'tcx> {
+        ::log_settings::settings().indentation -= 1;
+        let frame = self.stack.pop().expect(
+            "tried to pop a stack frame, but there were none",
+        );
+        M::stack_pop(self, frame.extra)?;
         match frame.return_to_block {
             StackPopCleanup::Goto(block) => {
-                self.goto_block(block)?;
+                if !unwinding {
+                    self.goto_block(block)?;
+                }
             }
             StackPopCleanup::None { cleanup } => {
                 if !cleanup {
@@ -673,19 +635,26 @@ impl<'a, 'mir, 'tcx: 'mir, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M
     }
 
     #[inline(always)]
-    pub fn frame(&self) -> &Frame<'mir, 'tcx> {
+    pub fn frame(&self) -> &Frame<'mir, 'tcx, M::FrameExtra> {
         self.stack.last().expect("no call frames exist")
     }
 
     #[inline(always)]
-    pub fn frame_mut(&mut self) -> &mut Frame<'mir, 'tcx> {
+    pub fn frame_mut(&mut self) -> &mut Frame<'mir, 'tcx, M::FrameExtra> {
         self.stack.last_mut().expect("no call frames exist")
     }
 
-    pub(super) fn mir(&self) -> &'mir mir::Mir<'tcx> {
+    pub fn mir(&self) -> &'mir mir::Mir<'tcx> {
         self.frame().mir
     }
 
+    /// The location of the statement or terminator that `step` executes next, or `None` if
+    /// the stack is empty. The statement index equals the number of statements in the block
+    /// when the terminator is next.
+    pub fn current_location(&self) -> Option<mir::Location> {
+        self.stack.last().map(|frame| frame.current_location())
+    }
+
     pub fn substs(&self) -> &'tcx Substs<'tcx> {
         if let Some(frame) = self.stack.last() {
             frame.instance.substs
@@ -762,7 +731,8 @@ impl<'a, 'mir, 'tcx: 'mir, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M
         let mut last_span = None;
         let mut frames = Vec::new();
         // skip 1 because the last frame is just the environment of the constant
-        for &Frame { instance, span, mir, block, stmt, .. } in self.stack().iter().skip(1).rev() {
+        for frame in self.stack().iter().skip(1).rev() {
+            let Frame { instance, span, mir, .. } = *frame;
             // make sure we don't emit frames that are duplicates of the previous
             if explicit_span == Some(span) {
                 last_span = Some(span);
@@ -782,12 +752,7 @@ impl<'a, 'mir, 'tcx: 'mir, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M
             } else {
                 instance.to_string()
             };
-            let block = &mir.basic_blocks()[block];
-            let source_info = if stmt < block.statements.len() {
-                block.statements[stmt].source_info
-            } else {
-                block.terminator().source_info
-            };
+            let source_info = frame.current_source_info();
             let lint_root = match mir.source_scope_local_data {
                 mir::ClearCrossCrate::Set(ref ivs) => Some(ivs[source_info.scope].lint_root),
                 mir::ClearCrossCrate::Clear => None,
@@ -12,17 +12,50 @@
 //! looking at their MIR.  Intrinsics/functions supported here are shared by CTFE
 //! and miri.
 
+use std::cmp;
+
 use syntax::symbol::Symbol;
+use rustc::hir::def::Def;
+use rustc::mir;
 use rustc::ty;
-use rustc::ty::layout::{LayoutOf, Primitive};
+use rustc::ty::layout::{Align, Integer, IntegerExt, LayoutOf, Primitive, Size, TyLayout};
 use rustc::mir::interpret::{
-    EvalResult, EvalErrorKind, Scalar,
+    EvalResult, EvalErrorKind, Scalar, PointerArithmetic, sign_extend,
 };
+use rustc_apfloat::{Float, Round};
+use rustc_apfloat::ieee::{Double, Single};
 
 use super::{
-    Machine, PlaceTy, OpTy, EvalContext,
+    Machine, PlaceTy, OpTy, EvalContext, Value,
 };
 
+/// The smallest `n` such that `addr + n * stride` is a multiple of `align`, which must be
+/// a power of two, or `u128::max_value()` if there is none.
+fn align_offset_of_addr(addr: u64, stride: u64, align: u64) -> u128 {
+    let byte_offset = align.wrapping_sub(addr) & (align - 1);
+    if byte_offset == 0 {
+        return 0;
+    }
+    if stride == 0 {
+        return u128::max_value();
+    }
+    // `n * stride == byte_offset (mod align)` has a solution iff the largest power of two
+    // dividing both `stride` and `align` also divides `byte_offset`. Dividing everything
+    // by it leaves an odd multiplier, which is invertible modulo the remaining power of two.
+    let gcd = cmp::min(1 << stride.trailing_zeros(), align);
+    if byte_offset % gcd != 0 {
+        return u128::max_value();
+    }
+    let modulus = align / gcd;
+    let multiplier = stride / gcd;
+    // Every odd number is its own inverse modulo 8, and each Newton step doubles the
+    // number of correct low bits.
+    let mut inverse = multiplier;
+    for _ in 0..5 {
+        inverse = inverse.wrapping_mul(2u64.wrapping_sub(multiplier.wrapping_mul(inverse)));
+    }
+    ((byte_offset / gcd).wrapping_mul(inverse) & (modulus - 1)) as u128
+}
 
 fn numeric_intrinsic<'tcx>(
     name: &str,
@@ -39,6 +72,9 @@ fn numeric_intrinsic<'tcx>(
         "ctlz" => bits.leading_zeros() as u128 - extra,
         "cttz" => (bits << extra).trailing_zeros() as u128 - extra,
         "bswap" => (bits << extra).swap_bytes(),
+        "bitreverse" => (0..size.bits()).fold(0, |acc, i| {
+            acc | ((bits >> i) & 1) << (size.bits() - 1 - i)
+        }),
         _ => bug!("not a numeric intrinsic: {}", name),
     };
     Ok(Scalar::Bits { bits: bits_out, size: size.bytes() as u8 })
@@ -76,6 +112,22 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                 self.write_scalar(size_val, dest)?;
             }
 
+            "size_of_val" | "min_align_of_val" => {
+                let ptr = self.read_value(args[0])?;
+                let place = self.ref_to_mplace(ptr)?;
+                let (size, align) = self.size_and_align_of_mplace(place)?;
+                let bits = if intrinsic_name == "size_of_val" {
+                    size.bytes()
+                } else {
+                    align.abi()
+                };
+                let val = Scalar::Bits {
+                    bits: bits as u128,
+                    size: dest.layout.size.bytes() as u8,
+                };
+                self.write_scalar(val, dest)?;
+            }
+
             "type_id" => {
                 let ty = substs.type_at(0);
                 let type_id = self.tcx.type_id_hash(ty) as u128;
@@ -85,7 +137,40 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                 };
                 self.write_scalar(id_val, dest)?;
             }
-            "ctpop" | "cttz" | "cttz_nonzero" | "ctlz" | "ctlz_nonzero" | "bswap" => {
+
+            "type_name" => {
+                let ty = substs.type_at(0);
+                let name = self.str_to_value(&ty.to_string())?;
+                self.write_value(name, dest)?;
+            }
+
+            "needs_drop" => {
+                let ty = substs.type_at(0);
+                let needs_drop = ty.needs_drop(self.tcx.tcx, self.param_env);
+                self.write_scalar(Scalar::from_bool(needs_drop), dest)?;
+            }
+
+            "discriminant_value" => {
+                let ptr = self.read_value(args[0])?;
+                let place = self.ref_to_mplace(ptr)?;
+                let (discr, _) = self.read_discriminant(place.into())?;
+                // Like codegen, sign extend discriminants of signed type to the result.
+                let discr = match place.layout.ty.ty_adt_def() {
+                    Some(def) if def.is_enum() && def.repr.discr_type().is_signed() => {
+                        let size = Integer::from_attr(self.tcx.tcx, def.repr.discr_type()).size();
+                        let shift = 128 - size.bits();
+                        (((discr << shift) as i128) >> shift) as u128
+                    }
+                    _ => discr,
+                };
+                let discr_val = Scalar::Bits {
+                    bits: self.truncate(discr, dest.layout),
+                    size: dest.layout.size.bytes() as u8,
+                };
+                self.write_scalar(discr_val, dest)?;
+            }
+            "ctpop" | "cttz" | "cttz_nonzero" | "ctlz" | "ctlz_nonzero" | "bswap"
+            | "bitreverse" => {
                 let ty = substs.type_at(0);
                 let layout_of = self.layout_of(ty)?;
                 let bits = self.read_scalar(args[0])?.to_bits(layout_of.size)?;
@@ -104,12 +189,566 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                 self.write_scalar(out_val, dest)?;
             }
 
+            "assume" => {
+                if !self.read_scalar(args[0])?.to_bool()? {
+                    return err!(AssumptionNotHeld);
+                }
+            }
+
+            "likely" | "unlikely" => {
+                let val = self.read_scalar(args[0])?;
+                self.write_scalar(val, dest)?;
+            }
+
+            | "sqrtf32" | "sqrtf64" | "powif32" | "powif64" | "fabsf32" | "fabsf64"
+            | "floorf32" | "floorf64" | "ceilf32" | "ceilf64" | "truncf32" | "truncf64"
+            | "roundf32" | "roundf64" => {
+                let bits = self.read_scalar(args[0])?.to_bits(dest.layout.size)?;
+                let op_name = &intrinsic_name[..intrinsic_name.len() - "f32".len()];
+                let exp = if op_name == "powi" {
+                    self.read_scalar(args[1])?.to_i32()?
+                } else {
+                    0
+                };
+                let res = if intrinsic_name.ends_with("f32") {
+                    // IEEE 754 requires `sqrt` to be correctly rounded, so the host
+                    // computes the same result as any other implementation, except for
+                    // the NaN it returns, which `float_intrinsic` picks instead.
+                    let host_sqrt = |x: Single| {
+                        let res = f32::from_bits(x.to_bits() as u32).sqrt();
+                        Single::from_bits(res.to_bits() as u128)
+                    };
+                    self.float_intrinsic(op_name, Single::from_bits(bits), exp, host_sqrt)
+                        .to_bits()
+                } else {
+                    let host_sqrt = |x: Double| {
+                        let res = f64::from_bits(x.to_bits() as u64).sqrt();
+                        Double::from_bits(res.to_bits() as u128)
+                    };
+                    self.float_intrinsic(op_name, Double::from_bits(bits), exp, host_sqrt)
+                        .to_bits()
+                };
+                let res = Scalar::Bits { bits: res, size: dest.layout.size.bytes() as u8 };
+                self.write_scalar(res, dest)?;
+            }
+
+            "float_to_int_unchecked" => {
+                let fty = match args[0].layout.ty.sty {
+                    ty::Float(fty) => fty,
+                    _ => return err!(Intrinsic(format!(
+                        "invalid monomorphization of `float_to_int_unchecked` intrinsic: \
+                         expected basic float type, found `{}`", args[0].layout.ty,
+                    ))),
+                };
+                let bits = self.read_scalar(args[0])?.to_bits(args[0].layout.size)?;
+                let res = self.float_to_int_checked(bits, fty, dest.layout.ty)?;
+                self.write_scalar(res, dest)?;
+            }
+
+            "transmute" => {
+                // This cannot fail for MIR built from Rust source: `intrinsicck` only accepts
+                // transmutes whose size skeletons match, which holds for every substitution
+                // of the generic parameters, so not even a generic function can get here
+                // with types of different sizes. It only guards MIR that never went through
+                // typeck, like shims of machines, so it reports rather than ICEs.
+                if args[0].layout.size != dest.layout.size {
+                    return err!(Intrinsic(format!(
+                        "transmuting from `{}` ({} bytes) to `{}` ({} bytes), \
+                         which have different sizes",
+                        args[0].layout.ty, args[0].layout.size.bytes(),
+                        dest.layout.ty, dest.layout.size.bytes(),
+                    )));
+                }
+                self.copy_op_transmute(args[0], dest)?;
+            }
+
+            "three_way_compare" => {
+                let ty = substs.type_at(0);
+                if !(ty.is_integral() || ty.is_char() || ty.is_bool()) {
+                    return err!(Intrinsic(format!("three_way_compare called on type `{}`, \
+                                                   which is not an integer, `char` or `bool`",
+                                                  ty)));
+                }
+                let l = self.read_value(args[0])?;
+                let r = self.read_value(args[1])?;
+                self.binop_ignore_overflow(mir::BinOp::Cmp, l, r, dest)?;
+            }
+
+            "rotate_left" | "rotate_right" => {
+                let layout = args[0].layout;
+                let l = self.read_scalar(args[0])?.to_bits(layout.size)?;
+                let r = self.read_scalar(args[1])?.to_bits(layout.size)?;
+                let val = self.rotate_int(l, r, layout, intrinsic_name == "rotate_left");
+                self.write_scalar(val, dest)?;
+            }
+
+            "add_with_overflow" | "sub_with_overflow" | "mul_with_overflow" => {
+                let l = self.read_value(args[0])?;
+                let r = self.read_value(args[1])?;
+                let op = match intrinsic_name {
+                    "add_with_overflow" => mir::BinOp::Add,
+                    "sub_with_overflow" => mir::BinOp::Sub,
+                    _ => mir::BinOp::Mul,
+                };
+                self.binop_with_overflow(op, l, r, dest)?;
+            }
+
+            "overflowing_add" | "overflowing_sub" | "overflowing_mul" => {
+                // These wrap around by definition, so the machine does not get a say
+                // like it does for unchecked MIR arithmetic.
+                let l = self.read_value(args[0])?;
+                let r = self.read_value(args[1])?;
+                let op = match intrinsic_name {
+                    "overflowing_add" => mir::BinOp::Add,
+                    "overflowing_sub" => mir::BinOp::Sub,
+                    _ => mir::BinOp::Mul,
+                };
+                let (val, _overflowed) = self.binary_op(op, l, r)?;
+                self.write_value(val.value, dest)?;
+            }
+
+            "unchecked_div" | "unchecked_rem" | "unchecked_shl" | "unchecked_shr" => {
+                let l = self.read_value(args[0])?;
+                let r = self.read_value(args[1])?;
+                let op = match intrinsic_name {
+                    "unchecked_div" => mir::BinOp::Div,
+                    "unchecked_rem" => mir::BinOp::Rem,
+                    "unchecked_shl" => mir::BinOp::Shl,
+                    _ => mir::BinOp::Shr,
+                };
+                if let mir::BinOp::Div | mir::BinOp::Rem = op {
+                    if r.to_scalar()?.to_bits(r.layout.size)? == 0 {
+                        return err!(UncheckedDivisionByZero(intrinsic_name.to_string()));
+                    }
+                }
+                let (val, overflowed) = self.binary_op(op, l, r)?;
+                if overflowed {
+                    return match op {
+                        mir::BinOp::Shl | mir::BinOp::Shr => self.shift_overflow(l, r),
+                        _ => err!(UncheckedOverflow(intrinsic_name.to_string())),
+                    };
+                }
+                self.write_value(val.value, dest)?;
+            }
+
+            "exact_div" => {
+                let l = self.read_value(args[0])?;
+                let r = self.read_value(args[1])?;
+                if r.to_scalar()?.to_bits(r.layout.size)? == 0 {
+                    return err!(UncheckedDivisionByZero(intrinsic_name.to_string()));
+                }
+                let (val, overflowed) = self.binary_op(mir::BinOp::Div, l, r)?;
+                if overflowed {
+                    return err!(UncheckedOverflow(intrinsic_name.to_string()));
+                }
+                let (rem, _) = self.binary_op(mir::BinOp::Rem, l, r)?;
+                if rem.to_scalar()?.to_bits(rem.layout.size)? != 0 {
+                    return err!(InexactDivision);
+                }
+                self.write_value(val.value, dest)?;
+            }
+
+            "saturating_add" | "saturating_sub" => {
+                let l = self.read_value(args[0])?;
+                let r = self.read_value(args[1])?;
+                let op = if intrinsic_name == "saturating_add" {
+                    mir::BinOp::Add
+                } else {
+                    mir::BinOp::Sub
+                };
+                let val = self.saturating_arith(op, l, r)?;
+                self.write_scalar(val, dest)?;
+            }
+
+            "copy" | "copy_nonoverlapping" => {
+                let elem_layout = self.layout_of(substs.type_at(0))?;
+                let count = self.read_scalar(args[2])?.to_usize(self)?;
+                let size = self.intrinsic_array_size(intrinsic_name, elem_layout, count)?;
+                let src = self.read_scalar(args[0])?.not_undef()?;
+                let dest = self.read_scalar(args[1])?.not_undef()?;
+                self.memory.copy(
+                    src,
+                    elem_layout.align,
+                    dest,
+                    elem_layout.align,
+                    size,
+                    intrinsic_name == "copy_nonoverlapping",
+                )?;
+            }
+
+            "write_bytes" => {
+                let elem_layout = self.layout_of(substs.type_at(0))?;
+                let ptr = self.read_scalar(args[0])?.not_undef()?;
+                let val = self.read_scalar(args[1])?.to_u8()?;
+                let count = self.read_scalar(args[2])?.to_usize(self)?;
+                let size = self.intrinsic_array_size(intrinsic_name, elem_layout, count)?;
+                self.memory.check_align(ptr, elem_layout.align)?;
+                self.memory.write_repeat(ptr, val, size)?;
+            }
+
+            "offset" => {
+                let ptr = self.read_scalar(args[0])?.not_undef()?;
+                let offset = self.read_scalar(args[1])?.to_isize(self)?;
+                let ptr = self.pointer_offset_inbounds(ptr, substs.type_at(0), offset)?;
+                self.write_scalar(ptr, dest)?;
+            }
+
+            "arith_offset" => {
+                let ptr = self.read_scalar(args[0])?.not_undef()?;
+                let offset = self.read_scalar(args[1])?.to_isize(self)?;
+                let pointee_size = self.layout_of(substs.type_at(0))?.size.bytes() as i64;
+                let ptr = ptr.ptr_wrapping_signed_offset(offset.wrapping_mul(pointee_size), self);
+                self.write_scalar(ptr, dest)?;
+            }
+
+            "ptr_offset_from" => {
+                let ptr = self.read_scalar(args[0])?.not_undef()?;
+                let base = self.read_scalar(args[1])?.not_undef()?;
+                let pointee_layout = self.layout_of(substs.type_at(0))?;
+                let distance = self.ptr_offset_from(ptr, base, pointee_layout)?;
+                let val = Scalar::Bits {
+                    bits: self.truncate(distance as u128, dest.layout),
+                    size: dest.layout.size.bytes() as u8,
+                };
+                self.write_scalar(val, dest)?;
+            }
+
+            "ptr_guaranteed_eq" | "ptr_guaranteed_ne" => {
+                let a = self.read_scalar(args[0])?.not_undef()?;
+                let b = self.read_scalar(args[1])?.not_undef()?;
+                let res = match self.guaranteed_ptr_eq(a, b)? {
+                    Some(eq) => (intrinsic_name == "ptr_guaranteed_eq") == eq,
+                    None => false,
+                };
+                self.write_scalar(Scalar::from_bool(res), dest)?;
+            }
+
+            name if name.starts_with("simd_") => {
+                return self.emulate_simd_intrinsic(name, args, dest);
+            }
+
+            name if name.starts_with("atomic_") && M::SINGLE_THREADED_ATOMICS => {
+                self.emulate_atomic_intrinsic(name, args, dest)?;
+            }
+
             _ => return Ok(false),
         }
 
         Ok(true)
     }
 
+    /// Applies the float intrinsic `op_name`, e.g. `floor` for `floorf64`, to `x`. `exp` is the
+    /// exponent of `powi`, and `sqrt` computes the square root.
+    fn float_intrinsic<F: Float>(
+        &self,
+        op_name: &str,
+        x: F,
+        exp: i32,
+        sqrt: impl FnOnce(F) -> F,
+    ) -> F {
+        let res = match op_name {
+            // Only the NaN results can differ between hosts.
+            "sqrt" if x.is_nan() || (x.is_negative() && !x.is_zero()) => F::NAN,
+            "sqrt" => sqrt(x),
+            "fabs" => x.abs(),
+            "floor" => x.round_to_integral(Round::TowardNegative).value,
+            "ceil" => x.round_to_integral(Round::TowardPositive).value,
+            "trunc" => x.round_to_integral(Round::TowardZero).value,
+            "round" => x.round_to_integral(Round::NearestTiesToAway).value,
+            // The same sequence of multiplications as `__powidf2` in compiler-rt, which is what
+            // `llvm.powi` calls at runtime.
+            "powi" => {
+                let (mut base, mut n) = (x, exp);
+                let mut res = F::from_u128(1).value;
+                loop {
+                    if n & 1 != 0 {
+                        res = (res * base).value;
+                    }
+                    n /= 2;
+                    if n == 0 {
+                        break;
+                    }
+                    base = (base * base).value;
+                }
+                if exp < 0 { (F::from_u128(1).value / res).value } else { res }
+            }
+            _ => bug!("not a float intrinsic: {}", op_name),
+        };
+        self.adjust_nan(res, &[x])
+    }
+
+    /// Executes a `simd_*` intrinsic on `#[repr(simd)]` vectors, lane by lane. Returns whether
+    /// the intrinsic is supported.
+    fn emulate_simd_intrinsic(
+        &mut self,
+        intrinsic_name: &str,
+        args: &[OpTy<'tcx>],
+        dest: PlaceTy<'tcx>,
+    ) -> EvalResult<'tcx, bool> {
+        let op = match intrinsic_name {
+            "simd_add" => Some(mir::BinOp::Add),
+            "simd_sub" => Some(mir::BinOp::Sub),
+            "simd_mul" => Some(mir::BinOp::Mul),
+            "simd_div" => Some(mir::BinOp::Div),
+            "simd_rem" => Some(mir::BinOp::Rem),
+            "simd_shl" => Some(mir::BinOp::Shl),
+            "simd_shr" => Some(mir::BinOp::Shr),
+            "simd_and" => Some(mir::BinOp::BitAnd),
+            "simd_or" => Some(mir::BinOp::BitOr),
+            "simd_xor" => Some(mir::BinOp::BitXor),
+            "simd_eq" => Some(mir::BinOp::Eq),
+            "simd_ne" => Some(mir::BinOp::Ne),
+            "simd_lt" => Some(mir::BinOp::Lt),
+            "simd_le" => Some(mir::BinOp::Le),
+            "simd_gt" => Some(mir::BinOp::Gt),
+            "simd_ge" => Some(mir::BinOp::Ge),
+            _ => None,
+        };
+        if let Some(op) = op {
+            self.binary_simd_op(op, args[0], args[1], dest)?;
+            return Ok(true);
+        }
+
+        match intrinsic_name {
+            "simd_extract" => {
+                let index = self.simd_lane_index(args[0], args[1])?;
+                let lane = self.operand_field(args[0], index)?;
+                self.copy_op(lane, dest)?;
+            }
+
+            "simd_insert" => {
+                let index = self.simd_lane_index(args[0], args[1])?;
+                self.copy_op(args[0], dest)?;
+                let lane = self.place_field(dest, index)?;
+                self.copy_op(args[2], lane)?;
+            }
+
+            // `simd_shuffleN(x, y, indices)` picks `N` lanes out of the concatenation of `x`
+            // and `y`.
+            name if name.starts_with("simd_shuffle") => {
+                let len = args[0].layout.ty.simd_size(*self.tcx) as u64;
+                let out_len = dest.layout.ty.simd_size(*self.tcx) as u64;
+                for i in 0..out_len {
+                    let index = self.operand_field(args[2], i)?;
+                    let index = self.read_scalar(index)?.to_u32()? as u64;
+                    let lane = if index < len {
+                        self.operand_field(args[0], index)?
+                    } else if index < 2 * len {
+                        self.operand_field(args[1], index - len)?
+                    } else {
+                        return err!(Intrinsic(format!("{}: lane index {} is out of bounds \
+                                                       for two vectors of {} lanes",
+                                                      name, index, len)));
+                    };
+                    let dest_lane = self.place_field(dest, i)?;
+                    self.copy_op(lane, dest_lane)?;
+                }
+            }
+
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Reads the lane index `index` for an access to the vector `vector`, and checks that it
+    /// is in bounds.
+    fn simd_lane_index(&self, vector: OpTy<'tcx>, index: OpTy<'tcx>) -> EvalResult<'tcx, u64> {
+        let len = vector.layout.ty.simd_size(*self.tcx) as u64;
+        let index = self.read_scalar(index)?.to_u32()? as u64;
+        if index >= len {
+            return err!(Intrinsic(format!("SIMD lane index {} is out of bounds for a vector \
+                                           of {} lanes", index, len)));
+        }
+        Ok(index)
+    }
+
+    /// Executes an `atomic_*` intrinsic, ignoring the memory ordering in its name.
+    fn emulate_atomic_intrinsic(
+        &mut self,
+        intrinsic_name: &str,
+        args: &[OpTy<'tcx>],
+        dest: PlaceTy<'tcx>,
+    ) -> EvalResult<'tcx> {
+        // E.g. `xadd` for `atomic_xadd_acqrel`
+        let op_name = intrinsic_name["atomic_".len()..].split('_').next().unwrap();
+        if op_name == "fence" || op_name == "singlethreadfence" {
+            return Ok(());
+        }
+
+        let ptr = self.read_value(args[0])?;
+        let place = self.ref_to_mplace(ptr)?;
+        match place.layout.ty.sty {
+            ty::Int(_) | ty::Uint(_) => {}
+            _ => return err!(Intrinsic(format!("invalid monomorphization of `{}` intrinsic: \
+                                                expected basic integer type, found `{}`",
+                                               intrinsic_name, place.layout.ty))),
+        }
+        // Integers are always a power of two bytes large
+        let size = place.layout.size.bytes();
+        self.memory.check_align(place.ptr, Align::from_bytes(size, size).unwrap())?;
+
+        match op_name {
+            "load" => {
+                let val = self.read_value(place.into())?;
+                self.write_value(val.value, dest)?;
+            }
+
+            "store" => {
+                self.copy_op(args[1], place.into())?;
+            }
+
+            "xchg" => {
+                let old = self.read_value(place.into())?;
+                self.copy_op(args[1], place.into())?;
+                self.write_value(old.value, dest)?;
+            }
+
+            "cxchg" | "cxchgweak" => {
+                let old = self.read_value(place.into())?;
+                let expected = self.read_value(args[1])?;
+                let (eq, _) = self.binary_op(mir::BinOp::Eq, old, expected)?;
+                let eq = eq.to_scalar()?.to_bool()?;
+                if eq {
+                    self.copy_op(args[2], place.into())?;
+                }
+                let res = Value::ScalarPair(old.to_scalar()?.into(), Scalar::from_bool(eq).into());
+                self.write_value(res, dest)?;
+            }
+
+            _ => {
+                let old = self.read_value(place.into())?;
+                let rhs = self.read_value(args[1])?;
+                let new = match op_name {
+                    "xadd" => self.binary_op(mir::BinOp::Add, old, rhs)?.0,
+                    "xsub" => self.binary_op(mir::BinOp::Sub, old, rhs)?.0,
+                    "and" => self.binary_op(mir::BinOp::BitAnd, old, rhs)?.0,
+                    "or" => self.binary_op(mir::BinOp::BitOr, old, rhs)?.0,
+                    "xor" => self.binary_op(mir::BinOp::BitXor, old, rhs)?.0,
+                    "nand" => {
+                        let and = self.binary_op(mir::BinOp::BitAnd, old, rhs)?.0;
+                        self.unary_op(mir::UnOp::Not, and)?
+                    }
+                    // The signedness of the comparison comes from the type: `max` and `min`
+                    // are used with signed integers, `umax` and `umin` with unsigned ones.
+                    "max" | "umax" | "min" | "umin" => {
+                        let op = if op_name.ends_with("max") {
+                            mir::BinOp::Gt
+                        } else {
+                            mir::BinOp::Lt
+                        };
+                        let (keep_old, _) = self.binary_op(op, old, rhs)?;
+                        if keep_old.to_scalar()?.to_bool()? { old } else { rhs }
+                    }
+                    _ => return err!(Unimplemented(format!("calling intrinsic `{}`",
+                                                           intrinsic_name))),
+                };
+                self.write_value(new.value, place.into())?;
+                self.write_value(old.value, dest)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Executes a call to an intrinsic that never returns, like `abort`.
+    pub fn emulate_diverging_intrinsic(
+        &mut self,
+        instance: ty::Instance<'tcx>,
+    ) -> EvalResult<'tcx> {
+        match &self.tcx.item_name(instance.def_id()).as_str()[..] {
+            "abort" => err!(Abort),
+            // Anything else that diverges, e.g. `transmute` returning `!`, is unreachable.
+            _ => err!(Unreachable),
+        }
+    }
+
+    /// Computes the size of `count` consecutive values of `elem_layout`, as accessed by
+    /// the intrinsic `name`.
+    fn intrinsic_array_size(
+        &self,
+        name: &str,
+        elem_layout: TyLayout<'tcx>,
+        count: u64,
+    ) -> EvalResult<'tcx, Size> {
+        // No object can be larger than the target's address space allows, which for 16-bit
+        // targets is much less than what a `u64` can hold.
+        match elem_layout.size.bytes().checked_mul(count) {
+            Some(size) if size < self.tcx.data_layout.obj_size_bound() => {
+                Ok(Size::from_bytes(size))
+            }
+            _ => err!(Intrinsic(format!("{} of {} values of type `{}` overflows \
+                                            the address space",
+                                           name, count, elem_layout.ty))),
+        }
+    }
+
+    /// The distance from `base` to `ptr` in units of `pointee_layout`, with the semantics of
+    /// the `ptr_offset_from` intrinsic.
+    fn ptr_offset_from(
+        &self,
+        ptr: Scalar,
+        base: Scalar,
+        pointee_layout: TyLayout<'tcx>,
+    ) -> EvalResult<'tcx, i64> {
+        let pointee_size = pointee_layout.size.bytes() as i64;
+        if pointee_size == 0 {
+            return err!(OffsetFromZeroSized(pointee_layout.ty));
+        }
+        let bytes = match (ptr, base) {
+            (Scalar::Ptr(ptr), Scalar::Ptr(base)) => {
+                if ptr.alloc_id != base.alloc_id {
+                    return err!(InvalidPointerMath);
+                }
+                ptr.offset.bytes().wrapping_sub(base.offset.bytes()) as i64
+            }
+            (Scalar::Bits { bits: ptr, .. }, Scalar::Bits { bits: base, .. }) => {
+                // The difference wraps around at the pointer width, and its sign is the top bit
+                // at that width
+                let (bytes, _) = self.memory.truncate_to_ptr(ptr.wrapping_sub(base));
+                sign_extend(bytes as u128, self.memory.pointer_size()) as i128 as i64
+            }
+            _ => return err!(InvalidPointerMath),
+        };
+        if bytes % pointee_size != 0 {
+            return err!(OffsetFromInexact { bytes, pointee: pointee_layout.ty });
+        }
+        Ok(bytes / pointee_size)
+    }
+
+    /// Computes what `ptr::align_offset` returns: how many `stride`-sized steps `ptr` has to
+    /// be moved to become aligned to `align`. Unless the machine knows better, this is
+    /// `u128::max_value()` (`usize::MAX` once truncated) if that depends on the address of
+    /// the allocation `ptr` points into.
+    fn align_offset(&self, ptr: Scalar, stride: u64, align: u64) -> EvalResult<'tcx, u128> {
+        if !align.is_power_of_two() {
+            return err!(Intrinsic(format!("align_offset called with alignment {}, \
+                                           which is not a power of two", align)));
+        }
+        let addr = match ptr {
+            Scalar::Bits { bits, .. } => bits as u64,
+            Scalar::Ptr(ptr) => {
+                // An allocation that is at least as aligned as requested lets us work with
+                // the offset into it instead of the unknown address.
+                if self.memory.get(ptr.alloc_id)?.align.abi() < align {
+                    let offset = M::align_offset(self, ptr, stride, align)?;
+                    return Ok(offset.unwrap_or(u128::max_value()));
+                }
+                ptr.offset.bytes()
+            }
+        };
+        Ok(align_offset_of_addr(addr, stride, align))
+    }
+
+    /// Compares two pointers if that is possible without knowing where allocations
+    /// are placed in memory. Returns `None` if neither we nor the machine can tell.
+    pub(super) fn guaranteed_ptr_eq(&self, a: Scalar, b: Scalar) -> EvalResult<'tcx, Option<bool>> {
+        match (a, b) {
+            (Scalar::Bits { bits: a, .. }, Scalar::Bits { bits: b, .. }) => Ok(Some(a == b)),
+            (Scalar::Ptr(a), Scalar::Ptr(b)) if a.alloc_id == b.alloc_id =>
+                Ok(Some(a.offset == b.offset)),
+            _ => M::ptr_guaranteed_cmp(self, a, b),
+        }
+    }
+
     /// "Intercept" a function call because we have something special to do for it.
     /// Returns whether an intercept happened.
     pub fn hook_fn(
@@ -168,8 +807,54 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
             let line = self.read_scalar(line.into())?.to_u32()?;
             let col = self.read_scalar(col.into())?.to_u32()?;
             return Err(EvalErrorKind::Panic { msg, file, line, col }.into());
+        } else if Some(def_id) == self.tcx.lang_items().align_offset_fn() {
+            let dest = dest.expect("align_offset can't diverge");
+            let ptr = self.read_scalar(args[0])?.not_undef()?;
+            let align = self.read_scalar(args[1])?.to_usize(self)?;
+            let stride = self.layout_of(instance.substs.type_at(0))?.size.bytes();
+            let offset = self.align_offset(ptr, stride, align)?;
+            let val = Scalar::Bits {
+                bits: self.truncate(offset, dest.layout),
+                size: dest.layout.size.bytes() as u8,
+            };
+            self.write_scalar(val, dest)?;
+            return Ok(true);
         } else {
             return Ok(false);
         }
     }
+
+    /// Look up a MIR implementation for the intrinsic `instance`. This is a function
+    /// of the same name, marked `#[rustc_intrinsic_fallback]`, in the `fallback`
+    /// submodule of the module declaring the intrinsic (see `core::intrinsics::fallback`).
+    ///
+    /// Only intrinsics from other crates are considered.
+    // FIXME: also look for fallbacks when evaluating inside the crate declaring them
+    pub fn intrinsic_fallback(
+        &self,
+        instance: ty::Instance<'tcx>,
+    ) -> Option<ty::Instance<'tcx>> {
+        let def_id = instance.def_id();
+        if def_id.is_local() {
+            return None;
+        }
+        let name = self.tcx.item_name(def_id);
+        let parent = self.tcx.parent_def_id(def_id)?;
+        let fallback_mod = self.tcx.item_children(parent).iter().filter_map(|child| {
+            match child.def {
+                Def::Mod(module) if child.ident.name == "fallback" => Some(module),
+                _ => None,
+            }
+        }).next()?;
+        let fallback = self.tcx.item_children(fallback_mod).iter().filter_map(|child| {
+            match child.def {
+                Def::Fn(fn_def_id) if child.ident.name == name => Some(fn_def_id),
+                _ => None,
+            }
+        }).next()?;
+        if !self.tcx.has_attr(fallback, "rustc_intrinsic_fallback") {
+            return None;
+        }
+        Some(ty::Instance::new(fallback, instance.substs))
+    }
 }
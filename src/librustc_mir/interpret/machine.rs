@@ -15,11 +15,38 @@
 use std::hash::Hash;
 
 use rustc::hir::def_id::DefId;
-use rustc::mir::interpret::{Allocation, EvalResult, Scalar};
+use rustc::mir::interpret::{Allocation, EvalResult, Scalar, Pointer};
 use rustc::mir;
-use rustc::ty::{self, layout::TyLayout, query::TyCtxtAt};
+use rustc::ty::{self, Ty, layout::{Size, TyLayout}, query::TyCtxtAt};
+use rustc_apfloat::{Float, Status};
+use syntax::source_map::Span;
 
-use super::{EvalContext, PlaceTy, OpTy};
+use super::{EvalContext, PlaceTy, OpTy, Memory, MemoryAccess};
+
+/// What happens when evaluated code panics.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum UnwindPolicy {
+    /// Evaluation stops with an error right away.
+    Abort,
+    /// The stack is unwound like with `panic=unwind`: the cleanup blocks of all frames run, and
+    /// evaluation stops with an error only once the panic leaves the outermost frame.
+    Unwind,
+}
+
+/// How a function call is carried out, as decided by `Machine::find_fn`.
+pub enum FnCall<'mir, 'tcx: 'mir> {
+    /// A stack frame running this MIR is pushed for the called instance.
+    Mir(&'mir mir::Mir<'tcx>),
+    /// This instance is called instead, with the same arguments, signature and return place,
+    /// so it must take its arguments the same way as the original callee.
+    Redirect(ty::Instance<'tcx>),
+    /// The machine did the work of the called function, including writing the return value.
+    /// Execution continues at the return block.
+    Emulated,
+    /// The machine took care of everything, including moving the instruction pointer, e.g.
+    /// by pushing its own stack frame.
+    Handled,
+}
 
 /// Methods of this trait signifies a point where CTFE evaluation would fail
 /// and some use case dependent behaviour can instead be applied
@@ -30,17 +57,30 @@ pub trait Machine<'mir, 'tcx>: Clone + Eq + Hash {
     /// Additional memory kinds a machine wishes to distinguish from the builtin ones
     type MemoryKinds: ::std::fmt::Debug + Copy + Clone + Eq + Hash;
 
+    /// Extra data stored in every call frame
+    type FrameExtra: Clone + Eq + Hash;
+
     /// The memory kind to use for mutated statics -- or None if those are not supported.
     const MUT_STATIC_KIND: Option<Self::MemoryKinds>;
 
-    /// Entry point to all function calls.
+    /// Whether a shift that MIR does not check for overflow is undefined behavior if it is by a
+    /// negative amount or by at least the width of the shifted integer. Otherwise, only the low
+    /// bits of the amount are used, like for any other unchecked overflow.
+    const SHIFT_OVERFLOW_IS_UB: bool = false;
+
+    /// Whether the engine executes the `atomic_*` intrinsics. Without threads, every ordering
+    /// is sequentially consistent, so they become plain memory accesses that additionally
+    /// require the place to be aligned to its size.
+    const SINGLE_THREADED_ATOMICS: bool = false;
+
+    /// Entry point to all function calls, consulted before a stack frame is pushed for
+    /// `instance`.
     ///
-    /// Returns either the mir to use for the call, or `None` if execution should
-    /// just proceed (which usually means this hook did all the work that the
-    /// called function should usually have done).  In the latter case, it is
-    /// this hook's responsibility to call `goto_block(ret)` to advance the instruction pointer!
-    /// (This is to support functions like `__rust_maybe_catch_panic` that neither find a MIR
-    /// nor just jump to `ret`, but instead push their own stack frame.)
+    /// This is the place to intercept calls, e.g. by matching on
+    /// `ecx.tcx.symbol_name(instance)` for functions like `memcpy` or `__rust_alloc` that have
+    /// no MIR. See `FnCall` for what the hook can do with a call. Functions like
+    /// `__rust_maybe_catch_panic` that neither find a MIR nor just jump to `ret`, but instead
+    /// push their own stack frame, return `FnCall::Handled`.
     /// Passing `dest`and `ret` in the same `Option` proved very annoying when only one of them
     /// was used.
     fn find_fn<'a>(
@@ -49,16 +89,18 @@ pub trait Machine<'mir, 'tcx>: Clone + Eq + Hash {
         args: &[OpTy<'tcx>],
         dest: Option<PlaceTy<'tcx>>,
         ret: Option<mir::BasicBlock>,
-    ) -> EvalResult<'tcx, Option<&'mir mir::Mir<'tcx>>>;
+    ) -> EvalResult<'tcx, FnCall<'mir, 'tcx>>;
 
     /// Directly process an intrinsic without pushing a stack frame.
-    /// If this returns successfully, the engine will take care of jumping to the next block.
+    /// If this returns `true`, the engine will take care of jumping to the next block.
+    /// If this returns `false`, the engine calls the intrinsic's MIR fallback body
+    /// (see `EvalContext::intrinsic_fallback`) or errors if there is none.
     fn call_intrinsic<'a>(
         ecx: &mut EvalContext<'a, 'mir, 'tcx, Self>,
         instance: ty::Instance<'tcx>,
         args: &[OpTy<'tcx>],
         dest: PlaceTy<'tcx>,
-    ) -> EvalResult<'tcx>;
+    ) -> EvalResult<'tcx, bool>;
 
     /// Called for read access to a foreign static item.
     /// This can be called multiple times for the same static item and should return consistent
@@ -69,7 +111,8 @@ pub trait Machine<'mir, 'tcx>: Clone + Eq + Hash {
         def_id: DefId,
     ) -> EvalResult<'tcx, &'tcx Allocation>;
 
-    /// Called for all binary operations except on float types.
+    /// Called for all binary operations except on float types and `Offset`, which the engine
+    /// implements itself.
     ///
     /// Returns `None` if the operation should be handled by the integer
     /// op code in order to share more code between machines
@@ -84,6 +127,136 @@ pub trait Machine<'mir, 'tcx>: Clone + Eq + Hash {
         right_layout: TyLayout<'tcx>,
     ) -> EvalResult<'tcx, Option<(Scalar, bool)>>;
 
+    /// Called for `==` and `!=` between a pointer and a plain integer, e.g. a null check. The
+    /// result of such a comparison generally depends on the address of the allocation, which the
+    /// engine does not know. `EvalContext::ptr_int_eq_known` answers the comparisons for which
+    /// that address does not matter, machines can use it to be less strict.
+    ///
+    /// Returns `Some(equal)` if the machine decides the comparison, and `None` to hand it on to
+    /// `try_ptr_op` like any other operation involving pointers.
+    fn ptr_int_eq<'a>(
+        _ecx: &EvalContext<'a, 'mir, 'tcx, Self>,
+        _ptr: Pointer,
+        _int: u128,
+    ) -> EvalResult<'tcx, Option<bool>> {
+        Ok(None)
+    }
+
+    /// Called for `==` and `!=` between pointers into two different allocations, whose result
+    /// depends on where the allocations are placed in memory. Pointers into the same allocation
+    /// are compared by the engine.
+    ///
+    /// By default such pointers are unequal, which is right unless one of them points one
+    /// past the end of its allocation. Machines can be stricter and reject the comparison, or
+    /// looser and compare actual addresses.
+    fn ptr_eq_cross_alloc<'a>(
+        _ecx: &EvalContext<'a, 'mir, 'tcx, Self>,
+        _left: Pointer,
+        _right: Pointer,
+    ) -> EvalResult<'tcx, bool> {
+        Ok(false)
+    }
+
+    /// Called by `ptr_guaranteed_eq` and `ptr_guaranteed_ne` for comparisons the engine
+    /// cannot answer on its own: pointers into different allocations, or a pointer and
+    /// an integer.
+    ///
+    /// Returns `Some(equal)` if the machine knows whether the two are equal, and `None`
+    /// if that is unknowable, in which case both intrinsics return `false`.
+    fn ptr_guaranteed_cmp<'a>(
+        _ecx: &EvalContext<'a, 'mir, 'tcx, Self>,
+        _left: Scalar,
+        _right: Scalar,
+    ) -> EvalResult<'tcx, Option<bool>> {
+        Ok(None)
+    }
+
+    /// Called by the `align_offset` lang item for pointers into allocations that are less
+    /// aligned than requested, so that where the pointer ends up depends on the address
+    /// the allocation is placed at.
+    ///
+    /// Returns the offset (in units of `stride` bytes) if the machine knows it, and `None`
+    /// otherwise, in which case `usize::MAX` is returned: the function is allowed to
+    /// give up that way.
+    fn align_offset<'a>(
+        _ecx: &EvalContext<'a, 'mir, 'tcx, Self>,
+        _ptr: Pointer,
+        _stride: u64,
+        _align: u64,
+    ) -> EvalResult<'tcx, Option<u128>> {
+        Ok(None)
+    }
+
+    /// Called when an arithmetic operation overflows that MIR does not check for overflow,
+    /// e.g. `+` in a crate built without overflow checks. Such operations wrap around.
+    ///
+    /// Returning an error, e.g. `Overflow(op)` or a machine-specific UB report, aborts
+    /// evaluation at the operation instead.
+    fn unchecked_overflow<'a>(
+        _ecx: &EvalContext<'a, 'mir, 'tcx, Self>,
+        _op: mir::BinOp,
+    ) -> EvalResult<'tcx> {
+        Ok(())
+    }
+
+    /// Called when a floating-point operation raises an IEEE 754 exception, i.e. its result is
+    /// inexact, overflowed or underflowed, or the operation was invalid (e.g. `0.0 / 0.0`) or
+    /// divided by zero. `status` holds the flags of all exceptions raised.
+    ///
+    /// The result of the operation is always the IEEE 754 default result. Returning an error
+    /// aborts evaluation at the operation instead.
+    fn float_op_status<'a>(
+        _ecx: &EvalContext<'a, 'mir, 'tcx, Self>,
+        _op: mir::BinOp,
+        _status: Status,
+    ) -> EvalResult<'tcx> {
+        Ok(())
+    }
+
+    /// Called when a floating-point operation on `inputs` produces a NaN. Returns the NaN to
+    /// use as the result, which may differ from `nan` in its sign and payload.
+    ///
+    /// By default, the NaN computed by `rustc_apfloat` is used, so the result is the same on
+    /// all hosts and targets. Machines can instead mimic what the target hardware does, e.g.
+    /// propagate the payload of an input NaN.
+    fn float_nan<'a, F: Float>(
+        _ecx: &EvalContext<'a, 'mir, 'tcx, Self>,
+        nan: F,
+        _inputs: &[F],
+    ) -> F {
+        nan
+    }
+
+    /// The maximum number of terminators a single evaluation may execute, or `None` if there
+    /// is no limit. Exceeding it yields a `StepLimitReached` error.
+    fn step_limit<'a>(_ecx: &EvalContext<'a, 'mir, 'tcx, Self>) -> Option<u64> {
+        None
+    }
+
+    /// How panics are handled, i.e. failing `Assert` terminators and calls to
+    /// `EvalContext::start_panic`.
+    fn unwind_policy<'a>(_ecx: &EvalContext<'a, 'mir, 'tcx, Self>) -> UnwindPolicy {
+        UnwindPolicy::Abort
+    }
+
+    /// Whether every value written or copied to a place is checked to be valid at the type
+    /// of that place, as `-Zmiri`-style validation does. References are checked themselves,
+    /// but not followed.
+    fn enforce_validity<'a>(_ecx: &EvalContext<'a, 'mir, 'tcx, Self>) -> bool {
+        false
+    }
+
+    /// Called before the `Drop` impl of a value of type `ty` is run, typically by drop glue.
+    /// Drops that do not reach a `Drop` impl, e.g. of `None::<String>`, never call this.
+    ///
+    /// Returning an error rejects the drop, e.g. for machines that cannot run destructors.
+    fn before_drop<'a>(
+        _ecx: &EvalContext<'a, 'mir, 'tcx, Self>,
+        _ty: Ty<'tcx>,
+    ) -> EvalResult<'tcx> {
+        Ok(())
+    }
+
     /// Heap allocations via the `box` keyword
     ///
     /// Returns a pointer to the allocated memory
@@ -92,6 +265,18 @@ pub trait Machine<'mir, 'tcx>: Clone + Eq + Hash {
         dest: PlaceTy<'tcx>,
     ) -> EvalResult<'tcx>;
 
+    /// Called immediately before a new stack frame is pushed.
+    /// The returned value is stored in the frame's `extra` field.
+    fn stack_push<'a>(
+        ecx: &mut EvalContext<'a, 'mir, 'tcx, Self>,
+    ) -> EvalResult<'tcx, Self::FrameExtra>;
+
+    /// Called immediately after a stack frame got popped, with that frame's `extra` data.
+    fn stack_pop<'a>(
+        ecx: &mut EvalContext<'a, 'mir, 'tcx, Self>,
+        extra: Self::FrameExtra,
+    ) -> EvalResult<'tcx>;
+
     /// Execute a validation operation
     fn validation_op<'a>(
         _ecx: &mut EvalContext<'a, 'mir, 'tcx, Self>,
@@ -100,4 +285,17 @@ pub trait Machine<'mir, 'tcx>: Clone + Eq + Hash {
     ) -> EvalResult<'tcx> {
         Ok(())
     }
+
+    /// Called whenever a read or write overlaps a range registered with
+    /// `Memory::add_watch`. `span` is the span of the statement performing the access.
+    /// Returning an error aborts evaluation at the access.
+    fn memory_watch_triggered<'a>(
+        _mem: &Memory<'a, 'mir, 'tcx, Self>,
+        _access: MemoryAccess,
+        _ptr: Pointer,
+        _size: Size,
+        _span: Span,
+    ) -> EvalResult<'tcx> {
+        Ok(())
+    }
 }
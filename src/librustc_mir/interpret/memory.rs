@@ -17,15 +17,14 @@
 //! short-circuiting the empty case!
 
 use std::collections::VecDeque;
-use std::hash::{Hash, Hasher};
-use std::ptr;
+use std::{ptr, slice};
 
 use rustc::ty::{self, Instance, query::TyCtxtAt};
 use rustc::ty::layout::{self, Align, TargetDataLayout, Size, HasDataLayout};
 use rustc::mir::interpret::{Pointer, AllocId, Allocation, ScalarMaybeUndef, GlobalId,
                             EvalResult, Scalar, EvalErrorKind, AllocType, truncate};
 pub use rustc::mir::interpret::{write_target_uint, read_target_uint};
-use rustc_data_structures::fx::{FxHashSet, FxHashMap, FxHasher};
+use rustc_data_structures::fx::{FxHashSet, FxHashMap};
 
 use syntax::ast::Mutability;
 
@@ -39,6 +38,13 @@ pub enum MemoryKind<T> {
     Machine(T),
 }
 
+/// The kind of access that triggered a watchpoint
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub enum MemoryAccess {
+    Read,
+    Write,
+}
+
 #[derive(Clone)]
 pub struct Memory<'a, 'mir, 'tcx: 'a + 'mir, M: Machine<'mir, 'tcx>> {
     /// Additional data required by the Machine
@@ -51,6 +57,10 @@ pub struct Memory<'a, 'mir, 'tcx: 'a + 'mir, M: Machine<'mir, 'tcx>> {
     /// a static creates a copy here, in the machine.
     alloc_map: FxHashMap<AllocId, (MemoryKind<M::MemoryKinds>, Allocation)>,
 
+    /// Byte ranges (start offset, size) per allocation for which the machine is
+    /// notified via `Machine::memory_watch_triggered` whenever they are accessed.
+    watches: FxHashMap<AllocId, Vec<(Size, Size)>>,
+
     pub tcx: TyCtxtAt<'a, 'tcx, 'tcx>,
 }
 
@@ -61,58 +71,6 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> HasDataLayout for &'a Memory<'a, 'm
     }
 }
 
-impl<'a, 'mir, 'tcx, M> Eq for Memory<'a, 'mir, 'tcx, M>
-    where M: Machine<'mir, 'tcx>,
-          'tcx: 'a + 'mir,
-{}
-
-impl<'a, 'mir, 'tcx, M> PartialEq for Memory<'a, 'mir, 'tcx, M>
-    where M: Machine<'mir, 'tcx>,
-          'tcx: 'a + 'mir,
-{
-    fn eq(&self, other: &Self) -> bool {
-        let Memory {
-            data,
-            alloc_map,
-            tcx: _,
-        } = self;
-
-        *data == other.data
-            && *alloc_map == other.alloc_map
-    }
-}
-
-impl<'a, 'mir, 'tcx, M> Hash for Memory<'a, 'mir, 'tcx, M>
-    where M: Machine<'mir, 'tcx>,
-          'tcx: 'a + 'mir,
-{
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        let Memory {
-            data,
-            alloc_map: _,
-            tcx: _,
-        } = self;
-
-        data.hash(state);
-
-        // We ignore some fields which don't change between evaluation steps.
-
-        // Since HashMaps which contain the same items may have different
-        // iteration orders, we use a commutative operation (in this case
-        // addition, but XOR would also work), to combine the hash of each
-        // `Allocation`.
-        self.alloc_map.iter()
-            .map(|(&id, alloc)| {
-                let mut h = FxHasher::default();
-                id.hash(&mut h);
-                alloc.hash(&mut h);
-                h.finish()
-            })
-            .fold(0u64, |hash, x| hash.wrapping_add(x))
-            .hash(state);
-    }
-}
-
 /// Helper function to obtain the global (tcx) allocation for a static
 fn const_eval_static<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>>(
     tcx: TyCtxtAt<'a, 'tcx, 'tcx>,
@@ -157,10 +115,61 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> Memory<'a, 'mir, 'tcx, M> {
         Memory {
             data,
             alloc_map: FxHashMap::default(),
+            watches: FxHashMap::default(),
             tcx,
         }
     }
 
+    /// Register a watchpoint on `size` bytes starting at `ptr`. Any read or write
+    /// overlapping this range will invoke `Machine::memory_watch_triggered`.
+    pub fn add_watch(&mut self, ptr: Pointer, size: Size) {
+        self.watches.entry(ptr.alloc_id).or_default().push((ptr.offset, size));
+    }
+
+    /// Remove a watchpoint previously registered with exactly this range.
+    /// Returns whether such a watchpoint existed.
+    pub fn remove_watch(&mut self, ptr: Pointer, size: Size) -> bool {
+        let removed = match self.watches.get_mut(&ptr.alloc_id) {
+            Some(ranges) => {
+                let len = ranges.len();
+                ranges.retain(|&range| range != (ptr.offset, size));
+                ranges.len() != len
+            }
+            None => return false,
+        };
+        if self.watches.get(&ptr.alloc_id).map_or(false, |ranges| ranges.is_empty()) {
+            self.watches.remove(&ptr.alloc_id);
+        }
+        removed
+    }
+
+    /// Remove all watchpoints
+    pub fn clear_watches(&mut self) {
+        self.watches.clear();
+    }
+
+    /// Notify the machine if the given access overlaps any watched range.
+    /// `tcx.span` is the span of the statement currently being evaluated.
+    fn check_watches(&self, ptr: Pointer, size: Size, access: MemoryAccess) -> EvalResult<'tcx> {
+        if self.watches.is_empty() {
+            return Ok(());
+        }
+        let ranges = match self.watches.get(&ptr.alloc_id) {
+            Some(ranges) => ranges,
+            None => return Ok(()),
+        };
+        let start = ptr.offset;
+        let end = start + size;
+        for &(watch_start, watch_size) in ranges {
+            if start < watch_start + watch_size && watch_start < end {
+                M::memory_watch_triggered(self, access, ptr, size, self.tcx.span)?;
+                // Report each access only once, even if it overlaps several watches
+                break;
+            }
+        }
+        Ok(())
+    }
+
     pub fn create_fn_alloc(&mut self, instance: Instance<'tcx>) -> Pointer {
         self.tcx.alloc_map.lock().create_fn_alloc(instance).into()
     }
@@ -310,15 +319,19 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> Memory<'a, 'mir, 'tcx, M> {
             return err!(AlignmentCheckFailed {
                 has: alloc_align,
                 required: required_align,
+                ptr,
             });
         }
         if offset % required_align.abi() == 0 {
             Ok(())
         } else {
-            let has = offset % required_align.abi();
+            // The alignment the pointer actually has is the largest power of two dividing
+            // its offset (the allocation itself is aligned enough, as checked above).
+            let has = 1 << offset.trailing_zeros();
             err!(AlignmentCheckFailed {
                 has: Align::from_bytes(has, has).unwrap(),
                 required: required_align,
+                ptr,
             })
         }
     }
@@ -352,6 +365,15 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> Memory<'a, 'mir, 'tcx, M> {
         }
     }
 
+    /// Returns the allocation with the given id, and its kind, if it is local to this
+    /// instance of the engine, i.e. not a static that was never written to.
+    pub(super) fn get_local(
+        &self,
+        id: AllocId,
+    ) -> Option<&(MemoryKind<M::MemoryKinds>, Allocation)> {
+        self.alloc_map.get(&id)
+    }
+
     pub fn get_mut(
         &mut self,
         id: AllocId,
@@ -512,6 +534,7 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> Memory<'a, 'mir, 'tcx, M> {
         }
         // if ptr.offset is in bounds, then so is ptr (because offset checks for overflow)
         self.check_bounds(ptr.offset(size, self)?, true)?;
+        self.check_watches(ptr, size, MemoryAccess::Read)?;
         let alloc = self.get(ptr.alloc_id)?;
         assert_eq!(ptr.offset.bytes() as usize as u64, ptr.offset.bytes());
         assert_eq!(size.bytes() as usize as u64, size.bytes());
@@ -534,6 +557,7 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> Memory<'a, 'mir, 'tcx, M> {
         }
         // if ptr.offset is in bounds, then so is ptr (because offset checks for overflow)
         self.check_bounds(ptr.offset(size, &*self)?, true)?;
+        self.check_watches(ptr, size, MemoryAccess::Write)?;
         let alloc = self.get_mut(ptr.alloc_id)?;
         assert_eq!(ptr.offset.bytes() as usize as u64, ptr.offset.bytes());
         assert_eq!(size.bytes() as usize as u64, size.bytes());
@@ -661,8 +685,8 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> Memory<'a, 'mir, 'tcx, M> {
                     relocations
                     .iter()
                     .map(|&(offset, alloc_id)| {
-                    (offset + dest.offset - src.offset + (i * size * relocations.len() as u64),
-                    alloc_id)
+                    // The `i`th copy starts `i * size` bytes after `dest`.
+                    (offset + dest.offset - src.offset + (i * size), alloc_id)
                     })
                 );
             }
@@ -679,17 +703,23 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> Memory<'a, 'mir, 'tcx, M> {
         // `dest` could possibly overlap.
         unsafe {
             assert_eq!(size.bytes() as usize as u64, size.bytes());
-            if src.alloc_id == dest.alloc_id {
-                if nonoverlapping {
-                    if (src.offset <= dest.offset && src.offset + size > dest.offset) ||
-                        (dest.offset <= src.offset && dest.offset + size > src.offset)
-                    {
-                        return err!(Intrinsic(
-                            "copy_nonoverlapping called on overlapping ranges".to_string(),
-                        ));
-                    }
+            if src.alloc_id == dest.alloc_id && nonoverlapping {
+                if (src.offset <= dest.offset && src.offset + size > dest.offset) ||
+                    (dest.offset <= src.offset && dest.offset + size > src.offset)
+                {
+                    return err!(Intrinsic(
+                        "copy_nonoverlapping called on overlapping ranges".to_string(),
+                    ));
                 }
+            }
 
+            // Like `write_repeat`, leave the pages of zeroed memory alone if all we would
+            // store there are zeros.
+            let src_slice = slice::from_raw_parts(src_bytes, size.bytes() as usize);
+            let dest_slice = slice::from_raw_parts(dest_bytes, (size * length).bytes() as usize);
+            if src_slice.iter().all(|&b| b == 0) && dest_slice.iter().all(|&b| b == 0) {
+                // nothing to copy
+            } else if src.alloc_id == dest.alloc_id {
                 for i in 0..length {
                     ptr::copy(src_bytes,
                               dest_bytes.offset((size.bytes() * i) as isize),
@@ -759,7 +789,11 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> Memory<'a, 'mir, 'tcx, M> {
         }
         let bytes = self.get_bytes_mut(ptr.to_ptr()?, count, align)?;
         for b in bytes {
-            *b = val;
+            // Only store bytes that change, so the pages of a fresh (zeroed) allocation that
+            // is filled with zeros are never touched, and the allocator can leave them unmapped.
+            if *b != val {
+                *b = val;
+            }
         }
         Ok(())
     }
@@ -938,7 +972,6 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> Memory<'a, 'mir, 'tcx, M> {
 
 /// Undefined bytes
 impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> Memory<'a, 'mir, 'tcx, M> {
-    // FIXME(solson): This is a very naive, slow version.
     fn copy_undef_mask(
         &mut self,
         src: Pointer,
@@ -946,19 +979,29 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> Memory<'a, 'mir, 'tcx, M> {
         size: Size,
         repeat: u64,
     ) -> EvalResult<'tcx> {
-        // The bits have to be saved locally before writing to dest in case src and dest overlap.
+        // The runs have to be saved locally before writing to dest in case src and dest overlap.
         assert_eq!(size.bytes() as usize as u64, size.bytes());
 
-        let undef_mask = self.get(src.alloc_id)?.undef_mask.clone();
+        let runs = self.get(src.alloc_id)?.undef_mask.runs(src.offset, src.offset + size);
         let dest_allocation = self.get_mut(dest.alloc_id)?;
 
-        for i in 0..size.bytes() {
-            let defined = undef_mask.get(src.offset + Size::from_bytes(i));
+        if let [(_, _, defined)] = runs[..] {
+            // Uniform definedness, so all copies can be set at once.
+            dest_allocation.undef_mask.set_range_inbounds(
+                dest.offset,
+                dest.offset + size * repeat,
+                defined,
+            );
+            return Ok(());
+        }
 
-            for j in 0..repeat {
-                dest_allocation.undef_mask.set(
-                    dest.offset + Size::from_bytes(i + (size.bytes() * j)),
-                    defined
+        for j in 0..repeat {
+            let copy_start = dest.offset + size * j;
+            for &(start, end, defined) in &runs {
+                dest_allocation.undef_mask.set_range_inbounds(
+                    copy_start + (start - src.offset),
+                    copy_start + (end - src.offset),
+                    defined,
                 );
             }
         }
@@ -9,6 +9,13 @@
 // except according to those terms.
 
 //! An interpreter for MIR used in CTFE and by miri
+//!
+//! Everything re-exported from this module forms the interface against which machines other
+//! than the compile-time evaluator (most notably miri) are written. That interface is
+//! perma-unstable and may change with any rustc release, but it is intended to be sufficient
+//! for such machines to live out of tree: the `Machine` trait hooks, together with the
+//! `EvalContext`, `Memory`, place and operand helpers exported here, should be all an
+//! external machine needs.
 
 mod cast;
 mod eval_context;
@@ -18,6 +25,7 @@ mod machine;
 mod memory;
 mod operator;
 mod step;
+mod snapshot;
 mod terminator;
 mod traits;
 mod validity;
@@ -29,12 +37,21 @@ pub use self::eval_context::{
 
 pub use self::place::{Place, PlaceTy, MemPlace, MPlaceTy};
 
-pub use self::memory::{Memory, MemoryKind};
+pub use self::memory::{Memory, MemoryKind, MemoryAccess};
 
-pub use self::machine::Machine;
+pub use self::machine::{Machine, FnCall, UnwindPolicy};
 
 pub use self::operand::{Value, ValTy, Operand, OpTy};
 
+pub use self::validity::PathElem;
+
+// The value and allocation types of the engine live in `rustc` so that they can be part of
+// the query system; re-export them so that machines only need to depend on this module.
+pub use rustc::mir::interpret::{
+    EvalError, EvalResult, EvalErrorKind, Scalar, ScalarMaybeUndef, Pointer, AllocId,
+    Allocation, GlobalId, PointerArithmetic,
+};
+
 // reexports for compatibility
 pub use const_eval::{
     eval_promoted,
@@ -163,6 +163,13 @@ impl<'tcx> From<MPlaceTy<'tcx>> for OpTy<'tcx> {
     }
 }
 
+impl<'tcx> ValTy<'tcx> {
+    #[inline]
+    pub fn from_scalar(val: Scalar, layout: TyLayout<'tcx>) -> Self {
+        ValTy { value: Value::Scalar(val.into()), layout }
+    }
+}
+
 impl<'tcx> From<ValTy<'tcx>> for OpTy<'tcx> {
     #[inline(always)]
     fn from(val: ValTy<'tcx>) -> Self {
@@ -406,6 +413,40 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
         Ok(self.ref_to_mplace(val)?)
     }
 
+    /// The field index of the state of the generator type `ty`. Generators are laid out as a
+    /// struct containing the upvars, then the state, then the locals that are live across
+    /// a suspension point (see `transform::generator`).
+    pub fn generator_state_field(&self, ty: ty::Ty<'tcx>) -> usize {
+        match ty.sty {
+            ty::Generator(def_id, substs, _) => substs.upvar_tys(def_id, *self.tcx).count(),
+            _ => bug!("generator_state_field: {:?} is not a generator", ty),
+        }
+    }
+
+    /// Read the state of a generator: `0` is unresumed, `1` returned, `2` poisoned, and
+    /// every higher value a suspension point.
+    pub fn read_generator_state(&self, op: OpTy<'tcx>) -> EvalResult<'tcx, u32> {
+        let field = self.generator_state_field(op.layout.ty);
+        let state = self.operand_field(op, field as u64)?;
+        self.read_scalar(state)?.to_u32()
+    }
+
+    /// Project to the `saved_local`th local that the generator keeps alive across
+    /// suspension points.
+    pub fn operand_generator_saved_local(
+        &self,
+        op: OpTy<'tcx>,
+        saved_local: usize,
+    ) -> EvalResult<'tcx, OpTy<'tcx>> {
+        let field = self.generator_state_field(op.layout.ty) + 1 + saved_local;
+        if field >= op.layout.fields.count() {
+            return err!(Unimplemented(format!(
+                "generator {:?} has no saved local {}", op.layout.ty, saved_local,
+            )));
+        }
+        self.operand_field(op, field as u64)
+    }
+
     pub fn operand_projection(
         &self,
         base: OpTy<'tcx>,
@@ -584,7 +625,7 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                     .expect("tagged layout for non adt")
                     .discriminants(self.tcx.tcx)
                     .position(|var| var.val == real_discr)
-                    .ok_or_else(|| EvalErrorKind::InvalidDiscriminant(real_discr))?;
+                    .ok_or_else(|| EvalErrorKind::InvalidDiscriminant(rval.layout.ty, real_discr))?;
                 (real_discr, index)
             },
             layout::Variants::NicheFilling {
@@ -593,32 +634,38 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                 niche_start,
                 ..
             } => {
-                let variants_start = *niche_variants.start() as u128;
-                let variants_end = *niche_variants.end() as u128;
-                let real_discr = match raw_discr {
+                let variants_start = *niche_variants.start();
+                let variants_end = *niche_variants.end();
+                let index = match raw_discr {
                     Scalar::Ptr(_) => {
-                        // The niche must be just 0 (which a pointer value never is)
-                        assert!(niche_start == 0);
-                        assert!(variants_start == variants_end);
-                        dataful_variant as u128
+                        // A pointer is never null, so if the niche is just 0, it can only be a
+                        // value of the dataful variant. Otherwise, we cannot tell.
+                        if niche_start != 0 || variants_start != variants_end {
+                            return err!(ReadPointerAsBytes);
+                        }
+                        dataful_variant
                     },
                     Scalar::Bits { bits: raw_discr, size } => {
                         assert_eq!(size as u64, discr_val.layout.size.bytes());
-                        let discr = raw_discr.wrapping_sub(niche_start)
-                            .wrapping_add(variants_start);
-                        if variants_start <= discr && discr <= variants_end {
-                            discr
+                        // The niche values are `niche_start..` for `niche_variants`, which may
+                        // wrap around at the size of the niche field
+                        let relative = self.truncate(
+                            raw_discr.wrapping_sub(niche_start),
+                            discr_val.layout,
+                        );
+                        if relative <= (variants_end - variants_start) as u128 {
+                            variants_start + relative as usize
                         } else {
-                            dataful_variant as u128
+                            dataful_variant
                         }
                     },
                 };
-                let index = real_discr as usize;
-                assert_eq!(index as u128, real_discr);
-                assert!(index < rval.layout.ty
-                    .ty_adt_def()
-                    .expect("tagged layout for non adt")
-                    .variants.len());
+                let adt_def = rval.layout.ty.ty_adt_def().expect("niche layout for non adt");
+                if index >= adt_def.variants.len() {
+                    return err!(InvalidDiscriminant(rval.layout.ty, index as u128));
+                }
+                // The logical discriminant, which may differ from the variant index
+                let real_discr = adt_def.discriminant_for_variant(*self.tcx, index).val;
                 (real_discr, index)
             }
         })
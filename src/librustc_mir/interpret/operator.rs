@@ -9,14 +9,18 @@
 // except according to those terms.
 
 use rustc::mir;
-use rustc::ty::{self, layout::TyLayout};
+use rustc::ty::{self, Ty, layout::TyLayout};
 use syntax::ast::FloatTy;
 use rustc_apfloat::ieee::{Double, Single};
-use rustc_apfloat::Float;
-use rustc::mir::interpret::{EvalResult, Scalar};
+use rustc_apfloat::{Float, Status};
+use rustc::mir::interpret::{EvalResult, EvalErrorKind, Scalar, Pointer};
 
-use super::{EvalContext, PlaceTy, Value, Machine, ValTy};
+use super::{EvalContext, OpTy, PlaceTy, Value, Machine, ValTy};
 
+/// The result of a `Cmp` operation: an `Ordering`, which is represented as an `i8`.
+fn ordering_scalar(ord: ::std::cmp::Ordering) -> Scalar {
+    Scalar::Bits { bits: ord as i8 as u8 as u128, size: 1 }
+}
 
 impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
     /// Applies the binary operation `op` to the two operands and writes a tuple of the result
@@ -28,13 +32,14 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
         right: ValTy<'tcx>,
         dest: PlaceTy<'tcx>,
     ) -> EvalResult<'tcx> {
-        let (val, overflowed) = self.binary_op(op, left, right)?;
+        let (val, overflowed, _) = self.checked_binary_op(op, left, right)?;
         let val = Value::ScalarPair(val.into(), Scalar::from_bool(overflowed).into());
         self.write_value(val, dest)
     }
 
     /// Applies the binary operation `op` to the arguments and writes the result to the
-    /// destination.
+    /// destination. If the operation overflows, the machine decides whether the wrapped
+    /// result is used, see `Machine::unchecked_overflow`.
     pub fn binop_ignore_overflow(
         &mut self,
         op: mir::BinOp,
@@ -42,8 +47,112 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
         right: ValTy<'tcx>,
         dest: PlaceTy<'tcx>,
     ) -> EvalResult<'tcx> {
-        let (val, _overflowed) = self.binary_op(op, left, right)?;
-        self.write_scalar(val, dest)
+        let (val, overflowed) = self.binary_op(op, left, right)?;
+        if overflowed {
+            if (op == mir::BinOp::Shl || op == mir::BinOp::Shr) && M::SHIFT_OVERFLOW_IS_UB {
+                return self.shift_overflow(left, right);
+            }
+            M::unchecked_overflow(self, op)?;
+        }
+        self.write_value(val.value, dest)
+    }
+
+    /// Reports that shifting `left` by `right` overflowed, i.e. that the shift amount is
+    /// negative or not smaller than the width of `left`.
+    pub(super) fn shift_overflow<T>(
+        &self,
+        left: ValTy<'tcx>,
+        right: ValTy<'tcx>,
+    ) -> EvalResult<'tcx, T> {
+        let amount = right.to_scalar()?.to_bits(right.layout.size)?;
+        let signed = right.layout.abi.is_signed();
+        let amount = if signed { self.sign_extend(amount, right.layout) } else { amount };
+        let bits = left.layout.size.bits();
+        err!(ShiftOverflow { amount, signed, bits })
+    }
+
+    /// Applies the binary operation `op` to each pair of lanes of two `#[repr(simd)]` vectors
+    /// and writes the resulting vector to the destination. Lanes that overflow wrap around.
+    /// Comparisons set a lane of the destination to all ones if they hold, and to zero
+    /// otherwise.
+    pub fn binary_simd_op(
+        &mut self,
+        op: mir::BinOp,
+        left: OpTy<'tcx>,
+        right: OpTy<'tcx>,
+        dest: PlaceTy<'tcx>,
+    ) -> EvalResult<'tcx> {
+        assert!(left.layout.ty.is_simd() && right.layout.ty.is_simd() && dest.layout.ty.is_simd());
+        let len = left.layout.ty.simd_size(*self.tcx);
+        assert_eq!(len, right.layout.ty.simd_size(*self.tcx));
+        assert_eq!(len, dest.layout.ty.simd_size(*self.tcx));
+
+        for i in 0..len as u64 {
+            let l = self.operand_field(left, i)?;
+            let l = self.read_value(l)?;
+            let r = self.operand_field(right, i)?;
+            let r = self.read_value(r)?;
+            let dest_lane = self.place_field(dest, i)?;
+            let (val, _overflowed) = self.binary_op(op, l, r)?;
+            let val = if val.layout.ty.is_bool() {
+                // A comparison
+                let bits = if val.to_scalar()?.to_bool()? {
+                    self.truncate(u128::max_value(), dest_lane.layout)
+                } else {
+                    0
+                };
+                Scalar::Bits { bits, size: dest_lane.layout.size.bytes() as u8 }
+            } else {
+                val.to_scalar()?
+            };
+            self.write_scalar(val, dest_lane)?;
+        }
+        Ok(())
+    }
+
+    /// Applies the binary operation `op` to the two operands and returns the result, whether
+    /// it overflowed, and the layout of the result. Unlike `binop_with_overflow`, this does
+    /// not need a destination place.
+    pub fn checked_binary_op(
+        &self,
+        op: mir::BinOp,
+        left: ValTy<'tcx>,
+        right: ValTy<'tcx>,
+    ) -> EvalResult<'tcx, (Scalar, bool, TyLayout<'tcx>)> {
+        let (val, overflowed) = self.binary_op(op, left, right)?;
+        Ok((val.to_scalar()?, overflowed, val.layout))
+    }
+
+    /// Applies `Add` or `Sub` to two integer operands, clamping the result to the range of
+    /// their type instead of wrapping around on overflow.
+    pub fn saturating_arith(
+        &self,
+        op: mir::BinOp,
+        left: ValTy<'tcx>,
+        right: ValTy<'tcx>,
+    ) -> EvalResult<'tcx, Scalar> {
+        assert!(op == mir::BinOp::Add || op == mir::BinOp::Sub,
+                "saturating arithmetic is only supported for `Add` and `Sub`, not `{:?}`", op);
+        let (val, overflowed) = self.binary_op(op, left, right)?;
+        if !overflowed {
+            return val.to_scalar();
+        }
+        let layout = left.layout;
+        let size = layout.size;
+        let bits = if layout.abi.is_signed() {
+            // Adding and subtracting can only overflow in the direction of the sign of the
+            // left operand.
+            let l = left.to_scalar()?.to_bits(size)?;
+            let l = self.sign_extend(l, layout) as i128;
+            // The bit patterns of the minimum and the maximum value of the type
+            let min = 1u128 << (size.bits() - 1);
+            if l < 0 { min } else { min - 1 }
+        } else if op == mir::BinOp::Add {
+            self.truncate(u128::max_value(), layout)
+        } else {
+            0
+        };
+        Ok(Scalar::Bits { bits, size: size.bytes() as u8 })
     }
 }
 
@@ -63,7 +172,8 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
             Le => l <= r,
             Gt => l > r,
             Ge => l >= r,
-            _ => bug!("Invalid operation on char: {:?}", bin_op),
+            Cmp => return Ok((ordering_scalar(l.cmp(&r)), false)),
+            _ => return err!(InvalidCharOp(bin_op)),
         };
         return Ok((Scalar::from_bool(res), false));
     }
@@ -86,7 +196,8 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
             BitAnd => l & r,
             BitOr => l | r,
             BitXor => l ^ r,
-            _ => bug!("Invalid operation on bool: {:?}", bin_op),
+            Cmp => return Ok((ordering_scalar(l.cmp(&r)), false)),
+            _ => return err!(InvalidBoolOp(bin_op)),
         };
         return Ok((Scalar::from_bool(res), false));
     }
@@ -105,24 +216,29 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
             ($ty:path, $size:expr) => {{
                 let l = <$ty>::from_bits(l);
                 let r = <$ty>::from_bits(r);
-                let bitify = |res: ::rustc_apfloat::StatusAnd<$ty>| Scalar::Bits {
-                    bits: res.value.to_bits(),
+                let bitify = |res: ::rustc_apfloat::StatusAnd<$ty>| (Scalar::Bits {
+                    bits: self.adjust_nan(res.value, &[l, r]).to_bits(),
                     size: $size,
-                };
-                let val = match bin_op {
-                    Eq => Scalar::from_bool(l == r),
-                    Ne => Scalar::from_bool(l != r),
-                    Lt => Scalar::from_bool(l < r),
-                    Le => Scalar::from_bool(l <= r),
-                    Gt => Scalar::from_bool(l > r),
-                    Ge => Scalar::from_bool(l >= r),
+                }, res.status);
+                let (val, status) = match bin_op {
+                    Eq => (Scalar::from_bool(l == r), Status::OK),
+                    Ne => (Scalar::from_bool(l != r), Status::OK),
+                    Lt => (Scalar::from_bool(l < r), Status::OK),
+                    Le => (Scalar::from_bool(l <= r), Status::OK),
+                    Gt => (Scalar::from_bool(l > r), Status::OK),
+                    Ge => (Scalar::from_bool(l >= r), Status::OK),
                     Add => bitify(l + r),
                     Sub => bitify(l - r),
                     Mul => bitify(l * r),
                     Div => bitify(l / r),
+                    // This is C `fmod` (LLVM `frem`), which is what `%` compiles to at runtime:
+                    // the result has the sign of `l`, and is NaN if `l` is infinite or `r` is zero.
                     Rem => bitify(l % r),
                     _ => bug!("invalid float op: `{:?}`", bin_op),
                 };
+                if status != Status::OK {
+                    M::float_op_status(self, bin_op, status)?;
+                }
                 return Ok((val, false));
             }};
         }
@@ -132,6 +248,16 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
         }
     }
 
+    /// Lets the machine pick the NaN to use if `res`, the result of a float operation on
+    /// `inputs`, is a NaN.
+    pub(super) fn adjust_nan<F: Float>(&self, res: F, inputs: &[F]) -> F {
+        if res.is_nan() {
+            M::float_nan(self, res, inputs)
+        } else {
+            res
+        }
+    }
+
     fn binary_int_op(
         &self,
         bin_op: mir::BinOp,
@@ -175,8 +301,19 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
             }, oflo));
         }
 
-        // For the remaining ops, the types must be the same on both sides
+        // For the remaining ops, the types must be the same on both sides, except for
+        // comparisons and bitwise ops on integers that only differ in their width
         if left_layout.ty != right_layout.ty {
+            let widenable = left_layout.ty.is_integral() && right_layout.ty.is_integral() &&
+                left_layout.abi.is_signed() == right_layout.abi.is_signed();
+            match bin_op {
+                Eq | Ne | Lt | Le | Gt | Ge | Cmp | BitAnd | BitOr | BitXor if widenable => {
+                    return self.binary_mixed_width_int_op(
+                        bin_op, l, left_layout, r, right_layout,
+                    );
+                }
+                _ => {}
+            }
             let msg = format!(
                 "unimplemented asymmetric binary op {:?}: {:?} ({:?}), {:?} ({:?})",
                 bin_op,
@@ -202,6 +339,11 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                 let r = self.sign_extend(r, right_layout) as i128;
                 return Ok((Scalar::from_bool(op(&l, &r)), false));
             }
+            if bin_op == Cmp {
+                let l = self.sign_extend(l, left_layout) as i128;
+                let r = self.sign_extend(r, right_layout) as i128;
+                return Ok((ordering_scalar(l.cmp(&r)), false));
+            }
             let op: Option<fn(i128, i128) -> (i128, bool)> = match bin_op {
                 Div if r == 0 => return err!(DivisionByZero),
                 Rem if r == 0 => return err!(RemainderByZero),
@@ -213,25 +355,20 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                 _ => None,
             };
             if let Some(op) = op {
-                let l128 = self.sign_extend(l, left_layout) as i128;
+                let l = self.sign_extend(l, left_layout) as i128;
                 let r = self.sign_extend(r, right_layout) as i128;
                 let size = left_layout.size;
-                match bin_op {
-                    Rem | Div => {
-                        // int_min / -1
-                        if r == -1 && l == (1 << (size.bits() - 1)) {
-                            return Ok((Scalar::Bits { bits: l, size: size.bytes() as u8 }, true));
-                        }
-                    },
-                    _ => {},
-                }
-                trace!("{}, {}, {}", l, l128, r);
-                let (result, mut oflo) = op(l128, r);
+                // The smallest and largest values of the type
+                let min = i128::min_value() >> (128 - size.bits());
+                let max = !min;
+                trace!("{}, {}", l, r);
+                // For types narrower than 128 bits, `op` computes the exact result, which
+                // overflowed if it does not fit the type
+                let (result, oflo) = op(l, r);
                 trace!("{}, {}", result, oflo);
-                if !oflo && size.bits() != 128 {
-                    let max = 1 << (size.bits() - 1);
-                    oflo = result >= max || result < -max;
-                }
+                // `int_min % -1` overflows as well, even though the result, 0, fits the type
+                let oflo = oflo || result < min || result > max ||
+                    ((bin_op == Div || bin_op == Rem) && r == -1 && l == min);
                 let result = result as u128;
                 let truncated = self.truncate(result, left_layout);
                 return Ok((Scalar::Bits {
@@ -252,6 +389,7 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
             Le => Scalar::from_bool(l <= r),
             Gt => Scalar::from_bool(l > r),
             Ge => Scalar::from_bool(l >= r),
+            Cmp => ordering_scalar(l.cmp(&r)),
 
             BitOr => Scalar::Bits { bits: l | r, size },
             BitAnd => Scalar::Bits { bits: l & r, size },
@@ -291,13 +429,113 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
         Ok((val, false))
     }
 
-    /// Returns the result of the specified operation and whether it overflowed.
+    /// Applies a comparison or bitwise operation to two integers of the same signedness but
+    /// different widths, by extending both to 128 bits first. The result of a bitwise
+    /// operation has the type of the left operand.
+    fn binary_mixed_width_int_op(
+        &self,
+        bin_op: mir::BinOp,
+        // passing in raw bits
+        l: u128,
+        left_layout: TyLayout<'tcx>,
+        r: u128,
+        right_layout: TyLayout<'tcx>,
+    ) -> EvalResult<'tcx, (Scalar, bool)> {
+        use rustc::mir::BinOp::*;
+        use std::cmp::Ordering::*;
+
+        let signed = left_layout.abi.is_signed();
+        // Unsigned values are already zero-extended
+        let (l, r) = if signed {
+            (self.sign_extend(l, left_layout), self.sign_extend(r, right_layout))
+        } else {
+            (l, r)
+        };
+        let ord = if signed { (l as i128).cmp(&(r as i128)) } else { l.cmp(&r) };
+        let bitwise = |bits: u128| Scalar::Bits {
+            bits: self.truncate(bits, left_layout),
+            size: left_layout.size.bytes() as u8,
+        };
+        let val = match bin_op {
+            Eq => Scalar::from_bool(ord == Equal),
+            Ne => Scalar::from_bool(ord != Equal),
+            Lt => Scalar::from_bool(ord == Less),
+            Le => Scalar::from_bool(ord != Greater),
+            Gt => Scalar::from_bool(ord == Greater),
+            Ge => Scalar::from_bool(ord != Less),
+            Cmp => ordering_scalar(ord),
+            BitAnd => bitwise(l & r),
+            BitOr => bitwise(l | r),
+            BitXor => bitwise(l ^ r),
+            _ => bug!("invalid mixed-width integer op: {:?}", bin_op),
+        };
+        Ok((val, false))
+    }
+
+    /// Rotates the bits of the integer `l` to the left, or to the right if `left` is false, by
+    /// `r` modulo the width of its type. Unlike shifts, rotations never overflow.
+    pub fn rotate_int(
+        &self,
+        // passing in raw bits
+        l: u128,
+        r: u128,
+        layout: TyLayout<'tcx>,
+        left: bool,
+    ) -> Scalar {
+        let width = layout.size.bits() as u128;
+        let r = r % width;
+        let res = if r == 0 {
+            l
+        } else if left {
+            (l << r) | (l >> (width - r))
+        } else {
+            (l >> r) | (l << (width - r))
+        };
+        Scalar::Bits {
+            bits: self.truncate(res, layout),
+            size: layout.size.bytes() as u8,
+        }
+    }
+
+    /// Returns the result of the specified operation, together with its layout: `bool` for
+    /// comparisons, `Ordering` for `Cmp` and the type of the left operand otherwise. Also
+    /// returns whether the operation overflowed.
     pub fn binary_op(
+        &self,
+        bin_op: mir::BinOp,
+        left: ValTy<'tcx>,
+        right: ValTy<'tcx>,
+    ) -> EvalResult<'tcx, (ValTy<'tcx>, bool)> {
+        use rustc::mir::BinOp::*;
+
+        let (val, overflowed) = self.binary_scalar_op(bin_op, left, right)?;
+        // Not `BinOp::ty`, which insists on both operands having the same type even where
+        // integers of different widths are fine.
+        let ty = match bin_op {
+            Eq | Ne | Lt | Le | Gt | Ge => self.tcx.types.bool,
+            Cmp => self.tcx.mk_ordering(),
+            _ => left.layout.ty,
+        };
+        Ok((ValTy::from_scalar(val, self.layout_of(ty)?), overflowed))
+    }
+
+    /// Returns the result of the specified operation and whether it overflowed.
+    fn binary_scalar_op(
         &self,
         bin_op: mir::BinOp,
         ValTy { value: left, layout: left_layout }: ValTy<'tcx>,
         ValTy { value: right, layout: right_layout }: ValTy<'tcx>,
     ) -> EvalResult<'tcx, (Scalar, bool)> {
+        if let (Value::ScalarPair(l_ptr, l_meta), Value::ScalarPair(r_ptr, r_meta)) =
+            (left, right)
+        {
+            return self.binary_fat_ptr_op(
+                bin_op,
+                (l_ptr.not_undef()?, l_meta.not_undef()?),
+                (r_ptr.not_undef()?, r_meta.not_undef()?),
+            );
+        }
+
         let left = left.to_scalar()?;
         let right = right.to_scalar()?;
 
@@ -330,6 +568,42 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                 assert!(right_layout.ty.is_integral() || right_layout.ty.is_unsafe_ptr() ||
                     right_layout.ty.is_fn());
 
+                if bin_op == mir::BinOp::Offset {
+                    let pointee_ty = left_layout.ty
+                        .builtin_deref(true)
+                        .expect("Offset called on non-ptr type")
+                        .ty;
+                    let offset = right.to_bits(right_layout.size)?;
+                    let offset = self.sign_extend(offset, right_layout) as i128 as i64;
+                    let ptr = self.pointer_offset_inbounds(left, pointee_ty, offset)?;
+                    return Ok((ptr, false));
+                }
+
+                if bin_op == mir::BinOp::Eq || bin_op == mir::BinOp::Ne {
+                    // Pointers into the same allocation are equal if their offsets are, the
+                    // machine decides about pointers into different allocations
+                    if let (Scalar::Ptr(l), Scalar::Ptr(r)) = (left, right) {
+                        let eq = if l.alloc_id == r.alloc_id {
+                            l.offset == r.offset
+                        } else {
+                            M::ptr_eq_cross_alloc(self, l, r)?
+                        };
+                        return Ok((Scalar::from_bool((bin_op == mir::BinOp::Eq) == eq), false));
+                    }
+
+                    // Comparisons between a pointer and an integer are up to the machine
+                    let ptr_int = match (left, right) {
+                        (Scalar::Ptr(ptr), Scalar::Bits { bits, .. }) |
+                        (Scalar::Bits { bits, .. }, Scalar::Ptr(ptr)) => Some((ptr, bits)),
+                        _ => None,
+                    };
+                    if let Some((ptr, int)) = ptr_int {
+                        if let Some(eq) = M::ptr_int_eq(self, ptr, int)? {
+                            return Ok((Scalar::from_bool((bin_op == mir::BinOp::Eq) == eq), false));
+                        }
+                    }
+                }
+
                 // Handle operations that support pointer values
                 if let Some(handled) =
                     M::try_ptr_op(self, bin_op, left, left_layout, right, right_layout)?
@@ -345,7 +619,96 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
         }
     }
 
+    /// Compares two fat pointers, e.g. `&[T]` or `&dyn Trait`. They are equal if both their
+    /// data pointers and their metadata are equal.
+    fn binary_fat_ptr_op(
+        &self,
+        bin_op: mir::BinOp,
+        (left_ptr, left_meta): (Scalar, Scalar),
+        (right_ptr, right_meta): (Scalar, Scalar),
+    ) -> EvalResult<'tcx, (Scalar, bool)> {
+        let eq = match bin_op {
+            mir::BinOp::Eq => true,
+            mir::BinOp::Ne => false,
+            _ => {
+                let msg = format!("unimplemented binary op on fat pointers: {:?}", bin_op);
+                return err!(Unimplemented(msg));
+            }
+        };
+        let equal = self.thin_ptr_eq(left_ptr, right_ptr)? &&
+            self.thin_ptr_eq(left_meta, right_meta)?;
+        Ok((Scalar::from_bool(equal == eq), false))
+    }
+
+    /// Compares two pointer-sized scalars, which may be pointers, lengths or vtables.
+    fn thin_ptr_eq(&self, left: Scalar, right: Scalar) -> EvalResult<'tcx, bool> {
+        if let Some(eq) = self.guaranteed_ptr_eq(left, right)? {
+            return Ok(eq);
+        }
+        // Everything else is up to the machine, just like for thin raw pointers
+        let layout = self.layout_of(self.tcx.mk_imm_ptr(self.tcx.types.u8))?;
+        let (res, _) = self.binary_scalar_op(
+            mir::BinOp::Eq,
+            ValTy { value: Value::Scalar(left.into()), layout },
+            ValTy { value: Value::Scalar(right.into()), layout },
+        )?;
+        res.to_bool()
+    }
+
+    /// Compares a pointer with an integer, if the result does not depend on where the
+    /// allocation of the pointer is placed in memory. Returns `None` if it does.
+    pub fn ptr_int_eq_known(&self, ptr: Pointer, int: u128) -> EvalResult<'tcx, Option<bool>> {
+        let offset = ptr.offset.bytes();
+        let (size, align) = match self.memory.get(ptr.alloc_id) {
+            Ok(alloc) => (alloc.bytes.len() as u64, alloc.align.abi()),
+            // Functions and dead allocations: still not at address 0, but nothing else is known
+            Err(_) => (0, 1),
+        };
+        // Allocations start at a non-zero multiple of their alignment, and the address of an
+        // in-bounds pointer does not wrap around, so it is at least `align + offset`.
+        if offset <= size && int < (align + offset) as u128 {
+            return Ok(Some(false));
+        }
+        // Whatever the address is, it has the same remainder modulo the alignment as the offset.
+        if int % align as u128 != (offset % align) as u128 {
+            return Ok(Some(false));
+        }
+        Ok(None)
+    }
+
+    /// Offsets `ptr` by `offset` elements of type `pointee_ty`, with the semantics of the
+    /// `offset` intrinsic: the result must be in-bounds of the allocation `ptr` points into,
+    /// or point one past its end.
+    pub fn pointer_offset_inbounds(
+        &self,
+        ptr: Scalar,
+        pointee_ty: Ty<'tcx>,
+        offset: i64,
+    ) -> EvalResult<'tcx, Scalar> {
+        let pointee_size = self.layout_of(pointee_ty)?.size.bytes() as i64;
+        let offset = offset
+            .checked_mul(pointee_size)
+            .ok_or_else(|| EvalErrorKind::Overflow(mir::BinOp::Mul))?;
+        // Offsetting by zero is a no-op, even for dangling or integer pointers
+        if offset == 0 {
+            return Ok(ptr);
+        }
+        let ptr = ptr.ptr_signed_offset(offset, self)?;
+        self.memory.check_bounds(ptr.to_ptr()?, false)?;
+        Ok(ptr)
+    }
+
+    /// Returns the result of the specified operation, which has the type of the operand.
     pub fn unary_op(
+        &self,
+        un_op: mir::UnOp,
+        val: ValTy<'tcx>,
+    ) -> EvalResult<'tcx, ValTy<'tcx>> {
+        let res = self.unary_scalar_op(un_op, val.to_scalar()?, val.layout)?;
+        Ok(ValTy::from_scalar(res, val.layout))
+    }
+
+    fn unary_scalar_op(
         &self,
         un_op: mir::UnOp,
         val: Scalar,
@@ -362,16 +725,22 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                 let val = val.to_bool()?;
                 let res = match un_op {
                     Not => !val,
-                    _ => bug!("Invalid bool op {:?}", un_op)
+                    _ => return err!(InvalidUnaryOp(un_op, layout.ty)),
                 };
                 Ok(Scalar::from_bool(res))
             }
             ty::Float(fty) => {
                 let val = val.to_bits(layout.size)?;
                 let res = match (un_op, fty) {
-                    (Neg, FloatTy::F32) => Single::to_bits(-Single::from_bits(val)),
-                    (Neg, FloatTy::F64) => Double::to_bits(-Double::from_bits(val)),
-                    _ => bug!("Invalid float op {:?}", un_op)
+                    (Neg, FloatTy::F32) => {
+                        let val = Single::from_bits(val);
+                        self.adjust_nan(-val, &[val]).to_bits()
+                    }
+                    (Neg, FloatTy::F64) => {
+                        let val = Double::from_bits(val);
+                        self.adjust_nan(-val, &[val]).to_bits()
+                    }
+                    _ => return err!(InvalidUnaryOp(un_op, layout.ty)),
                 };
                 Ok(Scalar::Bits { bits: res, size: layout.size.bytes() as u8 })
             }
@@ -381,7 +750,9 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                 let res = match un_op {
                     Not => !val,
                     Neg => {
-                        assert!(layout.abi.is_signed());
+                        if !layout.abi.is_signed() {
+                            return err!(InvalidUnaryOp(un_op, layout.ty));
+                        }
                         (-(val as i128)) as u128
                     }
                 };
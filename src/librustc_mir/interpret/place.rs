@@ -138,7 +138,7 @@ impl<'tcx> MPlaceTy<'tcx> {
     }
 
     #[inline]
-    pub(super) fn len(self, cx: impl HasDataLayout) -> EvalResult<'tcx, u64> {
+    pub fn len(self, cx: impl HasDataLayout) -> EvalResult<'tcx, u64> {
         if self.layout.is_unsized() {
             // We need to consult `extra` metadata
             match self.layout.ty.sty {
@@ -230,6 +230,24 @@ impl<'tcx> PlaceTy<'tcx> {
     }
 }
 
+/// Whether `field` of a value with layout `layout` has the same offset, size and immediate
+/// representation as the whole value. This is the case for single-field wrappers such as
+/// `NonNull` or `Wrapping`, which can then be accessed without going through memory.
+fn is_transparent_field(layout: TyLayout, field_layout: TyLayout, field: u64) -> bool {
+    let field = match usize::try_from(field) {
+        Ok(field) if field < layout.fields.count() => field,
+        _ => return false,
+    };
+    if layout.fields.offset(field) != Size::ZERO || layout.size != field_layout.size {
+        return false;
+    }
+    match (&layout.abi, &field_layout.abi) {
+        (layout::Abi::Scalar(..), layout::Abi::Scalar(..)) |
+        (layout::Abi::ScalarPair(..), layout::Abi::ScalarPair(..)) => true,
+        _ => false,
+    }
+}
+
 impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
     /// Take a value, which represents a (thin or fat) reference, and make it a place.
     /// Alignment is just based on the type.  This is the inverse of `MemPlace::to_ref`.
@@ -368,6 +386,11 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
     ) -> EvalResult<'tcx, MPlaceTy<'tcx>> {
         // Downcasts only change the layout
         assert_eq!(base.extra, None);
+        if let ty::Generator(..) = base.layout.ty.sty {
+            // Every suspension state of a generator has the layout of the generator itself:
+            // the locals saved across any suspension point are fields after the state.
+            return Ok(base);
+        }
         Ok(MPlaceTy { layout: base.layout.for_variant(self, variant), ..base })
     }
 
@@ -420,8 +443,16 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
         base: PlaceTy<'tcx>,
         field: u64,
     ) -> EvalResult<'tcx, PlaceTy<'tcx>> {
-        // FIXME: We could try to be smarter and avoid allocation for fields that span the
-        // entire place.
+        // Fast path for newtypes: a field that spans the entire immediate can be accessed
+        // in place, without forcing the local into memory.
+        if let Place::Local { .. } = base.place {
+            // As in `mplace_field`, an array index may not fit into a host `usize`, but then
+            // all fields have the same layout.
+            let field_layout = base.layout.field(self, usize::try_from(field).unwrap_or(0))?;
+            if is_transparent_field(base.layout, field_layout, field) {
+                return Ok(PlaceTy { place: base.place, layout: field_layout });
+            }
+        }
         let mplace = self.force_allocation(base)?;
         Ok(self.mplace_field(mplace, field)?.into())
     }
@@ -436,12 +467,32 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
             Place::Ptr(mplace) =>
                 self.mplace_downcast(MPlaceTy { mplace, layout: base.layout }, variant)?.into(),
             Place::Local { .. } => {
+                if let ty::Generator(..) = base.layout.ty.sty {
+                    // See `mplace_downcast`.
+                    return Ok(base);
+                }
                 let layout = base.layout.for_variant(&self, variant);
                 PlaceTy { layout, ..base }
             }
         })
     }
 
+    /// Project to the `saved_local`th local that the generator in `base` keeps alive across
+    /// suspension points. This lets machines set up and inspect resumed generators.
+    pub fn place_generator_saved_local(
+        &mut self,
+        base: PlaceTy<'tcx>,
+        saved_local: usize,
+    ) -> EvalResult<'tcx, PlaceTy<'tcx>> {
+        let field = self.generator_state_field(base.layout.ty) + 1 + saved_local;
+        if field >= base.layout.fields.count() {
+            return err!(Unimplemented(format!(
+                "generator {:?} has no saved local {}", base.layout.ty, saved_local,
+            )));
+        }
+        self.place_field(base, field as u64)
+    }
+
     /// Project into a place
     pub fn place_projection(
         &mut self,
@@ -554,6 +605,19 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
         &mut self,
         src_val: Value,
         dest: PlaceTy<'tcx>,
+    ) -> EvalResult<'tcx> {
+        self.write_value_no_validate(src_val, dest)?;
+        if M::enforce_validity(self) {
+            self.validate_operand_shallow(self.place_to_op(dest)?)?;
+        }
+        Ok(())
+    }
+
+    /// Write a value to a place, without checking that it is valid at the type of `dest`.
+    fn write_value_no_validate(
+        &mut self,
+        src_val: Value,
+        dest: PlaceTy<'tcx>,
     ) -> EvalResult<'tcx> {
         trace!("write_value: {:?} <- {:?}", *dest, src_val);
         // See if we can avoid an allocation. This is the counterpart to `try_read_value`,
@@ -624,6 +688,20 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
         &mut self,
         src: OpTy<'tcx>,
         dest: PlaceTy<'tcx>,
+    ) -> EvalResult<'tcx> {
+        self.copy_op_no_validate(src, dest)?;
+        if M::enforce_validity(self) {
+            self.validate_operand_shallow(self.place_to_op(dest)?)?;
+        }
+        Ok(())
+    }
+
+    /// Copies the data from an operand to a place, without checking that it is valid at
+    /// the type of `dest`.
+    fn copy_op_no_validate(
+        &mut self,
+        src: OpTy<'tcx>,
+        dest: PlaceTy<'tcx>,
     ) -> EvalResult<'tcx> {
         assert_eq!(src.layout.size, dest.layout.size,
             "Size mismatch when copying!\nsrc: {:#?}\ndest: {:#?}", src, dest);
@@ -634,7 +712,9 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                 // Yay, we got a value that we can write directly.  We write with the
                 // *source layout*, because that was used to load, and if they do not match
                 // this is a transmute we want to support.
-                return self.write_value(src_val, PlaceTy { place: *dest, layout: src.layout }),
+                return self.write_value_no_validate(
+                    src_val, PlaceTy { place: *dest, layout: src.layout }
+                ),
             Err(mplace) => mplace.to_scalar_ptr_align(),
         };
         // Slow path, this does not fit into an immediate. Just memcpy.
@@ -647,6 +727,32 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
         )
     }
 
+    /// Copies the data from an operand to a place of a possibly different type, but
+    /// of the same size. The bytes are reinterpreted at the destination type.
+    pub fn copy_op_transmute(
+        &mut self,
+        src: OpTy<'tcx>,
+        dest: PlaceTy<'tcx>,
+    ) -> EvalResult<'tcx> {
+        assert_eq!(src.layout.size, dest.layout.size,
+            "Size mismatch when transmuting!\nsrc: {:#?}\ndest: {:#?}", src, dest);
+        if src.layout.abi == dest.layout.abi {
+            // Values of this `Abi` can be written at either type.
+            self.copy_op_no_validate(src, dest)?;
+        } else {
+            // Otherwise go through memory, so that reading the local later
+            // does not find a value of the wrong shape.
+            let mplace = self.force_allocation(dest)?;
+            let dest_mem = MPlaceTy { mplace: *mplace, layout: src.layout };
+            self.copy_op_no_validate(src, dest_mem.into())?;
+        }
+        // The bytes have to make sense at the type they are reinterpreted as.
+        if M::enforce_validity(self) {
+            self.validate_operand_shallow(self.place_to_op(dest)?)?;
+        }
+        Ok(())
+    }
+
     /// Make sure that a place is in memory, and return where it is.
     /// This is essentially `force_to_memplace`.
     pub fn force_allocation(
@@ -731,8 +837,10 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                 if variant_index != dataful_variant {
                     let niche_dest =
                         self.place_field(dest, 0)?;
+                    // The niche values may wrap around at the size of the niche field
                     let niche_value = ((variant_index - niche_variants.start()) as u128)
                         .wrapping_add(niche_start);
+                    let niche_value = self.truncate(niche_value, niche_dest.layout);
                     self.write_scalar(Scalar::Bits {
                         bits: niche_value,
                         size: niche_dest.layout.size.bytes() as u8,
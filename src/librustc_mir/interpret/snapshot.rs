@@ -0,0 +1,360 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Snapshots of the interpreter state, used to detect infinite loops during const evaluation.
+//!
+//! Two states are the same if their stacks are the same, and so is all memory reachable from
+//! those stacks. Local allocations are compared by content, not by `AllocId`: a loop that
+//! takes the address of a local in every iteration gets a fresh allocation each time, but
+//! still revisits the same state. Allocations that are no longer reachable are ignored.
+
+use std::hash::{Hash, Hasher};
+
+use rustc::mir::interpret::{
+    AllocId, Allocation, EvalResult, EvalErrorKind, Pointer, Relocations, Scalar,
+    ScalarMaybeUndef,
+};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxHasher};
+
+use super::{
+    Frame, LocalValue, MemPlace, Memory, MemoryKind, Machine, Operand, Place, Value,
+};
+
+impl<'mir, 'tcx: 'mir, Extra: Eq> Eq for Frame<'mir, 'tcx, Extra> {}
+
+impl<'mir, 'tcx: 'mir, Extra: PartialEq> PartialEq for Frame<'mir, 'tcx, Extra> {
+    fn eq(&self, other: &Self) -> bool {
+        let Frame {
+            mir: _,
+            instance,
+            span: _,
+            return_to_block,
+            return_place,
+            locals,
+            block,
+            stmt,
+            extra,
+        } = self;
+
+        // Some of these are constant during evaluation, but are included
+        // anyways for correctness.
+        *instance == other.instance
+            && *return_to_block == other.return_to_block
+            && *return_place == other.return_place
+            && *locals == other.locals
+            && *block == other.block
+            && *stmt == other.stmt
+            && *extra == other.extra
+    }
+}
+
+impl<'mir, 'tcx: 'mir, Extra: Hash> Hash for Frame<'mir, 'tcx, Extra> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let Frame {
+            mir: _,
+            instance,
+            span: _,
+            return_to_block,
+            return_place,
+            locals,
+            block,
+            stmt,
+            extra,
+        } = self;
+
+        instance.hash(state);
+        return_to_block.hash(state);
+        return_place.hash(state);
+        locals.hash(state);
+        block.hash(state);
+        stmt.hash(state);
+        extra.hash(state);
+    }
+}
+
+/// The virtual machine state during const-evaluation at a given point in time.
+///
+/// All `AllocId`s of local allocations are replaced by canonical ones, assigned in the order
+/// in which the allocations are first reached from the stack. `allocations` holds the
+/// reachable local allocations in that same order.
+#[derive(PartialEq, Eq, Hash)]
+struct EvalSnapshot<'mir, 'tcx: 'mir, M: Machine<'mir, 'tcx>> {
+    machine: M,
+    memory_data: M::MemoryData,
+    stack: Vec<Frame<'mir, 'tcx, M::FrameExtra>>,
+    allocations: Vec<(MemoryKind<M::MemoryKinds>, Allocation)>,
+}
+
+impl<'mir, 'tcx, M> EvalSnapshot<'mir, 'tcx, M>
+    where M: Machine<'mir, 'tcx>,
+          'tcx: 'mir,
+{
+    fn new<'a>(
+        machine: &M,
+        stack: &[Frame<'mir, 'tcx, M::FrameExtra>],
+        memory: &Memory<'a, 'mir, 'tcx, M>,
+    ) -> Self {
+        let mut renumbering = AllocRenumbering {
+            memory,
+            ids: FxHashMap::default(),
+            reached: Vec::new(),
+        };
+        let stack = stack.iter().map(|frame| renumbering.frame(frame)).collect();
+
+        // Renumbering an allocation can reach more allocations, which get appended to
+        // `reached`, so this visits everything reachable from the stack.
+        let mut allocations = Vec::new();
+        loop {
+            let id = match renumbering.reached.get(allocations.len()) {
+                Some(&id) => id,
+                None => break,
+            };
+            let &(kind, ref alloc) = memory.get_local(id)
+                .expect("only local allocations are renumbered");
+            allocations.push((kind, renumbering.allocation(alloc)));
+        }
+
+        EvalSnapshot {
+            machine: machine.clone(),
+            memory_data: memory.data.clone(),
+            stack,
+            allocations,
+        }
+    }
+
+    /// Hashes the state that `new` would take a snapshot of, without copying the stack or
+    /// any memory. Equal snapshots have equal hashes.
+    fn hash_of<'a>(
+        machine: &M,
+        stack: &[Frame<'mir, 'tcx, M::FrameExtra>],
+        memory: &Memory<'a, 'mir, 'tcx, M>,
+    ) -> u64 {
+        let mut renumbering = AllocRenumbering {
+            memory,
+            ids: FxHashMap::default(),
+            reached: Vec::new(),
+        };
+        let mut fx = FxHasher::default();
+        machine.hash(&mut fx);
+        memory.data.hash(&mut fx);
+        for frame in stack {
+            renumbering.hash_frame(frame, &mut fx);
+        }
+
+        // Visit the allocations in the same order as `new`
+        let mut hashed = 0;
+        while let Some(&id) = renumbering.reached.get(hashed) {
+            hashed += 1;
+            let &(kind, ref alloc) = memory.get_local(id)
+                .expect("only local allocations are renumbered");
+            kind.hash(&mut fx);
+            renumbering.hash_allocation(alloc, &mut fx);
+        }
+        fx.finish()
+    }
+}
+
+/// Replaces the `AllocId`s of local allocations with canonical ones. Allocations in `tcx`
+/// cannot change during evaluation, so they keep their `AllocId`.
+struct AllocRenumbering<'b, 'a: 'b, 'mir: 'a, 'tcx: 'a + 'mir, M: Machine<'mir, 'tcx> + 'b> {
+    memory: &'b Memory<'a, 'mir, 'tcx, M>,
+    ids: FxHashMap<AllocId, AllocId>,
+    /// The original ids of the renumbered allocations, in canonical order.
+    reached: Vec<AllocId>,
+}
+
+impl<'b, 'a, 'mir, 'tcx, M> AllocRenumbering<'b, 'a, 'mir, 'tcx, M>
+    where M: Machine<'mir, 'tcx>,
+          'tcx: 'a + 'mir,
+{
+    fn alloc_id(&mut self, id: AllocId) -> AllocId {
+        if self.memory.get_local(id).is_none() {
+            return id;
+        }
+        if let Some(&canonical) = self.ids.get(&id) {
+            return canonical;
+        }
+        // Count down from the top, so canonical ids never clash with those of `tcx` allocations
+        let canonical = AllocId(u64::max_value() - self.reached.len() as u64);
+        self.ids.insert(id, canonical);
+        self.reached.push(id);
+        canonical
+    }
+
+    fn scalar(&mut self, scalar: Scalar) -> Scalar {
+        match scalar {
+            Scalar::Ptr(ptr) => Scalar::Ptr(Pointer {
+                alloc_id: self.alloc_id(ptr.alloc_id),
+                offset: ptr.offset,
+            }),
+            bits @ Scalar::Bits { .. } => bits,
+        }
+    }
+
+    fn scalar_maybe_undef(&mut self, scalar: ScalarMaybeUndef) -> ScalarMaybeUndef {
+        match scalar {
+            ScalarMaybeUndef::Scalar(scalar) => ScalarMaybeUndef::Scalar(self.scalar(scalar)),
+            ScalarMaybeUndef::Undef => ScalarMaybeUndef::Undef,
+        }
+    }
+
+    fn value(&mut self, value: Value) -> Value {
+        match value {
+            Value::Scalar(a) => Value::Scalar(self.scalar_maybe_undef(a)),
+            Value::ScalarPair(a, b) =>
+                Value::ScalarPair(self.scalar_maybe_undef(a), self.scalar_maybe_undef(b)),
+        }
+    }
+
+    fn mem_place(&mut self, place: MemPlace) -> MemPlace {
+        MemPlace {
+            ptr: self.scalar(place.ptr),
+            align: place.align,
+            extra: place.extra.map(|extra| self.scalar(extra)),
+        }
+    }
+
+    fn place(&mut self, place: Place) -> Place {
+        match place {
+            Place::Ptr(mplace) => Place::Ptr(self.mem_place(mplace)),
+            local @ Place::Local { .. } => local,
+        }
+    }
+
+    fn local(&mut self, local: LocalValue) -> LocalValue {
+        match local {
+            LocalValue::Dead => LocalValue::Dead,
+            LocalValue::Live(Operand::Immediate(value)) =>
+                LocalValue::Live(Operand::Immediate(self.value(value))),
+            LocalValue::Live(Operand::Indirect(mplace)) =>
+                LocalValue::Live(Operand::Indirect(self.mem_place(mplace))),
+        }
+    }
+
+    fn frame(
+        &mut self,
+        frame: &Frame<'mir, 'tcx, M::FrameExtra>,
+    ) -> Frame<'mir, 'tcx, M::FrameExtra> {
+        let mut frame = frame.clone();
+        frame.return_place = self.place(frame.return_place);
+        for local in frame.locals.iter_mut() {
+            *local = self.local(*local);
+        }
+        frame
+    }
+
+    fn hash_frame<H: Hasher>(&mut self, frame: &Frame<'mir, 'tcx, M::FrameExtra>, state: &mut H) {
+        let Frame {
+            mir: _,
+            instance,
+            span: _,
+            return_to_block,
+            return_place,
+            locals,
+            block,
+            stmt,
+            extra,
+        } = frame;
+
+        instance.hash(state);
+        return_to_block.hash(state);
+        self.place(*return_place).hash(state);
+        for &local in locals.iter() {
+            self.local(local).hash(state);
+        }
+        block.hash(state);
+        stmt.hash(state);
+        extra.hash(state);
+    }
+
+    fn hash_allocation<H: Hasher>(&mut self, alloc: &Allocation, state: &mut H) {
+        alloc.bytes.hash(state);
+        for &(offset, id) in alloc.relocations.iter() {
+            offset.hash(state);
+            self.alloc_id(id).hash(state);
+        }
+        alloc.undef_mask.hash(state);
+        alloc.align.hash(state);
+        alloc.mutability.hash(state);
+    }
+
+    fn allocation(&mut self, alloc: &Allocation) -> Allocation {
+        let relocations = alloc.relocations.iter()
+            .map(|&(offset, id)| (offset, self.alloc_id(id)))
+            .collect();
+        Allocation {
+            bytes: alloc.bytes.clone(),
+            relocations: Relocations::from_presorted(relocations),
+            undef_mask: alloc.undef_mask.clone(),
+            align: alloc.align,
+            mutability: alloc.mutability,
+        }
+    }
+}
+
+pub(super) struct InfiniteLoopDetector<'mir, 'tcx: 'mir, M: Machine<'mir, 'tcx>> {
+    /// The set of all `EvalSnapshot` *hashes* observed by this detector.
+    ///
+    /// When a collision occurs in this table, we store the full snapshot in
+    /// `snapshots`.
+    hashes: FxHashSet<u64>,
+
+    /// The set of all `EvalSnapshot`s observed by this detector.
+    ///
+    /// An `EvalSnapshot` will only be stored once it has caused a
+    /// collision in `hashes`. As a result, the detector must observe at least
+    /// *two* full cycles of an infinite loop before it triggers.
+    snapshots: FxHashSet<EvalSnapshot<'mir, 'tcx, M>>,
+}
+
+impl<'mir, 'tcx, M> Default for InfiniteLoopDetector<'mir, 'tcx, M>
+    where M: Machine<'mir, 'tcx>,
+          'tcx: 'mir,
+{
+    fn default() -> Self {
+        InfiniteLoopDetector {
+            hashes: FxHashSet::default(),
+            snapshots: FxHashSet::default(),
+        }
+    }
+}
+
+impl<'mir, 'tcx, M> InfiniteLoopDetector<'mir, 'tcx, M>
+    where M: Machine<'mir, 'tcx>,
+          'tcx: 'mir,
+{
+    /// Returns `true` if the loop detector has not yet observed a snapshot.
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    pub fn observe_and_analyze<'a>(
+        &mut self,
+        machine: &M,
+        stack: &[Frame<'mir, 'tcx, M::FrameExtra>],
+        memory: &Memory<'a, 'mir, 'tcx, M>,
+    ) -> EvalResult<'tcx, ()> {
+        let hash = EvalSnapshot::hash_of(machine, stack, memory);
+        if self.hashes.insert(hash) {
+            // No collision
+            return Ok(())
+        }
+
+        // Only now is it worth copying the state
+        let snapshot = EvalSnapshot::new(machine, stack, memory);
+        if self.snapshots.insert(snapshot) {
+            // Spurious collision or first cycle
+            return Ok(())
+        }
+
+        // Second cycle
+        Err(EvalErrorKind::InfiniteLoop.into())
+    }
+}
@@ -12,11 +12,16 @@
 //!
 //! The main entry point is the `step` method.
 
+use std::fmt;
+
 use rustc::mir;
-use rustc::ty::layout::LayoutOf;
-use rustc::mir::interpret::{EvalResult, Scalar};
+use rustc::ty::item_path;
+use rustc::ty::layout::{self, LayoutOf};
+use rustc::mir::interpret::{EvalResult, Scalar, ScalarMaybeUndef};
+
+use syntax::source_map::Span;
 
-use super::{EvalContext, Machine};
+use super::{EvalContext, Machine, OpTy, Operand, Value};
 
 /// Classify whether an operator is "left-homogeneous", i.e. the LHS has the
 /// same type as the result.
@@ -27,7 +32,7 @@ fn binop_left_homogeneous(op: mir::BinOp) -> bool {
         Add | Sub | Mul | Div | Rem | BitXor | BitAnd | BitOr |
         Offset | Shl | Shr =>
             true,
-        Eq | Ne | Lt | Le | Gt | Ge =>
+        Eq | Ne | Lt | Le | Gt | Ge | Cmp =>
             false,
     }
 }
@@ -38,7 +43,7 @@ fn binop_right_homogeneous(op: mir::BinOp) -> bool {
     use rustc::mir::BinOp::*;
     match op {
         Add | Sub | Mul | Div | Rem | BitXor | BitAnd | BitOr |
-        Eq | Ne | Lt | Le | Gt | Ge =>
+        Eq | Ne | Lt | Le | Gt | Ge | Cmp =>
             true,
         Offset | Shl | Shr =>
             false,
@@ -76,13 +81,27 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
         self.loop_detector.observe_and_analyze(&self.machine, &self.stack, &self.memory)
     }
 
+    /// Counts the terminator about to be executed against `Machine::step_limit`. Every loop and
+    /// call goes through a terminator, so this bounds the statements executed as well.
+    fn check_step_limit(&mut self) -> EvalResult<'tcx> {
+        self.steps_taken += 1;
+        match M::step_limit(self) {
+            Some(limit) if self.steps_taken > limit => err!(StepLimitReached(limit)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Runs until the stack is empty.
     pub fn run(&mut self) -> EvalResult<'tcx> {
         while self.step()? {}
         Ok(())
     }
 
-    /// Returns true as long as there are more things to do.
-    fn step(&mut self) -> EvalResult<'tcx, bool> {
+    /// Executes the next statement or terminator of the topmost frame, which is the one at
+    /// `current_location`. Returns true as long as there are more things to do.
+    ///
+    /// This lets a tool drive evaluation one step at a time, inspecting the stack in between.
+    pub fn step(&mut self) -> EvalResult<'tcx, bool> {
         if self.stack.is_empty() {
             return Ok(false);
         }
@@ -96,18 +115,50 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
 
         if let Some(stmt) = basic_block.statements.get(stmt_id) {
             assert_eq!(old_frames, self.cur_frame());
+            self.trace_step(stmt.source_info.span, &stmt.kind);
             self.statement(stmt)?;
             return Ok(true);
         }
 
+        self.check_step_limit()?;
         self.inc_step_counter_and_detect_loops()?;
 
         let terminator = basic_block.terminator();
         assert_eq!(old_frames, self.cur_frame());
+        self.trace_step(terminator.source_info.span, &terminator.kind);
         self.terminator(terminator)?;
         Ok(true)
     }
 
+    /// Implements `-Z trace-const-eval`: print the statement or terminator that is about to be
+    /// executed, indented by the depth of the stack. Unless the filter is `all`, only steps
+    /// below a frame of an item whose path contains the filter are printed, so a traced item
+    /// is traced together with everything it calls.
+    fn trace_step(&self, span: Span, kind: &dyn fmt::Debug) {
+        let filter = match self.tcx.sess.opts.debugging_opts.trace_const_eval {
+            Some(ref filter) => filter,
+            None => return,
+        };
+        if filter != "all" {
+            let traced = self.stack().iter().any(|frame| {
+                let def_id = frame.instance.def_id();
+                item_path::with_forced_impl_filename_line(|| {
+                    self.tcx.item_path_str(def_id)
+                }).contains(&filter[..])
+            });
+            if !traced {
+                return;
+            }
+        }
+        eprintln!(
+            "{:indent$}{:?} @ {}",
+            "",
+            kind,
+            self.tcx.sess.source_map().span_to_string(span),
+            indent = 2 * self.cur_frame(),
+        );
+    }
+
     fn statement(&mut self, stmt: &mir::Statement<'tcx>) -> EvalResult<'tcx> {
         debug!("{:?}", stmt);
 
@@ -215,8 +266,8 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
             UnaryOp(un_op, ref operand) => {
                 // The operand always has the same type as the result.
                 let val = self.read_value(self.eval_operand(operand, Some(dest.layout))?)?;
-                let val = self.unary_op(un_op, val.to_scalar()?, dest.layout)?;
-                self.write_scalar(val, dest)?;
+                let val = self.unary_op(un_op, val)?;
+                self.write_value(val.value, dest)?;
             }
 
             Aggregate(ref kind, ref operands) => {
@@ -248,7 +299,12 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                 let dest = self.force_allocation(dest)?;
                 let length = dest.len(&self)?;
 
-                if length > 0 {
+                if let Some(byte) = self.repeated_byte(op)? {
+                    // every byte of the array is the same, so just fill the whole thing
+                    let elem_size = dest.layout.field(&self, 0)?.size;
+                    self.memory.check_align(dest.ptr, dest.align)?;
+                    self.memory.write_repeat(dest.ptr, byte, elem_size * length)?;
+                } else if length > 0 {
                     // write the first
                     let first = self.mplace_field(dest, 0)?;
                     self.copy_op(op, first.into())?;
@@ -265,10 +321,17 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
             }
 
             Len(ref place) => {
-                // FIXME(CTFE): don't allow computing the length of arrays in const eval
                 let src = self.eval_place(place)?;
-                let mplace = self.force_allocation(src)?;
-                let len = mplace.len(&self)?;
+                let len = if src.layout.is_unsized() {
+                    // Unsized places always live in memory, with the length as metadata
+                    src.to_mem_place().len(&self)?
+                } else {
+                    // Arrays know their length statically, no need to allocate a local
+                    match src.layout.fields {
+                        layout::FieldPlacement::Array { count, .. } => count,
+                        _ => bug!("Len of non-array type {:?}", src.layout.ty),
+                    }
+                };
                 let size = self.memory.pointer_size().bytes() as u8;
                 self.write_scalar(
                     Scalar::Bits {
@@ -289,15 +352,21 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                 M::box_alloc(self, dest)?;
             }
 
-            NullaryOp(mir::NullOp::SizeOf, ty) => {
+            NullaryOp(null_op, ty) => {
                 let ty = self.monomorphize(ty, self.substs());
                 let layout = self.layout_of(ty)?;
-                assert!(!layout.is_unsized(),
-                        "SizeOf nullary MIR operator called for unsized type");
+                if layout.is_unsized() {
+                    return err!(SizeOfUnsizedType(ty));
+                }
+                let val = match null_op {
+                    mir::NullOp::SizeOf => layout.size.bytes(),
+                    mir::NullOp::AlignOf => layout.align.abi(),
+                    mir::NullOp::Box => bug!("`Box` has already been handled"),
+                };
                 let size = self.memory.pointer_size().bytes() as u8;
                 self.write_scalar(
                     Scalar::Bits {
-                        bits: layout.size.bytes() as u128,
+                        bits: val as u128,
                         size,
                     },
                     dest,
@@ -326,6 +395,26 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
         Ok(())
     }
 
+    /// If `op` is a defined integer value whose bytes are all the same, return that byte.
+    /// Arrays of such values can be filled with a single memset.
+    fn repeated_byte(&self, op: OpTy<'tcx>) -> EvalResult<'tcx, Option<u8>> {
+        let val = match *op {
+            Operand::Immediate(Value::Scalar(ScalarMaybeUndef::Scalar(val))) => val,
+            _ => return Ok(None),
+        };
+        Ok(match val {
+            Scalar::Bits { bits, size } if size as u64 == op.layout.size.bytes() => {
+                let byte = bits as u8;
+                if (0..size).all(|i| (bits >> (8 * i)) as u8 == byte) {
+                    Some(byte)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        })
+    }
+
     fn terminator(&mut self, terminator: &mir::Terminator<'tcx>) -> EvalResult<'tcx> {
         debug!("{:?}", terminator.kind);
         self.tcx.span = terminator.source_info.span;
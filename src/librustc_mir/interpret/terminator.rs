@@ -12,13 +12,15 @@ use std::borrow::Cow;
 
 use rustc::mir;
 use rustc::ty::{self, Ty};
-use rustc::ty::layout::LayoutOf;
+use rustc::ty::layout::{self, LayoutOf, TyLayout};
 use syntax::source_map::Span;
 use rustc_target::spec::abi::Abi;
 
-use rustc::mir::interpret::{EvalResult, Scalar};
+use rustc::mir::interpret::{EvalError, EvalResult, Scalar};
+use rustc_data_structures::indexed_vec::Idx;
 use super::{
-    EvalContext, Machine, Value, OpTy, Place, PlaceTy, ValTy, Operand, StackPopCleanup
+    EvalContext, Machine, FnCall, UnwindPolicy, Value, OpTy, Place, PlaceTy, ValTy, Operand,
+    StackPopCleanup, LocalValue,
 };
 
 impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
@@ -68,7 +70,7 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                         discr,
                         ValTy { value: Value::Scalar(const_int.into()), layout: discr.layout }
                     )?;
-                    if res.to_bool()? {
+                    if res.to_scalar()?.to_bool()? {
                         target_block = targets[index];
                         break;
                     }
@@ -81,9 +83,9 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                 ref func,
                 ref args,
                 ref destination,
-                ..
+                cleanup,
             } => {
-                let (dest, ret) = match *destination {
+                let (dest, mut ret) = match *destination {
                     Some((ref lv, target)) => (Some(self.eval_place(lv)?), Some(target)),
                     None => (None, None),
                 };
@@ -110,6 +112,15 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                                 }
                                 (instance, sig)
                             }
+                            // A non-capturing closure cast to a fn pointer. The cast gave the
+                            // pointer the closure's signature, so there is nothing to check.
+                            ty::Closure(..) => {
+                                let sig = self.tcx.normalize_erasing_late_bound_regions(
+                                    self.param_env,
+                                    &sig,
+                                );
+                                (instance, sig)
+                            }
                             _ => bug!("unexpected fn ptr to ty: {:?}", instance_ty),
                         }
                     }
@@ -127,6 +138,14 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                     }
                 };
                 let args = self.eval_operands(args)?;
+                if self.is_self_tail_call(fn_def, destination, cleanup) {
+                    // `dest` already is the return place of the caller, and the arguments do
+                    // not point into this frame, so the callee can take the place of this frame.
+                    ret = match self.pop_stack_frame_for_tail_call()? {
+                        StackPopCleanup::Goto(block) => block,
+                        StackPopCleanup::None { .. } => bug!("tail call from the outermost frame"),
+                    };
+                }
                 self.eval_fn_call(
                     fn_def,
                     &args[..],
@@ -142,7 +161,6 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                 target,
                 ..
             } => {
-                // FIXME(CTFE): forbid drop in const eval
                 let place = self.eval_place(location)?;
                 let ty = place.layout.ty;
                 trace!("TerminatorKind::drop: {:?}, type {}", location, ty);
@@ -169,7 +187,7 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                     self.goto_block(Some(target))?;
                 } else {
                     use rustc::mir::interpret::EvalErrorKind::*;
-                    return match *msg {
+                    let payload = match *msg {
                         BoundsCheck { ref len, ref index } => {
                             let len = self.read_value(self.eval_operand(len, None)?)
                                 .expect("can't eval len").to_scalar()?
@@ -177,24 +195,42 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                             let index = self.read_value(self.eval_operand(index, None)?)
                                 .expect("can't eval index").to_scalar()?
                                 .to_bits(self.memory().pointer_size())? as u64;
-                            err!(BoundsCheck { len, index })
+                            BoundsCheck { len, index }
                         }
-                        Overflow(op) => Err(Overflow(op).into()),
-                        OverflowNeg => Err(OverflowNeg.into()),
-                        DivisionByZero => Err(DivisionByZero.into()),
-                        RemainderByZero => Err(RemainderByZero.into()),
-                        GeneratorResumedAfterReturn |
-                        GeneratorResumedAfterPanic => unimplemented!(),
+                        Overflow(op) => match self.overflow_operands(cond) {
+                            Some((left, right)) => OverflowOperands { op, left, right },
+                            None => Overflow(op),
+                        },
+                        OverflowNeg => match self.negated_operand(cond) {
+                            Some(operand) => OverflowNegOperand(operand),
+                            None => OverflowNeg,
+                        },
+                        DivisionByZero => DivisionByZero,
+                        RemainderByZero => RemainderByZero,
+                        GeneratorResumedAfterReturn => GeneratorResumedAfterReturn,
+                        GeneratorResumedAfterPanic => GeneratorResumedAfterPanic,
                         _ => bug!(),
                     };
+                    self.start_panic(payload.into())?;
                 }
             }
 
-            Yield { .. } => unimplemented!("{:#?}", terminator.kind),
-            GeneratorDrop => unimplemented!(),
+            // Generator bodies are turned into state machines that store their state in the
+            // generator and return on every suspension, see `transform::generator`.
+            Yield { .. } => bug!("should have been eliminated by the generator transform"),
+            GeneratorDrop => bug!("should have been eliminated by the generator transform"),
             DropAndReplace { .. } => unimplemented!(),
-            Resume => unimplemented!(),
-            Abort => unimplemented!(),
+            Resume => {
+                assert!(self.unwinding.is_some(), "`Resume` without a panic in progress");
+                self.pop_stack_frame_unwinding()?;
+                self.unwind_to_cleanup()?;
+            }
+            // Unwinding into a function that must not unwind, e.g. one marked
+            // `#[unwind(aborts)]`, aborts the program.
+            Abort => {
+                self.unwinding = None;
+                return err!(Abort);
+            }
             FalseEdges { .. } => bug!("should have been eliminated by\
                                       `simplify_branches` mir pass"),
             FalseUnwind { .. } => bug!("should have been eliminated by\
@@ -205,6 +241,156 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
         Ok(())
     }
 
+    /// Finds the operands of the `CheckedBinaryOp` whose overflow flag is `cond`, and renders
+    /// them for an overflow message.
+    fn overflow_operands(&self, cond: &mir::Operand<'tcx>) -> Option<(String, String)> {
+        let checked = match *cond {
+            mir::Operand::Copy(mir::Place::Projection(ref proj)) |
+            mir::Operand::Move(mir::Place::Projection(ref proj)) => match proj.elem {
+                mir::ProjectionElem::Field(field, _) if field.index() == 1 =>
+                    self.assigned_rvalue(&proj.base),
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some(&mir::Rvalue::CheckedBinaryOp(_, ref left, ref right)) = checked {
+            if let (Some(left), Some(right)) =
+                (self.render_int_operand(left), self.render_int_operand(right))
+            {
+                return Some((left, right));
+            }
+        }
+        None
+    }
+
+    /// Finds the operand of a negation whose overflow check is `cond`, i.e. the left-hand
+    /// side of the comparison with the minimum value, and renders it for an overflow message.
+    fn negated_operand(&self, cond: &mir::Operand<'tcx>) -> Option<String> {
+        let check = match *cond {
+            mir::Operand::Copy(ref place) | mir::Operand::Move(ref place) =>
+                self.assigned_rvalue(place),
+            mir::Operand::Constant(_) => None,
+        };
+        match check {
+            Some(&mir::Rvalue::BinaryOp(mir::BinOp::Eq, ref operand, _)) =>
+                self.render_int_operand(operand),
+            _ => None,
+        }
+    }
+
+    /// The last rvalue assigned to `place` by a statement of the current block.
+    fn assigned_rvalue(&self, place: &mir::Place<'tcx>) -> Option<&'mir mir::Rvalue<'tcx>> {
+        let mir = self.mir();
+        mir[self.frame().block].statements.iter().rev()
+            .filter_map(|stmt| match stmt.kind {
+                mir::StatementKind::Assign(ref dest, ref rvalue) if dest == place => Some(rvalue),
+                _ => None,
+            })
+            .next()
+    }
+
+    /// Renders an integer operand with its type suffix, e.g. `200u8`. Returns `None` for
+    /// anything that is not an integer, and when the operand cannot be read, so that the
+    /// overflow is still reported, just without its operands.
+    fn render_int_operand(&self, op: &mir::Operand<'tcx>) -> Option<String> {
+        let op = self.eval_operand(op, None).ok()?;
+        let bits = self.read_value(op)
+            .and_then(|value| value.to_scalar())
+            .and_then(|scalar| scalar.to_bits(op.layout.size))
+            .ok()?;
+        match op.layout.ty.sty {
+            ty::Int(ity) => Some(format!("{}{}",
+                                         self.sign_extend(bits, op.layout) as i128,
+                                         ity.ty_to_string())),
+            ty::Uint(uty) => Some(format!("{}{}", bits, uty.ty_to_string())),
+            _ => None,
+        }
+    }
+
+    /// Whether calling `instance` from the current frame is a self tail call: a recursive call
+    /// whose result is returned right away. The callee can then replace the current frame
+    /// instead of being pushed on top of it, so tail recursion is not limited by the number of
+    /// stack frames.
+    fn is_self_tail_call(
+        &self,
+        instance: ty::Instance<'tcx>,
+        destination: &Option<(mir::Place<'tcx>, mir::BasicBlock)>,
+        cleanup: Option<mir::BasicBlock>,
+    ) -> bool {
+        let frame = self.frame();
+        if instance != frame.instance || cleanup.is_some() || self.unwinding.is_some() {
+            return false;
+        }
+        if let StackPopCleanup::None { .. } = frame.return_to_block {
+            return false;
+        }
+        // Locals in memory could be pointed to by the arguments, and would dangle once
+        // this frame is gone.
+        let locals_in_memory = frame.locals.iter().any(|local| match *local {
+            LocalValue::Live(Operand::Indirect(_)) => true,
+            _ => false,
+        });
+        if locals_in_memory {
+            return false;
+        }
+        let mut block = match *destination {
+            Some((mir::Place::Local(mir::RETURN_PLACE), target)) => target,
+            _ => return false,
+        };
+        // Follow the gotos from the return block, they may only end storage of locals before
+        // returning. Bound the walk by the number of blocks in case of an empty loop.
+        for _ in 0..frame.mir.basic_blocks().len() {
+            let data = &frame.mir[block];
+            let only_storage_dead = data.statements.iter().all(|stmt| match stmt.kind {
+                mir::StatementKind::StorageDead(_) | mir::StatementKind::Nop => true,
+                _ => false,
+            });
+            if !only_storage_dead {
+                return false;
+            }
+            match data.terminator().kind {
+                mir::TerminatorKind::Goto { target } => block = target,
+                mir::TerminatorKind::Return => return true,
+                _ => return false,
+            }
+        }
+        false
+    }
+
+    /// Starts a panic with `payload` at the current terminator. With `UnwindPolicy::Abort`,
+    /// or if this happens during unwinding, `payload` is returned as an error. Otherwise
+    /// control moves to the cleanup block of the terminator, and `Resume` continues
+    /// unwinding in the caller.
+    ///
+    /// Machines emulating the panic machinery call this instead of returning an error.
+    pub fn start_panic(&mut self, payload: EvalError<'tcx>) -> EvalResult<'tcx> {
+        if M::unwind_policy(self) == UnwindPolicy::Abort || self.unwinding.is_some() {
+            return Err(payload);
+        }
+        self.unwinding = Some((payload, self.tcx.span));
+        self.unwind_to_cleanup()
+    }
+
+    /// Jumps to the cleanup block of the current terminator, popping frames until one
+    /// has a cleanup block.
+    fn unwind_to_cleanup(&mut self) -> EvalResult<'tcx> {
+        use rustc::mir::TerminatorKind::*;
+        loop {
+            let cleanup = {
+                let frame = self.frame();
+                match frame.mir[frame.block].terminator().kind {
+                    Call { cleanup, .. } | Assert { cleanup, .. } => cleanup,
+                    Drop { unwind, .. } | DropAndReplace { unwind, .. } => unwind,
+                    _ => None,
+                }
+            };
+            if let Some(cleanup) = cleanup {
+                return self.goto_block(Some(cleanup));
+            }
+            self.pop_stack_frame_unwinding()?;
+        }
+    }
+
     /// Decides whether it is okay to call the method with signature `real_sig`
     /// using signature `sig`.
     /// FIXME: This should take into account the platform-dependent ABI description.
@@ -275,6 +461,39 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
         return Ok(false);
     }
 
+    /// Whether a value of layout `caller` is passed the same way as a parameter of layout
+    /// `callee` expects, so that it can be passed without reinterpreting its bits.
+    fn check_argument_compat(caller: TyLayout<'tcx>, callee: TyLayout<'tcx>) -> bool {
+        if caller.ty == callee.ty {
+            // No need to compare the layouts
+            return true;
+        }
+        if caller.size != callee.size {
+            return false;
+        }
+        match (&caller.abi, &callee.abi) {
+            (layout::Abi::Scalar(ref caller), layout::Abi::Scalar(ref callee)) =>
+                caller.value == callee.value,
+            (layout::Abi::ScalarPair(ref caller1, ref caller2),
+             layout::Abi::ScalarPair(ref callee1, ref callee2)) =>
+                caller1.value == callee1.value && caller2.value == callee2.value,
+            (layout::Abi::Vector { .. }, layout::Abi::Vector { .. }) =>
+                caller.abi == callee.abi,
+            (layout::Abi::Aggregate { .. }, layout::Abi::Aggregate { .. }) => true,
+            _ => false,
+        }
+    }
+
+    /// Copies the argument `arg` into the parameter `dest` of a new stack frame, after
+    /// checking that the parameter expects this kind of argument.
+    fn pass_argument(&mut self, arg: OpTy<'tcx>, dest: PlaceTy<'tcx>) -> EvalResult<'tcx> {
+        if !Self::check_argument_compat(arg.layout, dest.layout) {
+            return err!(FunctionArgMismatch(dest.layout.ty, arg.layout.ty));
+        }
+        // The layouts may still differ in details like the valid range of scalars
+        self.copy_op_transmute(arg, dest)
+    }
+
     /// Call this function -- pushing the stack frame and initializing the arguments.
     /// `sig` is optional in case of FnPtr/FnDef -- but mandatory for closures!
     fn eval_fn_call(
@@ -290,13 +509,21 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
 
         match instance.def {
             ty::InstanceDef::Intrinsic(..) => {
-                // The intrinsic itself cannot diverge, so if we got here without a return
-                // place... (can happen e.g. for transmute returning `!`)
                 let dest = match dest {
                     Some(dest) => dest,
-                    None => return err!(Unreachable)
+                    None => return self.emulate_diverging_intrinsic(instance),
                 };
-                M::call_intrinsic(self, instance, args, dest)?;
+                if !M::call_intrinsic(self, instance, args, dest)? {
+                    // The machine does not implement this intrinsic, call its MIR body instead
+                    return match self.intrinsic_fallback(instance) {
+                        Some(fallback) =>
+                            self.eval_fn_call(fallback, args, Some(dest), ret, span, None),
+                        None => {
+                            let name = self.tcx.item_name(instance.def_id());
+                            err!(Unimplemented(format!("calling intrinsic `{}`", name)))
+                        }
+                    };
+                }
                 // No stack frame gets pushed, the main loop will just act as if the
                 // call completed.
                 self.goto_block(ret)?;
@@ -308,9 +535,17 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
             ty::InstanceDef::DropGlue(..) |
             ty::InstanceDef::CloneShim(..) |
             ty::InstanceDef::Item(_) => {
+                if let Some(ty) = self.drop_impl_self_ty(instance) {
+                    M::before_drop(self, ty)?;
+                }
                 let mir = match M::find_fn(self, instance, args, dest, ret)? {
-                    Some(mir) => mir,
-                    None => return Ok(()),
+                    FnCall::Mir(mir) => mir,
+                    // The arguments are still laid out for the signature of the call site,
+                    // which `fn_sig` could not even compute for closures and their shims.
+                    FnCall::Redirect(instance) =>
+                        return self.eval_fn_call(instance, args, dest, ret, span, sig),
+                    FnCall::Emulated => return self.goto_block(ret),
+                    FnCall::Handled => return Ok(()),
                 };
 
                 let return_place = match dest {
@@ -380,16 +615,22 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                 // taking into account the `spread_arg`.
                 let mut args_iter = args_effective.iter();
                 let mut local_iter = mir.args_iter();
-                // HACK: ClosureOnceShim calls something that expects a ZST as
-                // first argument, but the callers do not actually pass that ZST.
+                // HACK: ClosureOnceShim and closures called through a fn pointer expect a ZST
+                // environment as first argument, but the callers do not actually pass that ZST.
                 // Codegen doesn't care because ZST arguments do not even exist there.
-                match instance.def {
-                    ty::InstanceDef::ClosureOnceShim { .. } if sig.abi == Abi::Rust => {
-                        let local = local_iter.next().unwrap();
-                        let dest = self.eval_place(&mir::Place::Local(local))?;
-                        assert!(dest.layout.is_zst());
-                    }
-                    _ => {}
+                let takes_env = match instance.def {
+                    ty::InstanceDef::ClosureOnceShim { .. } => true,
+                    // Not generators: their resumption takes the generator by reference
+                    ty::InstanceDef::Item(_) => match instance.ty(*self.tcx).sty {
+                        ty::Closure(..) => true,
+                        _ => false,
+                    },
+                    _ => false,
+                };
+                if takes_env && sig.abi == Abi::Rust {
+                    let local = local_iter.next().unwrap();
+                    let dest = self.eval_place(&mir::Place::Local(local))?;
+                    assert!(dest.layout.is_zst());
                 }
                 // Now back to norml argument passing.
                 while let Some(local) = local_iter.next() {
@@ -398,11 +639,11 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                         // Must be a tuple
                         for i in 0..dest.layout.fields.count() {
                             let dest = self.place_field(dest, i as u64)?;
-                            self.copy_op(*args_iter.next().unwrap(), dest)?;
+                            self.pass_argument(*args_iter.next().unwrap(), dest)?;
                         }
                     } else {
                         // Normal argument
-                        self.copy_op(*args_iter.next().unwrap(), dest)?;
+                        self.pass_argument(*args_iter.next().unwrap(), dest)?;
                     }
                 }
                 // Now we should be done
@@ -411,15 +652,9 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
             }
             // cannot use the shim here, because that will only result in infinite recursion
             ty::InstanceDef::Virtual(_, idx) => {
-                let ptr_size = self.memory.pointer_size();
-                let ptr_align = self.tcx.data_layout.pointer_align;
                 let ptr = self.ref_to_mplace(self.read_value(args[0])?)?;
                 let vtable = ptr.vtable()?;
-                let fn_ptr = self.memory.read_ptr_sized(
-                    vtable.offset(ptr_size * (idx as u64 + 3), &self)?,
-                    ptr_align
-                )?.to_ptr()?;
-                let instance = self.memory.get_fn(fn_ptr)?;
+                let instance = self.read_method_from_vtable(vtable, idx)?;
 
                 // We have to patch the self argument, in particular get the layout
                 // expected by the actual function. Cannot just use "field 0" due to
@@ -436,6 +671,16 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
         }
     }
 
+    /// If `instance` is the `drop` method of a `Drop` impl, returns the type it drops.
+    fn drop_impl_self_ty(&self, instance: ty::Instance<'tcx>) -> Option<Ty<'tcx>> {
+        let impl_def_id = self.tcx.impl_of_method(instance.def_id())?;
+        let trait_ref = self.tcx.impl_trait_ref(impl_def_id)?;
+        if Some(trait_ref.def_id) != self.tcx.lang_items().drop_trait() {
+            return None;
+        }
+        Some(self.monomorphize(trait_ref.self_ty(), instance.substs))
+    }
+
     fn drop_in_place(
         &mut self,
         place: PlaceTy<'tcx>,
@@ -457,6 +702,11 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
             _ => (instance, place),
         };
 
+        if let ty::InstanceDef::DropGlue(_, None) = instance.def {
+            // Nothing to drop
+            return self.goto_block(Some(target));
+        }
+
         let arg = OpTy {
             op: Operand::Immediate(place.to_ref()),
             layout: self.layout_of(self.tcx.mk_mut_ptr(place.layout.ty))?,
@@ -30,6 +30,10 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
     ) -> EvalResult<'tcx, Pointer> {
         debug!("get_vtable(trait_ref={:?})", trait_ref);
 
+        if let Some(&vtable) = self.vtables.get(&(ty, trait_ref)) {
+            return Ok(vtable);
+        }
+
         let layout = self.layout_of(trait_ref.self_ty())?;
         assert!(!layout.is_unsized(), "can't create a vtable for an unsized type");
         let size = layout.size.bytes();
@@ -72,10 +76,27 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
             vtable.alloc_id,
             Mutability::Immutable,
         )?;
+        self.vtables.insert((ty, trait_ref), vtable);
 
         Ok(vtable)
     }
 
+    /// Returns the method in slot `idx` of the vtable, as used by `InstanceDef::Virtual`.
+    pub fn read_method_from_vtable(
+        &self,
+        vtable: Pointer,
+        idx: usize,
+    ) -> EvalResult<'tcx, ty::Instance<'tcx>> {
+        let pointer_size = self.memory.pointer_size();
+        let pointer_align = self.tcx.data_layout.pointer_align;
+        // The first three slots hold the drop glue, the size and the alignment.
+        let fn_ptr = self.memory.read_ptr_sized(
+            vtable.offset(pointer_size * (3 + idx as u64), self)?,
+            pointer_align,
+        )?.to_ptr()?;
+        self.memory.get_fn(fn_ptr)
+    }
+
     /// Return the drop fn instance as well as the actual dynamic type
     pub fn read_drop_type_from_vtable(
         &self,
@@ -9,6 +9,7 @@
 // except according to those terms.
 
 use std::fmt::Write;
+use std::ops::RangeInclusive;
 
 use syntax_pos::symbol::Symbol;
 use rustc::ty::layout::{self, Size, Primitive};
@@ -19,7 +20,7 @@ use rustc::mir::interpret::{
 };
 
 use super::{
-    OpTy, Machine, EvalContext
+    OpTy, MPlaceTy, Machine, EvalContext, Value
 };
 
 macro_rules! validation_failure{
@@ -61,6 +62,7 @@ pub enum PathElem {
     TupleElem(usize),
     Deref,
     Tag,
+    GeneratorState,
 }
 
 // Adding a Deref and making a copy of the path to be put into the queue
@@ -90,11 +92,23 @@ fn path_format(path: &Vec<PathElem>) -> String {
                 // not the root.
                 write!(out, ".<deref>").unwrap(),
             Tag => write!(out, ".<enum-tag>").unwrap(),
+            GeneratorState => write!(out, ".<generator-state>").unwrap(),
         }
     }
     out
 }
 
+/// Describe the valid values of a scalar, whose range may wrap around at `max_hi`.
+fn wrapping_range_format(r: &RangeInclusive<u128>, max_hi: u128) -> String {
+    let (lo, hi) = r.clone().into_inner();
+    debug_assert!(hi <= max_hi);
+    if lo > hi {
+        format!("something in the range {:?} or {:?}", 0..=hi, lo..=max_hi)
+    } else {
+        format!("something in the range {:?}", r)
+    }
+}
+
 impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
     fn validate_scalar(
         &self,
@@ -106,6 +120,7 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
     ) -> EvalResult<'tcx> {
         trace!("validate scalar: {:#?}, {:#?}, {:#?}, {}", value, size, scalar, ty);
         let (lo, hi) = scalar.valid_range.clone().into_inner();
+        let max_hi = u128::max_value() >> (128 - size.bits());
 
         let value = match value {
             ScalarMaybeUndef::Scalar(scalar) => scalar,
@@ -118,30 +133,21 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                 bits
             },
             Scalar::Ptr(_) => {
-                let ptr_size = self.memory.pointer_size();
-                let ptr_max = u128::max_value() >> (128 - ptr_size.bits());
-                return if lo > hi {
-                    if lo - hi == 1 {
-                        // no gap, all values are ok
-                        Ok(())
-                    } else if hi < ptr_max || lo > 1 {
-                        let max = u128::max_value() >> (128 - size.bits());
-                        validation_failure!(
-                            "pointer",
-                            path,
-                            format!("something in the range {:?} or {:?}", 0..=lo, hi..=max)
-                        )
-                    } else {
-                        Ok(())
-                    }
-                } else if hi < ptr_max || lo > 1 {
+                // We do not know the address, only that it is not NULL, so the valid range
+                // has to contain everything else.
+                let covers_non_null = if lo > hi {
+                    lo - hi == 1
+                } else {
+                    lo <= 1 && hi == max_hi
+                };
+                return if covers_non_null {
+                    Ok(())
+                } else {
                     validation_failure!(
                         "pointer",
                         path,
-                        format!("something in the range {:?}", scalar.valid_range)
+                        wrapping_range_format(&scalar.valid_range, max_hi)
                     )
-                } else {
-                    Ok(())
                 };
             },
         };
@@ -153,7 +159,7 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                 debug_assert_eq!(size.bytes(), 4);
                 if ::std::char::from_u32(bits as u32).is_none() {
                     return validation_failure!(
-                        "character",
+                        format!("character {:#x}", bits),
                         path,
                         "a valid unicode codepoint"
                     );
@@ -162,28 +168,20 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
             _ => {},
         }
 
-        use std::ops::RangeInclusive;
-        let in_range = |bound: RangeInclusive<u128>| bound.contains(&bits);
-        if lo > hi {
-            if in_range(0..=hi) || in_range(lo..=u128::max_value()) {
-                Ok(())
-            } else {
-                validation_failure!(
-                    bits,
-                    path,
-                    format!("something in the range {:?} or {:?}", ..=hi, lo..)
-                )
-            }
+        // The range may wrap around, e.g. for the niche of an enum.
+        let in_range = if lo > hi {
+            bits <= hi || bits >= lo
         } else {
-            if in_range(scalar.valid_range.clone()) {
-                Ok(())
-            } else {
-                validation_failure!(
-                    bits,
-                    path,
-                    format!("something in the range {:?}", scalar.valid_range)
-                )
-            }
+            lo <= bits && bits <= hi
+        };
+        if in_range {
+            Ok(())
+        } else {
+            validation_failure!(
+                bits,
+                path,
+                wrapping_range_format(&scalar.valid_range, max_hi)
+            )
         }
     }
 
@@ -209,7 +207,7 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                 let variant = match self.read_discriminant(dest) {
                     Ok(res) => res.1,
                     Err(err) => match err.kind {
-                        EvalErrorKind::InvalidDiscriminant(val) =>
+                        EvalErrorKind::InvalidDiscriminant(_, val) =>
                             return validation_failure!(
                                 format!("invalid enum discriminant {}", val), path
                             ),
@@ -246,7 +244,19 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                     }
                     _ => dest
                 };
-                (index, dest)
+                match dest.layout.ty.sty {
+                    // Generators keep their suspension state in a field, the variant is found
+                    // by reading it.
+                    ty::Generator(..) => {
+                        let state = match self.read_generator_state(dest) {
+                            Ok(state) => state as usize,
+                            Err(_) =>
+                                return validation_failure!("undefined generator state", path),
+                        };
+                        (state, self.operand_downcast(dest, state)?)
+                    }
+                    _ => (index, dest)
+                }
             }
         };
 
@@ -284,19 +294,32 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                         };
                         let scalar = value.to_scalar_or_undef();
                         self.validate_scalar(scalar, size, scalar_layout, &path, dest.layout.ty)?;
+                        if let ty::FnPtr(_) = dest.layout.ty.sty {
+                            // integers cannot be checked, as for any other pointer
+                            if let Scalar::Ptr(ptr) = scalar.not_undef()? {
+                                if self.memory.get_fn(ptr).is_err() {
+                                    return validation_failure!(
+                                        "pointer to non-function memory", path, "a function pointer"
+                                    );
+                                }
+                            }
+                        }
                         if scalar_layout.value == Primitive::Pointer {
                             // ignore integer pointers, we can't reason about the final hardware
                             if let Scalar::Ptr(ptr) = scalar.not_undef()? {
                                 let alloc_kind = self.tcx.alloc_map.lock().get(ptr.alloc_id);
-                                if let Some(AllocType::Static(did)) = alloc_kind {
-                                    // statics from other crates are already checked.
-                                    // extern statics cannot be validated as they have no body.
-                                    if !did.is_local() || self.tcx.is_foreign_item(did) {
-                                        return Ok(());
-                                    }
+                                if let Some(AllocType::Static(_)) = alloc_kind {
+                                    // statics are validated when their own initializer is
+                                    // evaluated, and extern statics cannot be validated as they
+                                    // have no body. Recursing here would force the referenced
+                                    // static to be evaluated, which creates a query cycle for
+                                    // statics that refer to each other.
+                                    return Ok(());
                                 }
                                 if value.layout.ty.builtin_deref(false).is_some() {
-                                    let ptr_op = self.ref_to_mplace(value)?.into();
+                                    let ptr_place = self.ref_to_mplace(value)?;
+                                    self.validate_ref_target(ptr_place, path)?;
+                                    let ptr_op = ptr_place.into();
                                     // we have not encountered this pointer+layout combination
                                     // before.
                                     if seen.insert(ptr_op) {
@@ -391,8 +414,16 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                                 self.tcx.struct_tail(ptr.layout.ty)
                             ),
                     }
+                    // statics are validated on their own, see the thin pointer case
+                    if let Scalar::Ptr(p) = ptr.ptr {
+                        let alloc_kind = self.tcx.alloc_map.lock().get(p.alloc_id);
+                        if let Some(AllocType::Static(_)) = alloc_kind {
+                            return Ok(());
+                        }
+                    }
                     // for safe ptrs, recursively check it
                     if !dest.layout.ty.is_unsafe_ptr() {
+                        self.validate_ref_target(ptr, path)?;
                         let ptr = ptr.into();
                         if seen.insert(ptr) {
                             trace!("Recursing below fat ptr {:?}", ptr);
@@ -400,8 +431,27 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
                         }
                     }
                 } else {
+                    // Newtypes like `NonZero` restrict the range of the scalar they wrap, which
+                    // the layout of their field does not know about.
+                    if let layout::Abi::Scalar(ref scalar_layout) = dest.layout.abi {
+                        // Undefined or unreadable data is reported when visiting the field.
+                        if let Ok(Value::Scalar(ScalarMaybeUndef::Scalar(scalar))) =
+                            self.read_value(dest).map(|val| *val)
+                        {
+                            let size = scalar_layout.value.size(self);
+                            self.validate_scalar(
+                                scalar.into(), size, scalar_layout, &path, dest.layout.ty
+                            )?;
+                        }
+                    }
+                    // Which locals a generator has saved depends on where it is suspended, which
+                    // the layout does not record, so only its upvars and state are checked.
+                    let field_count = match dest.layout.ty.sty {
+                        ty::Generator(..) => self.generator_state_field(dest.layout.ty) + 1,
+                        _ => offsets.len(),
+                    };
                     // Not a pointer, perform regular aggregate handling below
-                    for i in 0..offsets.len() {
+                    for i in 0..field_count {
                         let field = self.operand_field(dest, i as u64)?;
                         path.push(self.aggregate_field_path_elem(dest.layout.ty, variant, i));
                         self.validate_operand(field, path, seen, todo)?;
@@ -413,9 +463,50 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
         Ok(())
     }
 
+    /// Checks that the memory a reference or `Box` points to lies entirely within a live
+    /// allocation and is aligned for the pointee type. Integer addresses are not checked, we
+    /// cannot reason about the final hardware.
+    fn validate_ref_target(
+        &self,
+        place: MPlaceTy<'tcx>,
+        path: &Vec<PathElem>,
+    ) -> EvalResult<'tcx> {
+        let ptr = match place.ptr {
+            Scalar::Ptr(ptr) => ptr,
+            Scalar::Bits { .. } => return Ok(()),
+        };
+        // extern types have neither a size nor an alignment we could check
+        if place.layout.is_unsized() && place.extra.is_none() {
+            return Ok(());
+        }
+        let (size, align) = match self.size_and_align_of_mplace(place) {
+            Ok(res) => res,
+            Err(_) => return validation_failure!("invalid metadata in fat pointer", path),
+        };
+        if let Err(err) = self.memory.check_align(place.ptr, align) {
+            return match err.kind {
+                EvalErrorKind::AlignmentCheckFailed { required, has, .. } =>
+                    validation_failure!(
+                        format!("unaligned reference (alignment {} required but pointer is \
+                                 only aligned to {})", required.abi(), has.abi()),
+                        path
+                    ),
+                _ => validation_failure!("dangling reference (no live allocation)", path),
+            };
+        }
+        let in_bounds = ptr.offset(size, self)
+            .and_then(|end| self.memory.check_bounds(end, false));
+        if in_bounds.is_err() {
+            return validation_failure!("dangling reference (not entirely in bounds)", path);
+        }
+        Ok(())
+    }
+
     fn aggregate_field_path_elem(&self, ty: Ty<'tcx>, variant: usize, field: usize) -> PathElem {
         match ty.sty {
             // generators and closures.
+            ty::Generator(..) if field == self.generator_state_field(ty) =>
+                PathElem::GeneratorState,
             ty::Closure(def_id, _) | ty::Generator(def_id, _, _) => {
                 let node_id = self.tcx.hir.as_local_node_id(def_id).unwrap();
                 let freevar = self.tcx.with_freevars(node_id, |fv| fv[field]);
@@ -438,4 +529,20 @@ impl<'a, 'mir, 'tcx, M: Machine<'mir, 'tcx>> EvalContext<'a, 'mir, 'tcx, M> {
             _ => bug!("aggregate_field_path_elem: got non-aggregate type {:?}", ty),
         }
     }
+
+    /// Checks the data at `op`, but not what references in it point to.
+    pub fn validate_operand_shallow(&self, op: OpTy<'tcx>) -> EvalResult<'tcx> {
+        self.validate_operand(op, &mut Vec::new(), &mut FxHashSet(), &mut Vec::new())
+    }
+
+    /// Checks the data at `op` and everything reachable from it through references.
+    pub fn validate_operand_deep(&self, op: OpTy<'tcx>) -> EvalResult<'tcx> {
+        let mut todo = vec![(op, Vec::new())];
+        let mut seen = FxHashSet();
+        seen.insert(op);
+        while let Some((op, mut path)) = todo.pop() {
+            self.validate_operand(op, &mut path, &mut seen, &mut todo)?;
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,106 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+//! Makes unwinding out of functions that must not unwind abort the process instead.
+
+use rustc::hir;
+use rustc::hir::def_id::DefId;
+use rustc::mir::*;
+use rustc::ty::{self, TyCtxt};
+use rustc_target::spec::PanicStrategy;
+use rustc_target::spec::abi::Abi;
+use syntax::attr::{self, UnwindAttr};
+use transform::{MirPass, MirSource};
+
+/// Functions with a non-Rust ABI are called from foreign code, which does not expect a Rust
+/// panic to unwind through its frames. For such functions, this pass sends every unwind that
+/// would leave the function to a block that aborts instead.
+pub struct AbortUnwindingCalls;
+
+impl MirPass for AbortUnwindingCalls {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          src: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        if src.promoted.is_some() {
+            return;
+        }
+        let node_id = tcx.hir.as_local_node_id(src.def_id).unwrap();
+        match tcx.hir.body_owner_kind(node_id) {
+            hir::BodyOwnerKind::Fn => {}
+            hir::BodyOwnerKind::Const |
+            hir::BodyOwnerKind::Static(_) => return,
+        }
+        // Closures and generators always have a Rust ABI.
+        let abi = match tcx.type_of(src.def_id).sty {
+            ty::FnDef(..) => tcx.fn_sig(src.def_id).abi(),
+            _ => return,
+        };
+        if !should_abort_on_panic(tcx, src.def_id, abi) {
+            return;
+        }
+
+        let source_info = SourceInfo {
+            span: mir.span,
+            scope: OUTERMOST_SOURCE_SCOPE,
+        };
+        let abort_block = mir.basic_blocks_mut().push(BasicBlockData {
+            statements: vec![],
+            terminator: Some(Terminator {
+                source_info,
+                kind: TerminatorKind::Abort,
+            }),
+            is_cleanup: true,
+        });
+
+        for block in mir.basic_blocks_mut() {
+            let is_cleanup = block.is_cleanup;
+            let terminator = block.terminator_mut();
+            if let TerminatorKind::Resume = terminator.kind {
+                // Unwinding out of the function, after running the cleanup.
+                terminator.kind = TerminatorKind::Abort;
+            } else if !is_cleanup {
+                // Unwinding out of the function right away.
+                if let Some(unwind) = terminator.unwind_mut() {
+                    if unwind.is_none() {
+                        *unwind = Some(abort_block);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn should_abort_on_panic<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                   fn_def_id: DefId,
+                                   abi: Abi)
+                                   -> bool {
+    // Not callable from C, so we can safely unwind through these
+    if abi == Abi::Rust || abi == Abi::RustCall { return false; }
+
+    // We never unwind, so it's not relevant to stop an unwind
+    if tcx.sess.panic_strategy() != PanicStrategy::Unwind { return false; }
+
+    // We cannot add landing pads, so don't add one
+    if tcx.sess.no_landing_pads() { return false; }
+
+    // This is a special case: some functions have a C abi but are meant to
+    // unwind anyway. Don't stop them.
+    let attrs = &tcx.get_attrs(fn_def_id);
+    match attr::find_unwind_attr(Some(tcx.sess.diagnostic()), attrs) {
+        // FIXME(rust-lang/rust#48251) -- Had to disable
+        // abort-on-panic by default for backwards compatibility reasons.
+        None => tcx.sess.opts.debugging_opts.abort_on_foreign_unwind,
+
+        Some(UnwindAttr::Allowed) => false,
+        Some(UnwindAttr::Aborts) => true,
+    }
+}
@@ -12,7 +12,9 @@
 //! assertion failures
 
 
+use rustc::hir;
 use rustc::hir::def::Def;
+use rustc::hir::def_id::DefId;
 use rustc::mir::{Constant, Location, Place, Mir, Operand, Rvalue, Local};
 use rustc::mir::{NullOp, UnOp, StatementKind, Statement, BasicBlock, LocalKind};
 use rustc::mir::{TerminatorKind, ClearCrossCrate, SourceInfo, BinOp, ProjectionElem};
@@ -24,6 +26,7 @@ use rustc::ty::{TyCtxt, self, Instance};
 use interpret::{EvalContext, CompileTimeEvaluator, eval_promoted, mk_borrowck_eval_cx};
 use interpret::{Value, OpTy, MemoryKind};
 use transform::{MirPass, MirSource};
+use syntax::attr;
 use syntax::source_map::{Span, DUMMY_SP};
 use rustc::ty::subst::Substs;
 use rustc_data_structures::indexed_vec::{IndexVec, Idx};
@@ -76,6 +79,9 @@ struct ConstPropagator<'b, 'a, 'tcx:'a+'b> {
     places: IndexVec<Local, Option<Const<'tcx>>>,
     can_const_prop: IndexVec<Local, bool>,
     param_env: ParamEnv<'tcx>,
+    /// Whether the MIR of this body contains overflow checks. Without them
+    /// arithmetic wraps at runtime, so that is what we evaluate it to.
+    check_overflow: bool,
 }
 
 impl<'a, 'b, 'tcx> LayoutOf for &'a ConstPropagator<'a, 'b, 'tcx> {
@@ -119,6 +125,7 @@ impl<'b, 'a, 'tcx:'b> ConstPropagator<'b, 'a, 'tcx> {
             param_env,
             can_const_prop: CanConstProp::check(mir),
             places: IndexVec::from_elem(None, &mir.local_decls),
+            check_overflow: check_overflow(tcx, source.def_id),
         }
     }
 
@@ -151,9 +158,11 @@ impl<'b, 'a, 'tcx:'b> ConstPropagator<'b, 'a, 'tcx> {
                 match diagnostic.error.kind {
                     // don't report these, they make no sense in a const prop context
                     | MachineError(_)
+                    | UnsupportedInConst { .. }
                     // at runtime these transformations might make sense
                     // FIXME: figure out the rules and start linting
                     | FunctionPointerTyMismatch(..)
+                    | FunctionArgMismatch(..)
                     // fine at runtime, might be a register address or sth
                     | ReadBytesAsPointer
                     // fine at runtime
@@ -161,6 +170,7 @@ impl<'b, 'a, 'tcx:'b> ConstPropagator<'b, 'a, 'tcx> {
                     | Unimplemented(_)
                     // don't report const evaluator limits
                     | StackFrameLimitReached
+                    | StepLimitReached(_)
                     | NoMirFor(..)
                     | InlineAsm
                     => {},
@@ -171,6 +181,7 @@ impl<'b, 'a, 'tcx:'b> ConstPropagator<'b, 'a, 'tcx> {
                     | InvalidFunctionPointer
                     | InvalidBool
                     | InvalidDiscriminant(..)
+                    | FloatToIntOutOfRange(..)
                     | PointerOutOfBounds { .. }
                     | InvalidNullPointerUsage
                     | MemoryLockViolation { .. }
@@ -182,6 +193,14 @@ impl<'b, 'a, 'tcx:'b> ConstPropagator<'b, 'a, 'tcx> {
                     | ReadUndefBytes
                     | DeadLocal
                     | InvalidBoolOp(_)
+                    | InvalidCharOp(_)
+                    | InvalidUnaryOp(..)
+                    | ShiftOverflow { .. }
+                    | InexactDivision
+                    | UncheckedDivisionByZero(_)
+                    | UncheckedOverflow(_)
+                    | OffsetFromZeroSized(_)
+                    | OffsetFromInexact { .. }
                     | DerefFunctionPointer
                     | ExecuteMemory
                     | Intrinsic(..)
@@ -192,8 +211,10 @@ impl<'b, 'a, 'tcx:'b> ConstPropagator<'b, 'a, 'tcx> {
                     | VtableForArgumentlessMethod
                     | ModifiedConstantMemory
                     | AssumptionNotHeld
+                    | Abort
                     // FIXME: should probably be removed and turned into a bug! call
                     | TypeNotPrimitive(_)
+                    | SizeOfUnsizedType(_)
                     | ReallocatedWrongMemoryKind(_, _)
                     | DeallocatedWrongMemoryKind(_, _)
                     | ReallocateNonBasePtr
@@ -234,6 +255,8 @@ impl<'b, 'a, 'tcx:'b> ConstPropagator<'b, 'a, 'tcx> {
                     | BoundsCheck{..}
                     | Overflow(_)
                     | OverflowNeg
+                    | OverflowOperands { .. }
+                    | OverflowNegOperand(_)
                     | DivisionByZero
                     | RemainderByZero
                     => {
@@ -352,10 +375,47 @@ impl<'b, 'a, 'tcx:'b> ConstPropagator<'b, 'a, 'tcx> {
                 })
             }
 
-            // FIXME(oli-obk): evaluate static/constant slice lengths
-            Rvalue::Len(_) => None,
-            Rvalue::NullaryOp(NullOp::SizeOf, ty) => {
-                type_size_of(self.tcx, self.param_env, ty).and_then(|n| Some((
+            Rvalue::Len(ref place) => {
+                // Slices are only reachable through a (wide) pointer, so look through a
+                // `Deref` to get at the metadata of constant and promoted slices.
+                let (op, span) = match *place {
+                    Place::Projection(ref proj) => match proj.elem {
+                        ProjectionElem::Deref => {
+                            let (base, span) = self.eval_place(&proj.base, source_info)?;
+                            let op = self.use_ecx(source_info, |this| {
+                                this.ecx.operand_projection(base, &proj.elem)
+                            })?;
+                            (op, span)
+                        }
+                        _ => self.eval_place(place, source_info)?,
+                    },
+                    _ => self.eval_place(place, source_info)?,
+                };
+                self.use_ecx(source_info, |this| {
+                    let len = match op.try_as_mplace() {
+                        Ok(mplace) => mplace.len(&this.ecx)?,
+                        // only sized arrays can be immediates
+                        Err(_) => op.layout.fields.count() as u64,
+                    };
+                    let size = this.tcx.data_layout.pointer_size.bytes() as u8;
+                    Ok((
+                        OpTy::from_scalar_value(
+                            Scalar::Bits { bits: len as u128, size },
+                            place_layout,
+                        ),
+                        span,
+                    ))
+                })
+            }
+            Rvalue::NullaryOp(null_op @ NullOp::SizeOf, ty) |
+            Rvalue::NullaryOp(null_op @ NullOp::AlignOf, ty) => {
+                let layout = self.tcx.layout_of(self.param_env.and(ty)).ok()?;
+                let n = if null_op == NullOp::SizeOf {
+                    layout.size.bytes()
+                } else {
+                    layout.align.abi()
+                };
+                Some((
                     OpTy::from_scalar_value(
                         Scalar::Bits {
                             bits: n as u128,
@@ -364,7 +424,7 @@ impl<'b, 'a, 'tcx:'b> ConstPropagator<'b, 'a, 'tcx> {
                         self.tcx.layout_of(self.param_env.and(self.tcx.types.usize)).ok()?,
                     ),
                     span,
-                )))
+                ))
             }
             Rvalue::UnaryOp(op, ref arg) => {
                 let def_id = if self.tcx.is_closure(self.source.def_id) {
@@ -380,13 +440,15 @@ impl<'b, 'a, 'tcx:'b> ConstPropagator<'b, 'a, 'tcx> {
 
                 let (arg, _) = self.eval_operand(arg, source_info)?;
                 let val = self.use_ecx(source_info, |this| {
-                    let prim = this.ecx.read_scalar(arg)?.not_undef()?;
+                    let val = this.ecx.read_value(arg)?;
+                    let prim = val.to_scalar()?;
                     match op {
                         UnOp::Neg => {
                             // Need to do overflow check here: For actual CTFE, MIR
                             // generation emits code that does this before calling the op.
                             let size = arg.layout.size;
-                            if prim.to_bits(size)? == (1 << (size.bits() - 1)) {
+                            if this.check_overflow &&
+                               prim.to_bits(size)? == (1 << (size.bits() - 1)) {
                                 return err!(OverflowNeg);
                             }
                         }
@@ -395,9 +457,9 @@ impl<'b, 'a, 'tcx:'b> ConstPropagator<'b, 'a, 'tcx> {
                         }
                     }
                     // Now run the actual operation.
-                    this.ecx.unary_op(op, prim, arg.layout)
+                    this.ecx.unary_op(op, val)
                 })?;
-                Some((OpTy::from_scalar_value(val, place_layout), span))
+                Some((val.into(), span))
             }
             Rvalue::CheckedBinaryOp(op, ref left, ref right) |
             Rvalue::BinaryOp(op, ref left, ref right) => {
@@ -451,25 +513,32 @@ impl<'b, 'a, 'tcx:'b> ConstPropagator<'b, 'a, 'tcx> {
                     this.ecx.read_value(left.0)
                 })?;
                 trace!("const evaluating {:?} for {:?} and {:?}", op, left, right);
-                let (val, overflow) = self.use_ecx(source_info, |this| {
-                    this.ecx.binary_op(op, l, r)
+                let (val, overflow, layout) = self.use_ecx(source_info, |this| {
+                    this.ecx.checked_binary_op(op, l, r)
                 })?;
-                let val = if let Rvalue::CheckedBinaryOp(..) = *rvalue {
-                    Value::ScalarPair(
+                let res = if let Rvalue::CheckedBinaryOp(..) = *rvalue {
+                    let val = Value::ScalarPair(
                         val.into(),
                         Scalar::from_bool(overflow).into(),
-                    )
+                    );
+                    OpTy {
+                        op: ::interpret::Operand::Immediate(val),
+                        layout: place_layout,
+                    }
                 } else {
-                    if overflow {
+                    // With overflow checks, `+`, `-` and `*` are `CheckedBinaryOp`s,
+                    // without them they wrap and `val` is the wrapped result.
+                    let wraps = !self.check_overflow &&
+                        (op == BinOp::Add || op == BinOp::Sub || op == BinOp::Mul);
+                    if overflow && !wraps {
                         let err = EvalErrorKind::Overflow(op).into();
                         let _: Option<()> = self.use_ecx(source_info, |_| Err(err));
                         return None;
                     }
-                    Value::Scalar(val.into())
-                };
-                let res = OpTy {
-                    op: ::interpret::Operand::Immediate(val),
-                    layout: place_layout,
+                    OpTy {
+                        op: ::interpret::Operand::Immediate(Value::Scalar(val.into())),
+                        layout,
+                    }
                 };
                 Some((res, span))
             },
@@ -477,10 +546,22 @@ impl<'b, 'a, 'tcx:'b> ConstPropagator<'b, 'a, 'tcx> {
     }
 }
 
-fn type_size_of<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
-                          param_env: ty::ParamEnv<'tcx>,
-                          ty: ty::Ty<'tcx>) -> Option<u64> {
-    tcx.layout_of(param_env.and(ty)).ok().map(|layout| layout.size.bytes())
+/// Whether MIR building emitted overflow checks for the body of `def_id`,
+/// following the same rules as `hair::cx::Cx::new`.
+fn check_overflow<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> bool {
+    let node_id = match tcx.hir.as_local_node_id(def_id) {
+        Some(node_id) => node_id,
+        None => return true,
+    };
+    if tcx.sess.overflow_checks() ||
+       attr::contains_name(tcx.hir.attrs(node_id), "rustc_inherit_overflow_checks") {
+        return true;
+    }
+    match tcx.hir.body_owner_kind(node_id) {
+        hir::BodyOwnerKind::Const |
+        hir::BodyOwnerKind::Static(_) => true,
+        hir::BodyOwnerKind::Fn => tcx.is_const_fn(def_id),
+    }
 }
 
 struct CanConstProp {
@@ -615,6 +696,7 @@ impl<'b, 'a, 'tcx> Visitor<'tcx> for ConstPropagator<'b, 'a, 'tcx> {
                         .as_local_node_id(self.source.def_id)
                         .expect("some part of a failing const eval must be local");
                     use rustc::mir::interpret::EvalErrorKind::*;
+                    let mut lint = ::rustc::lint::builtin::CONST_ERR;
                     let msg = match msg {
                         Overflow(_) |
                         OverflowNeg |
@@ -639,6 +721,7 @@ impl<'b, 'a, 'tcx> Visitor<'tcx> for ConstPropagator<'b, 'a, 'tcx> {
                                 })) => bits,
                                 _ => bug!("const index not primitive: {:?}", index),
                             };
+                            lint = ::rustc::lint::builtin::UNCONDITIONAL_PANIC;
                             format!(
                                 "index out of bounds: \
                                 the len is {} but the index is {}",
@@ -650,7 +733,7 @@ impl<'b, 'a, 'tcx> Visitor<'tcx> for ConstPropagator<'b, 'a, 'tcx> {
                         _ => return,
                     };
                     self.tcx.lint_node(
-                        ::rustc::lint::builtin::CONST_ERR,
+                        lint,
                         node_id,
                         span,
                         &msg,
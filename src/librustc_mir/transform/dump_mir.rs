@@ -54,7 +54,8 @@ pub fn on_mir_pass<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                              source: MirSource,
                              mir: &Mir<'tcx>,
                              is_after: bool) {
-    if mir_util::dump_enabled(tcx, pass_name, source) {
+    if mir_util::dump_enabled(tcx, pass_name, source) ||
+        super::pass_dump_forced(tcx, source, pass_name) {
         mir_util::dump_mir(tcx,
                            Some(pass_num),
                            pass_name,
@@ -24,7 +24,7 @@ use rustc::ty::subst::{Subst,Substs};
 
 use std::collections::VecDeque;
 use std::iter;
-use transform::{MirPass, MirSource};
+use transform::{MirPass, MirSource, PassOverride, pass_override};
 use super::simplify::{remove_dead_blocks, CfgSimplifier};
 
 use syntax::{attr};
@@ -53,7 +53,8 @@ impl MirPass for Inline {
                           tcx: TyCtxt<'a, 'tcx, 'tcx>,
                           source: MirSource,
                           mir: &mut Mir<'tcx>) {
-        if tcx.sess.opts.debugging_opts.mir_opt_level >= 2 {
+        if tcx.sess.opts.debugging_opts.mir_opt_level >= 2 ||
+            pass_override(tcx, source, &self.name()) == Some(PassOverride::Enable) {
             Inliner { tcx, source }.run_pass(mir);
         }
     }
@@ -13,7 +13,7 @@
 use rustc::hir::def_id::DefId;
 use rustc::middle::lang_items::LangItem;
 use rustc::mir::*;
-use rustc::ty::{List, Ty, TyCtxt, TyKind};
+use rustc::ty::{self, List, Ty, TyCtxt, TyKind};
 use rustc_data_structures::indexed_vec::{Idx};
 use transform::{MirPass, MirSource};
 use syntax;
@@ -26,7 +26,7 @@ impl MirPass for Lower128Bit {
                           _src: MirSource,
                           mir: &mut Mir<'tcx>) {
         let debugging_override = tcx.sess.opts.debugging_opts.lower_128bit_ops;
-        let target_default = tcx.sess.host.options.i128_lowering;
+        let target_default = tcx.sess.target.target.options.i128_lowering;
         if !debugging_override.unwrap_or(target_default) {
             return
         }
@@ -83,6 +83,17 @@ impl Lower128Bit {
                     | StatementKind::Assign(place, Rvalue::CheckedBinaryOp(_, lhs, rhs)) => {
                         (place, lhs, rhs)
                     }
+                    // `-x` becomes `0 - x`, overflow has already been checked by an assert
+                    StatementKind::Assign(place, Rvalue::UnaryOp(UnOp::Neg, operand)) => {
+                        let ty = operand.ty(local_decls, tcx);
+                        let zero = Operand::Constant(box Constant {
+                            span: source_info.span,
+                            ty,
+                            user_ty: None,
+                            literal: ty::Const::from_bits(tcx, 0, ty::ParamEnv::empty().and(ty)),
+                        });
+                        (place, zero, operand)
+                    }
                     _ => bug!("Statement doesn't match pattern any more?"),
                 };
 
@@ -166,6 +177,12 @@ fn lower_to<'a, 'tcx, D>(statement: &Statement<'tcx>, local_decls: &D, tcx: TyCt
                 return item_for_checked_op(bin_op, is_signed);
             }
         },
+        StatementKind::Assign(_, Rvalue::UnaryOp(UnOp::Neg, ref operand)) => {
+            let ty = operand.ty(local_decls, tcx);
+            if let Some(true) = sign_of_128bit(ty) {
+                return Some((LangItem::I128SubFnLangItem, RhsKind::Unchanged));
+            }
+        },
         _ => {},
     }
     None
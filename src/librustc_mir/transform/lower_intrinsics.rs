@@ -0,0 +1,76 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Replaces calls to intrinsics that have a MIR equivalent with that MIR operation.
+
+use rustc::mir::*;
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc_target::spec::abi::Abi;
+use transform::{MirPass, MirSource};
+
+pub struct LowerIntrinsics;
+
+impl MirPass for LowerIntrinsics {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          _src: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        for block in mir.basic_blocks_mut() {
+            let terminator = block.terminator.as_mut().unwrap();
+            let (rvalue, dest, target) = match terminator.kind {
+                TerminatorKind::Call {
+                    func: Operand::Constant(ref func),
+                    ref args,
+                    destination: Some((ref dest, target)),
+                    ..
+                } => match intrinsic_rvalue(tcx, func.ty, args) {
+                    Some(rvalue) => (rvalue, dest.clone(), target),
+                    None => continue,
+                },
+                _ => continue,
+            };
+            block.statements.push(Statement {
+                source_info: terminator.source_info,
+                kind: StatementKind::Assign(dest, rvalue),
+            });
+            terminator.kind = TerminatorKind::Goto { target };
+        }
+    }
+}
+
+/// The MIR operation equivalent to calling the intrinsic of type `func_ty` with `args`, if any.
+fn intrinsic_rvalue<'a, 'tcx>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    func_ty: Ty<'tcx>,
+    args: &[Operand<'tcx>],
+) -> Option<Rvalue<'tcx>> {
+    let (def_id, substs) = match func_ty.sty {
+        ty::FnDef(def_id, substs) => (def_id, substs),
+        _ => return None,
+    };
+    if tcx.fn_sig(def_id).abi() != Abi::RustIntrinsic {
+        return None;
+    }
+    match &tcx.item_name(def_id).as_str()[..] {
+        // Only types with a total order can be compared this way, the codegen of the
+        // intrinsic reports everything else.
+        "three_way_compare" => {
+            let ty = substs.type_at(0);
+            if ty.is_integral() || ty.is_char() || ty.is_bool() {
+                Some(Rvalue::BinaryOp(BinOp::Cmp, args[0].clone(), args[1].clone()))
+            } else {
+                None
+            }
+        }
+        "size_of" => Some(Rvalue::NullaryOp(NullOp::SizeOf, substs.type_at(0))),
+        "min_align_of" => Some(Rvalue::NullaryOp(NullOp::AlignOf, substs.type_at(0))),
+        _ => None,
+    }
+}
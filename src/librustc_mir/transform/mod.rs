@@ -23,6 +23,7 @@ use std::borrow::Cow;
 use syntax::ast;
 use syntax_pos::Span;
 
+pub mod abort_unwinding_calls;
 pub mod add_validation;
 pub mod add_moves_for_packed_drops;
 pub mod cleanup_post_borrowck;
@@ -38,6 +39,7 @@ pub mod promote_consts;
 pub mod qualify_consts;
 pub mod remove_noop_landing_pads;
 pub mod dump_mir;
+pub mod pass_stats;
 pub mod deaggregator;
 pub mod instcombine;
 pub mod copy_prop;
@@ -45,6 +47,7 @@ pub mod const_prop;
 pub mod generator;
 pub mod inline;
 pub mod lower_128bit;
+pub mod lower_intrinsics;
 pub mod uniform_array_move_out;
 
 pub(crate) fn provide(providers: &mut Providers) {
@@ -154,6 +157,58 @@ pub trait MirPass {
                           mir: &mut Mir<'tcx>);
 }
 
+/// Per-function pass control for tests, requested with
+/// `#[rustc_mir(enable_pass = "Name", disable_pass = "Name", dump_pass = "Name")]`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PassOverride {
+    /// Run the pass even if the current `-Z mir-opt-level` would skip it.
+    Enable,
+    /// Never run the pass on this function.
+    Disable,
+}
+
+/// Returns whether the `rustc_mir` attribute of `source` names `pass_name` under `key`.
+fn rustc_mir_pass_attr<'a, 'tcx>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    source: MirSource,
+    key: &str,
+    pass_name: &str,
+) -> bool {
+    if !source.def_id.is_local() || !tcx.has_attr(source.def_id, "rustc_mir") {
+        return false;
+    }
+    tcx.get_attrs(source.def_id).iter()
+        .filter(|attr| attr.check_name("rustc_mir"))
+        .flat_map(|attr| attr.meta_item_list().unwrap_or_default())
+        .filter_map(|item| item.meta_item().cloned())
+        .any(|mi| mi.check_name(key) && mi.value_str().map_or(false, |s| s == pass_name))
+}
+
+/// Looks up whether a test forces `pass_name` on or off for `source`.
+pub fn pass_override<'a, 'tcx>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    source: MirSource,
+    pass_name: &str,
+) -> Option<PassOverride> {
+    if rustc_mir_pass_attr(tcx, source, "disable_pass", pass_name) {
+        Some(PassOverride::Disable)
+    } else if rustc_mir_pass_attr(tcx, source, "enable_pass", pass_name) {
+        Some(PassOverride::Enable)
+    } else {
+        None
+    }
+}
+
+/// Whether the MIR before and after `pass_name` should be dumped for `source`,
+/// independently of `-Z dump-mir`.
+pub fn pass_dump_forced<'a, 'tcx>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    source: MirSource,
+    pass_name: &str,
+) -> bool {
+    rustc_mir_pass_attr(tcx, source, "dump_pass", pass_name)
+}
+
 pub macro run_passes($tcx:ident, $mir:ident, $def_id:ident, $suite_index:expr; $($pass:expr,)*) {{
     let suite_index: usize = $suite_index;
     let run_passes = |mir: &mut _, promoted| {
@@ -168,7 +223,12 @@ pub macro run_passes($tcx:ident, $mir:ident, $def_id:ident, $suite_index:expr; $
                                       &pass.name(), source, mir, is_after);
             };
             run_hooks(mir, index, false);
-            pass.run_pass($tcx, source, mir);
+            // A disabled pass still gets its dumps, so tests can check that nothing changed.
+            if pass_override($tcx, source, &pass.name()) != Some(PassOverride::Disable) {
+                let shape = pass_stats::on_mir_pass_start($tcx, mir);
+                pass.run_pass($tcx, source, mir);
+                pass_stats::on_mir_pass_end($tcx, &pass.name(), shape, mir);
+            }
             run_hooks(mir, index, true);
 
             index += 1;
@@ -192,6 +252,9 @@ fn mir_const<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx Stea
 
     let mut mir = tcx.mir_built(def_id).steal();
     run_passes![tcx, mir, def_id, 0;
+        // Make unwinding out of functions with a foreign ABI abort.
+        abort_unwinding_calls::AbortUnwindingCalls,
+
         // Remove all `EndRegion` statements that are not involved in borrows.
         cleanup_post_borrowck::CleanEndRegions,
 
@@ -259,6 +322,7 @@ fn optimized_mir<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx
         // From here on out, regions are gone.
         erase_regions::EraseRegions,
 
+        lower_intrinsics::LowerIntrinsics,
         lower_128bit::Lower128Bit,
 
 
@@ -0,0 +1,102 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Gathering of per-pass statistics for `-Z mir-pass-stats`.
+
+use std::cmp;
+
+use rustc::mir::Mir;
+use rustc::session::MirPassInfo;
+use rustc::ty::TyCtxt;
+use rustc_data_structures::fx::FxHashMap;
+
+/// A snapshot of the parts of a MIR body we compare before and after a pass.
+pub struct MirShape {
+    /// The `Debug` rendering of every statement, per basic block.
+    statements: Vec<Vec<String>>,
+    locals: usize,
+}
+
+impl MirShape {
+    fn new(mir: &Mir) -> Self {
+        MirShape {
+            statements: mir.basic_blocks().iter().map(|data| {
+                data.statements.iter().map(|stmt| format!("{:?}", stmt)).collect()
+            }).collect(),
+            locals: mir.local_decls.len(),
+        }
+    }
+}
+
+/// Takes a snapshot of `mir` if `-Z mir-pass-stats` is enabled.
+pub fn on_mir_pass_start<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    mir: &Mir<'tcx>,
+) -> Option<MirShape> {
+    if tcx.sess.opts.debugging_opts.mir_pass_stats {
+        Some(MirShape::new(mir))
+    } else {
+        None
+    }
+}
+
+/// Compares `mir` against the snapshot taken before the pass ran and records the difference.
+pub fn on_mir_pass_end<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    pass_name: &str,
+    before: Option<MirShape>,
+    mir: &Mir<'tcx>,
+) {
+    let before = match before {
+        Some(before) => before,
+        None => return,
+    };
+    let after = MirShape::new(mir);
+
+    let mut info = MirPassInfo {
+        runs: 1,
+        ..MirPassInfo::default()
+    };
+
+    // Match up the statements of each block by content, so that statements which merely
+    // moved within their block are not counted. What is left over on both sides counts as
+    // rewritten, the rest as removed or added.
+    let empty = Vec::new();
+    let num_blocks = cmp::max(before.statements.len(), after.statements.len());
+    for bb in 0..num_blocks {
+        let old = before.statements.get(bb).unwrap_or(&empty);
+        let new = after.statements.get(bb).unwrap_or(&empty);
+        let mut unmatched: FxHashMap<&String, usize> = FxHashMap();
+        for stmt in old {
+            *unmatched.entry(stmt).or_insert(0) += 1;
+        }
+        let mut matched = 0;
+        for stmt in new {
+            if let Some(count) = unmatched.get_mut(&stmt) {
+                if *count > 0 {
+                    *count -= 1;
+                    matched += 1;
+                }
+            }
+        }
+        let (old_left, new_left) = (old.len() - matched, new.len() - matched);
+        let rewritten = cmp::min(old_left, new_left);
+        info.statements_rewritten += rewritten;
+        info.statements_removed += old_left - rewritten;
+        info.statements_added += new_left - rewritten;
+    }
+
+    info.blocks_before = before.statements.len();
+    info.blocks_after = after.statements.len();
+    info.locals_before = before.locals;
+    info.locals_after = after.locals;
+
+    tcx.sess.mir_pass_stats.borrow_mut().record_pass(pass_name, info);
+}
@@ -20,6 +20,7 @@ use rustc_data_structures::indexed_vec::{IndexVec, Idx};
 use rustc_data_structures::fx::FxHashSet;
 use rustc::hir;
 use rustc::hir::def_id::DefId;
+use rustc::hir::intravisit::{self, NestedVisitorMap, Visitor as HirVisitor};
 use rustc::mir::interpret::ConstValue;
 use rustc::traits::{self, TraitEngine};
 use rustc::ty::{self, TyCtxt, Ty, TypeFoldable};
@@ -36,6 +37,7 @@ use syntax::feature_gate::{UnstableFeatures, feature_err, emit_feature_err, Gate
 use syntax_pos::{Span, DUMMY_SP};
 
 use std::fmt;
+use std::slice;
 use rustc_data_structures::sync::Lrc;
 use std::usize;
 
@@ -118,7 +120,8 @@ struct Qualifier<'a, 'gcx: 'a+'tcx, 'tcx: 'a> {
     qualif: Qualif,
     const_fn_arg_vars: BitArray<Local>,
     temp_promotion_state: IndexVec<Local, TempState>,
-    promotion_candidates: Vec<Candidate>
+    promotion_candidates: Vec<Candidate>,
+    short_circuit_spans: FxHashSet<Span>
 }
 
 impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
@@ -140,6 +143,14 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
             local_qualif[arg] = Some(qualif);
         }
 
+        let mut short_circuit_spans = FxHashSet();
+        if mode != Mode::Fn && tcx.sess.features_untracked().const_short_circuit {
+            if let Some(node_id) = tcx.hir.as_local_node_id(def_id) {
+                let body = tcx.hir.body(tcx.hir.body_owned_by(node_id));
+                ShortCircuitSpans(&mut short_circuit_spans).visit_body(body);
+            }
+        }
+
         Qualifier {
             mode,
             span: mir.span,
@@ -152,7 +163,20 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
             qualif: Qualif::empty(),
             const_fn_arg_vars: BitArray::new(mir.local_decls.len()),
             temp_promotion_state: temps,
-            promotion_candidates: vec![]
+            promotion_candidates: vec![],
+            short_circuit_spans,
+        }
+    }
+
+    /// Under `-Z unleash-the-miri-inside-of-you` const checks are downgraded to warnings so that
+    /// the evaluator gets to try the code regardless. Returns whether the caller should skip
+    /// emitting its error.
+    fn unleashed(&self) -> bool {
+        if self.tcx.sess.opts.debugging_opts.unleash_the_miri_inside_of_you {
+            self.tcx.sess.span_warn(self.span, "skipping const checks");
+            true
+        } else {
+            false
         }
     }
 
@@ -161,7 +185,7 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
     // slightly pointless (even with feature-gating).
     fn not_const(&mut self) {
         self.add(Qualif::NOT_CONST);
-        if self.mode != Mode::Fn {
+        if self.mode != Mode::Fn && !self.unleashed() {
             let mut err = struct_span_err!(
                 self.tcx.sess,
                 self.span,
@@ -182,7 +206,7 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
     /// Error about extra statements in a constant.
     fn statement_like(&mut self) {
         self.add(Qualif::NOT_CONST);
-        if self.mode != Mode::Fn {
+        if self.mode != Mode::Fn && !self.unleashed() {
             let mut err = feature_err(
                 &self.tcx.sess.parse_sess,
                 "const_let",
@@ -225,11 +249,14 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
         trace!("assign: {:?}", dest);
         let qualif = self.qualif;
         let span = self.span;
+        let short_circuit = self.tcx.sess.features_untracked().const_short_circuit;
         let store = |slot: &mut Option<Qualif>| {
-            if slot.is_some() {
-                span_bug!(span, "multiple assignments to {:?}", dest);
+            match *slot {
+                // The result of `&&` and `||` is assigned in both branches.
+                Some(old) if short_circuit => *slot = Some(old | qualif),
+                Some(_) => span_bug!(span, "multiple assignments to {:?}", dest),
+                None => *slot = Some(qualif),
             }
-            *slot = Some(qualif);
         };
 
         // Only handle promotable temps in non-const functions.
@@ -249,7 +276,10 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
                                    self.mir.local_kind(index) == LocalKind::Arg) &&
                                    self.tcx.sess.features_untracked().const_let => {
                 debug!("store to var {:?}", index);
-                self.local_qualif[index] = Some(self.qualif);
+                match self.local_qualif[index] {
+                    Some(old) => self.local_qualif[index] = Some(old | qualif),
+                    None => self.local_qualif[index] = Some(qualif),
+                }
             }
             Place::Local(index) if self.mir.local_kind(index) == LocalKind::Temp ||
                                    self.mir.local_kind(index) == LocalKind::ReturnPointer => {
@@ -269,6 +299,29 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
                 // already for the Box allocation Rvalue.
             }
 
+            // Writing to a field of a union fully initializes it, so we can
+            // treat it like an assignment to the union itself.
+            Place::Projection(box Projection {
+                base: Place::Local(index),
+                elem: ProjectionElem::Field(..)
+            }) if self.mir.local_kind(index) == LocalKind::Var
+               && self.mir.local_decls[index].ty.ty_adt_def().map_or(false, |def| def.is_union())
+               && self.tcx.sess.features_untracked().const_let => {
+                if self.mode == Mode::ConstFn &&
+                   !self.tcx.sess.features_untracked().const_fn_union {
+                    emit_feature_err(
+                        &self.tcx.sess.parse_sess, "const_fn_union",
+                        self.span, GateIssue::Language,
+                        "unions in const fn are unstable",
+                    );
+                }
+                debug!("store to union field of var {:?}", index);
+                // The other bytes of the union are still around, so keep their qualifications.
+                let mut qualif = self.local_qualif[index].map_or(qualif, |old| old | qualif);
+                qualif.restrict(self.mir.local_decls[index].ty, self.tcx, self.param_env);
+                self.local_qualif[index] = Some(qualif);
+            }
+
             // This must be an explicit assignment.
             _ => {
                 // Catch more errors in the destination.
@@ -284,24 +337,43 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
 
         let mir = self.mir;
 
-        let mut seen_blocks = BitArray::new(mir.basic_blocks().len());
-        let mut bb = START_BLOCK;
-        loop {
-            seen_blocks.insert(bb.index());
+        // Blocks are qualified in reverse postorder, so that every block is
+        // only visited after all the blocks jumping to it, unless there is
+        // a loop. Blocks that can only be reached by unwinding are skipped.
+        let rpo: Vec<_> = traversal::reverse_postorder(mir).map(|(bb, _)| bb).collect();
+        let mut rpo_index = IndexVec::from_elem(usize::MAX, mir.basic_blocks());
+        for (index, &bb) in rpo.iter().enumerate() {
+            rpo_index[bb] = index;
+        }
+        let mut reachable = BitArray::new(mir.basic_blocks().len());
+        reachable.insert(START_BLOCK.index());
+
+        'blocks: for &bb in &rpo {
+            if !reachable.contains(bb.index()) {
+                continue;
+            }
 
             self.visit_basic_block_data(bb, &mir[bb]);
 
-            let target = match mir[bb].terminator().kind {
-                TerminatorKind::Goto { target } |
-                TerminatorKind::Drop { target, .. } |
-                TerminatorKind::Assert { target, .. } |
-                TerminatorKind::Call { destination: Some((_, target)), .. } => {
-                    Some(target)
+            let terminator = mir[bb].terminator();
+            let targets = match terminator.kind {
+                TerminatorKind::Goto { ref target } |
+                TerminatorKind::Drop { ref target, .. } |
+                TerminatorKind::Assert { ref target, .. } |
+                TerminatorKind::Call { destination: Some((_, ref target)), .. } => {
+                    Some(slice::from_ref(target))
                 }
 
                 // Non-terminating calls cannot produce any value.
                 TerminatorKind::Call { destination: None, .. } => {
-                    break;
+                    Some(&[][..])
+                }
+
+                // The branches introduced by `&&` and `||`.
+                TerminatorKind::SwitchInt { switch_ty, ref targets, .. }
+                    if switch_ty.is_bool() && targets.len() == 2 &&
+                       self.short_circuit_spans.contains(&terminator.source_info.span) => {
+                    Some(&targets[..])
                 }
 
                 TerminatorKind::SwitchInt {..} |
@@ -354,19 +426,24 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
                         }
                     }
 
-                    break;
+                    Some(&[][..])
                 }
             };
 
-            match target {
-                // No loops allowed.
-                Some(target) if !seen_blocks.contains(target.index()) => {
-                    bb = target;
-                }
-                _ => {
+            let targets = match targets {
+                Some(targets) => targets,
+                None => {
                     self.not_const();
                     break;
                 }
+            };
+            for &target in targets {
+                // No loops allowed.
+                if rpo_index[target] <= rpo_index[bb] {
+                    self.not_const();
+                    break 'blocks;
+                }
+                reachable.insert(target.index());
             }
         }
 
@@ -407,6 +484,25 @@ impl<'a, 'tcx> Qualifier<'a, 'tcx, 'tcx> {
     }
 }
 
+/// Collects the spans of the `&&` and `||` expressions of a body, the only
+/// branches `#![feature(const_short_circuit)]` allows in constants.
+struct ShortCircuitSpans<'a>(&'a mut FxHashSet<Span>);
+
+impl<'a, 'tcx> HirVisitor<'tcx> for ShortCircuitSpans<'a> {
+    fn nested_visit_map<'this>(&'this mut self) -> NestedVisitorMap<'this, 'tcx> {
+        NestedVisitorMap::None
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr) {
+        if let hir::ExprKind::Binary(op, ..) = expr.node {
+            if op.node == hir::BinOpKind::And || op.node == hir::BinOpKind::Or {
+                self.0.insert(expr.span);
+            }
+        }
+        intravisit::walk_expr(self, expr);
+    }
+}
+
 /// Accumulates an Rvalue or Call's effects in self.qualif.
 /// For functions (constant or not), it also records
 /// candidates for promotion in promotion_candidates.
@@ -475,7 +571,7 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
                 }
                 self.add(Qualif::NOT_CONST);
 
-                if self.mode != Mode::Fn {
+                if self.mode != Mode::Fn && !self.unleashed() {
                     let mut err = struct_span_err!(self.tcx.sess, self.span, E0013,
                                                    "{}s cannot refer to statics, use \
                                                     a constant instead", self.mode);
@@ -516,7 +612,9 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
                         }
 
                         ProjectionElem::Field(..) |
-                        ProjectionElem::Index(_) => {
+                        ProjectionElem::Index(_) |
+                        ProjectionElem::ConstantIndex {..} |
+                        ProjectionElem::Subslice {..} => {
                             let base_ty = proj.base.ty(this.mir, this.tcx).to_ty(this.tcx);
                             if let Some(def) = base_ty.ty_adt_def() {
                                 if def.is_union() {
@@ -544,8 +642,6 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
                             this.qualif.restrict(ty, this.tcx, this.param_env);
                         }
 
-                        ProjectionElem::ConstantIndex {..} |
-                        ProjectionElem::Subslice {..} |
                         ProjectionElem::Downcast(..) => {
                             this.not_const()
                         }
@@ -569,11 +665,16 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
                 }
             }
             Operand::Constant(ref constant) => {
-                if let ConstValue::Unevaluated(def_id, _) = constant.literal.val {
-                    // Don't peek inside trait associated constants.
-                    if self.tcx.trait_of_item(def_id).is_some() {
-                        self.add_type(constant.literal.ty);
+                if let ConstValue::Unevaluated(def_id, substs) = constant.literal.val {
+                    // Trait associated constants can only be looked into once we know
+                    // which impl provides them, otherwise we have to rely on the type.
+                    let def_id = if self.tcx.trait_of_item(def_id).is_some() {
+                        ty::Instance::resolve(self.tcx, self.param_env, def_id, substs)
+                            .map(|instance| instance.def_id())
                     } else {
+                        Some(def_id)
+                    };
+                    if let Some(def_id) = def_id {
                         let (bits, _) = self.tcx.at(constant.span).mir_const_qualif(def_id);
 
                         let qualif = Qualif::from_bits(bits).expect("invalid mir_const_qualif");
@@ -583,6 +684,8 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
                         // the definition, e.g. impl associated const
                         // with type parameters, take it into account.
                         self.qualif.restrict(constant.literal.ty, self.tcx, self.param_env);
+                    } else {
+                        self.add_type(constant.literal.ty);
                     }
                 }
             }
@@ -620,6 +723,7 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
             Rvalue::UnaryOp(UnOp::Neg, _) |
             Rvalue::UnaryOp(UnOp::Not, _) |
             Rvalue::NullaryOp(NullOp::SizeOf, _) |
+            Rvalue::NullaryOp(NullOp::AlignOf, _) |
             Rvalue::CheckedBinaryOp(..) |
             Rvalue::Cast(CastKind::ReifyFnPointer, ..) |
             Rvalue::Cast(CastKind::UnsafeFnPointer, ..) |
@@ -656,7 +760,7 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
 
                     if forbidden_mut {
                         self.add(Qualif::NOT_CONST);
-                        if self.mode != Mode::Fn {
+                        if self.mode != Mode::Fn && !self.unleashed() {
                             let mut err = struct_span_err!(self.tcx.sess,  self.span, E0017,
                                                            "references in {}s may only refer \
                                                             to immutable values", self.mode);
@@ -684,7 +788,7 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
                         // NOT_CONST (see `if forbidden_mut` below), to avoid
                         // duplicate errors (from reborrowing, for example).
                         self.qualif = self.qualif - Qualif::MUTABLE_INTERIOR;
-                        if self.mode != Mode::Fn {
+                        if self.mode != Mode::Fn && !self.unleashed() {
                             span_err!(self.tcx.sess, self.span, E0492,
                                       "cannot borrow a constant which may contain \
                                        interior mutability, create a static instead");
@@ -772,7 +876,7 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
 
             Rvalue::NullaryOp(NullOp::Box, _) => {
                 self.add(Qualif::NOT_CONST);
-                if self.mode != Mode::Fn {
+                if self.mode != Mode::Fn && !self.unleashed() {
                     let mut err = struct_span_err!(self.tcx.sess, self.span, E0010,
                                                    "allocations are not allowed in {}s", self.mode);
                     err.span_label(self.span, format!("allocation not allowed in {}s", self.mode));
@@ -823,24 +927,135 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
                         match &self.tcx.item_name(def_id).as_str()[..] {
                             | "size_of"
                             | "min_align_of"
+                            | "size_of_val"
+                            | "min_align_of_val"
                             | "type_id"
+                            | "assume"
+                            | "likely"
+                            | "unlikely"
+                            | "abort"
+                            | "type_name"
+                            | "needs_drop"
+                            | "discriminant_value"
                             | "bswap"
+                            | "bitreverse"
                             | "ctpop"
                             | "cttz"
                             | "cttz_nonzero"
                             | "ctlz"
-                            | "ctlz_nonzero" => is_const_fn = Some(def_id),
+                            | "ctlz_nonzero"
+                            | "ptr_guaranteed_eq"
+                            | "ptr_guaranteed_ne"
+                            | "offset"
+                            | "arith_offset"
+                            | "ptr_offset_from"
+                            | "three_way_compare"
+                            | "add_with_overflow"
+                            | "sub_with_overflow"
+                            | "mul_with_overflow"
+                            | "overflowing_add"
+                            | "overflowing_sub"
+                            | "overflowing_mul"
+                            | "exact_div"
+                            | "unchecked_div"
+                            | "unchecked_rem"
+                            | "unchecked_shl"
+                            | "unchecked_shr"
+                            | "saturating_add"
+                            | "saturating_sub"
+                            | "rotate_left"
+                            | "rotate_right"
+                            | "sqrtf32"
+                            | "sqrtf64"
+                            | "powif32"
+                            | "powif64"
+                            | "fabsf32"
+                            | "fabsf64"
+                            | "floorf32"
+                            | "floorf64"
+                            | "ceilf32"
+                            | "ceilf64"
+                            | "truncf32"
+                            | "truncf64"
+                            | "roundf32"
+                            | "roundf64"
+                            | "float_to_int_unchecked"
+                            // These run their bodies in `intrinsics::fallback`.
+                            | "prefetch_read_data"
+                            | "prefetch_write_data"
+                            | "prefetch_read_instruction"
+                            | "prefetch_write_instruction" => is_const_fn = Some(def_id),
+
+                            // These read and write memory through raw pointers, just
+                            // like dereferencing them.
+                            | "copy"
+                            | "copy_nonoverlapping"
+                            | "write_bytes" if self.mode != Mode::Fn => {
+                                if !self.tcx.sess.features_untracked().const_raw_ptr_deref {
+                                    emit_feature_err(
+                                        &self.tcx.sess.parse_sess, "const_raw_ptr_deref",
+                                        self.span, GateIssue::Language,
+                                        &format!("dereferencing raw pointers in {}s is unstable",
+                                                 self.mode),
+                                    );
+                                }
+                                is_const_fn = Some(def_id);
+                            }
+
+                            // The result of a transmute is validated when the
+                            // evaluation of the constant is complete.
+                            "transmute" if self.mode != Mode::Fn => {
+                                if !self.tcx.sess.features_untracked().const_transmute {
+                                    emit_feature_err(
+                                        &self.tcx.sess.parse_sess, "const_transmute",
+                                        self.span, GateIssue::Language,
+                                        &format!("The use of std::mem::transmute() \
+                                                  is gated in {}s", self.mode),
+                                    );
+                                }
+                                is_const_fn = Some(def_id);
+                            }
+
+                            // Const evaluation has a single thread, so atomics are plain
+                            // memory accesses.
+                            name if name.starts_with("atomic_") => is_const_fn = Some(def_id),
 
                             name if name.starts_with("simd_shuffle") => {
                                 is_shuffle = true;
+                                is_const_fn = Some(def_id);
                             }
 
+                            | "simd_add"
+                            | "simd_sub"
+                            | "simd_mul"
+                            | "simd_div"
+                            | "simd_rem"
+                            | "simd_shl"
+                            | "simd_shr"
+                            | "simd_and"
+                            | "simd_or"
+                            | "simd_xor"
+                            | "simd_eq"
+                            | "simd_ne"
+                            | "simd_lt"
+                            | "simd_le"
+                            | "simd_gt"
+                            | "simd_ge"
+                            | "simd_extract"
+                            | "simd_insert" => is_const_fn = Some(def_id),
+
                             _ => {}
                         }
                     }
                     _ => {
                         if self.tcx.is_const_fn(def_id) || self.is_const_panic_fn(def_id) {
                             is_const_fn = Some(def_id);
+                        } else if self.mode != Mode::Fn &&
+                                  self.tcx.sess.features_untracked().const_trait_call &&
+                                  self.tcx.trait_of_item(def_id).is_some() {
+                            // The impl is only selected when the constant is evaluated,
+                            // which errors if its method turns out not to be a const fn.
+                            is_const_fn = Some(def_id);
                         }
                     }
                 }
@@ -938,7 +1153,7 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
                 }
             } else {
                 self.qualif = Qualif::NOT_CONST;
-                if self.mode != Mode::Fn {
+                if self.mode != Mode::Fn && !self.unleashed() {
                     // FIXME(#24111) Remove this check when const fn stabilizes
                     let (msg, note) = if let UnstableFeatures::Disallow =
                             self.tcx.sess.opts.unstable_features {
@@ -996,7 +1211,7 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
                 if let Some(span) = needs_drop {
                     // Double-check the type being dropped, to minimize false positives.
                     let ty = place.ty(self.mir, self.tcx).to_ty(self.tcx);
-                    if ty.needs_drop(self.tcx, self.param_env) {
+                    if ty.needs_drop(self.tcx, self.param_env) && !self.unleashed() {
                         struct_span_err!(self.tcx.sess, span, E0493,
                                          "destructors cannot be evaluated at compile-time")
                             .span_label(span, format!("{}s cannot evaluate destructors",
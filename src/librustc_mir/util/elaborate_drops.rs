@@ -337,6 +337,12 @@ impl<'l, 'b, 'tcx, D> DropCtxt<'l, 'b, 'tcx, D>
         self.drop_ladder(fields, succ, unwind).0
     }
 
+    /// Drops the contents of a box, then frees it with the `box_free` lang item.
+    ///
+    /// For unsized contents the metadata in the box pointer drives both steps:
+    /// `[T]` contents are dropped by a loop over their length (`open_drop_for_array`),
+    /// trait objects by the drop glue in their vtable (`open_drop_for_trait_object`),
+    /// and `box_free` derives the size and alignment to deallocate from that metadata.
     fn open_drop_for_box<'a>(&mut self, adt: &'tcx ty::AdtDef, substs: &'tcx Substs<'tcx>)
                              -> BasicBlock
     {
@@ -355,6 +361,58 @@ impl<'l, 'b, 'tcx, D> DropCtxt<'l, 'b, 'tcx, D>
         self.drop_subpath(&interior, interior_path, succ, unwind_succ)
     }
 
+    /// Drops a trait object by passing a pointer to it to the `drop_trait_object` lang item,
+    /// which calls the drop glue from the vtable. Without that lang item (e.g. for an old
+    /// libcore), this falls back to a `Drop` of the unsized place, which the backend handles.
+    fn open_drop_for_trait_object<'a>(&mut self) -> BasicBlock {
+        debug!("open_drop_for_trait_object({:?})", self);
+        let tcx = self.tcx();
+        let unwind = self.unwind; // FIXME(#43234)
+        let succ = self.succ;
+        let drop_fn = match tcx.lang_items().drop_trait_object_fn() {
+            Some(drop_fn) => drop_fn,
+            None => return self.complete_drop(Some(DropFlagMode::Deep), succ, unwind),
+        };
+
+        let ty = self.place_ty(self.place);
+        let ref_place = Place::Local(self.new_temp(tcx.mk_mut_ref(tcx.types.re_erased, ty)));
+        let ptr_ty = tcx.mk_mut_ptr(ty);
+        let ptr_place = Place::Local(self.new_temp(ptr_ty));
+        let unit_temp = Place::Local(self.new_temp(tcx.mk_nil()));
+        // ref = &mut P;
+        // ptr = ref as *mut dyn Trait;
+        // drop_trait_object::<dyn Trait>(ptr);
+        let statements = vec![
+            self.assign(&ref_place, Rvalue::Ref(
+                tcx.types.re_erased,
+                BorrowKind::Mut { allow_two_phase_borrow: false },
+                self.place.clone()
+            )),
+            self.assign(&ptr_place, Rvalue::Cast(
+                CastKind::Misc, Operand::Move(ref_place.clone()), ptr_ty
+            )),
+        ];
+        let call = TerminatorKind::Call {
+            func: Operand::function_handle(
+                tcx, drop_fn, tcx.intern_substs(&[ty.into()]), self.source_info.span
+            ),
+            args: vec![Operand::Move(ptr_place)],
+            destination: Some((unit_temp, succ)),
+            cleanup: unwind.into_option(),
+        };
+        let drop_block = self.elaborator.patch().new_block(BasicBlockData {
+            statements,
+            is_cleanup: unwind.is_cleanup(),
+            terminator: Some(Terminator {
+                source_info: self.source_info,
+                kind: call,
+            }),
+        });
+
+        let reset_block = self.drop_flag_reset_block(DropFlagMode::Deep, drop_block, unwind);
+        self.drop_flag_test_block(reset_block, succ, unwind)
+    }
+
     fn open_drop_for_adt<'a>(&mut self, adt: &'tcx ty::AdtDef, substs: &'tcx Substs<'tcx>)
                              -> BasicBlock {
         debug!("open_drop_for_adt({:?}, {:?}, {:?})", self, adt, substs);
@@ -811,11 +869,7 @@ impl<'l, 'b, 'tcx, D> DropCtxt<'l, 'b, 'tcx, D>
                     self.open_drop_for_adt(def, substs)
                 }
             }
-            ty::Dynamic(..) => {
-                let unwind = self.unwind; // FIXME(#43234)
-                let succ = self.succ;
-                self.complete_drop(Some(DropFlagMode::Deep), succ, unwind)
-            }
+            ty::Dynamic(..) => self.open_drop_for_trait_object(),
             ty::Array(ety, size) => {
                 let size = size.assert_usize(self.tcx());
                 self.open_drop_for_array(ety, size)
@@ -3749,6 +3749,24 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
         ty
     }
 
+    /// Whether `expr` may be used as the operand of a repeat expression even though its
+    /// type is not `Copy`. RFC 2203 allows this for paths to constants: every element
+    /// is a fresh copy of the constant's value, just like when naming the constant N times.
+    fn is_const_repeat_operand(&self, expr: &hir::Expr) -> bool {
+        if !self.tcx.features().const_in_array_repeat_expressions {
+            return false;
+        }
+        match expr.node {
+            hir::ExprKind::Path(ref qpath) => {
+                match self.tables.borrow().qpath_def(qpath, expr.hir_id) {
+                    Def::Const(..) | Def::AssociatedConst(..) => true,
+                    _ => false,
+                }
+            }
+            _ => false,
+        }
+    }
+
     fn check_expr_kind(&self,
                        expr: &'gcx hir::Expr,
                        expected: Expectation<'tcx>,
@@ -4229,9 +4247,9 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
 
                 if let Ok(count) = count {
                     let zero_or_one = count.assert_usize(tcx).map_or(false, |count| count <= 1);
-                    if !zero_or_one {
+                    if !zero_or_one && !self.is_const_repeat_operand(element) {
                         // For [foo, ..n] where n > 1, `foo` must have
-                        // Copy type:
+                        // Copy type, unless it is a constant (RFC 2203):
                         let lang_item = self.tcx.require_lang_item(lang_items::CopyTraitLangItem);
                         self.require_type_meets(t, expr.span, traits::RepeatVec, lang_item);
                     }
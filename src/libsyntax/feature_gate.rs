@@ -227,6 +227,12 @@ declare_features! (
     // Allows panicking during const eval (produces compile-time errors)
     (active, const_panic, "1.30.0", Some(51999), None),
 
+    // Allows `[CONST; N]` where `CONST` is a constant of a non-`Copy` type (RFC 2203)
+    (active, const_in_array_repeat_expressions, "1.30.0", Some(49147), None),
+
+    // Allows using `mem::transmute` in constants and const fn
+    (active, const_transmute, "1.30.0", Some(53605), None),
+
     // Allows using #[prelude_import] on glob `use` items.
     //
     // rustc internal
@@ -499,6 +505,15 @@ declare_features! (
 
     // unsized rvalues at arguments and parameters
     (active, unsized_locals, "1.30.0", Some(48055), None),
+
+    // Allows short-circuiting `&&` and `||` in constants and const fn
+    (active, const_short_circuit, "1.30.0", None, None),
+
+    // Allows `const fn` in trait impls, and calling trait methods in constants and const fn
+    (active, const_trait_call, "1.30.0", None, None),
+
+    // Allows `#![const_eval_limit="N"]` to change the number of steps const evaluation may take
+    (active, const_eval_limit, "1.30.0", None, None),
 );
 
 declare_features! (
@@ -928,6 +943,18 @@ pub const BUILTIN_ATTRIBUTES: &'static [(&'static str, AttributeType, AttributeG
                                       is just used for rustc unit tests \
                                       and will never be stable",
                                      cfg_fn!(rustc_attrs))),
+    ("rustc_intrinsic_fallback", Whitelisted, Gated(Stability::Unstable,
+                                                    "rustc_attrs",
+                                                    "the `#[rustc_intrinsic_fallback]` attribute \
+                                                     is used to give intrinsics a MIR body for \
+                                                     the const evaluator and will never be stable",
+                                                    cfg_fn!(rustc_attrs))),
+    ("rustc_custom_mir", Whitelisted, Gated(Stability::Unstable,
+                                            "rustc_attrs",
+                                            "the `#[rustc_custom_mir]` attribute \
+                                             is just used for rustc unit tests \
+                                             and will never be stable",
+                                            cfg_fn!(rustc_attrs))),
     ("rustc_inherit_overflow_checks", Whitelisted, Gated(Stability::Unstable,
                                                          "rustc_attrs",
                                                          "the `#[rustc_inherit_overflow_checks]` \
@@ -1140,6 +1167,11 @@ pub const BUILTIN_ATTRIBUTES: &'static [(&'static str, AttributeType, AttributeG
     ("no_builtins", CrateLevel, Ungated),
     ("recursion_limit", CrateLevel, Ungated),
     ("type_length_limit", CrateLevel, Ungated),
+    ("const_eval_limit", CrateLevel, Gated(Stability::Unstable,
+                                           "const_eval_limit",
+                                           "the `#[const_eval_limit]` attribute is \
+                                            an experimental feature",
+                                           cfg_fn!(const_eval_limit))),
 ];
 
 // cfg(...)'s that are feature gated
@@ -0,0 +1,35 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "lib"]
+
+use std::ops::Range;
+
+extern "Rust" {
+    fn take_range(r: Range<usize>);
+}
+
+// Small aggregates built in the caller are passed as two immediates without
+// going through a temporary on the stack.
+
+// CHECK-LABEL: @pass_range
+#[no_mangle]
+pub fn pass_range(start: usize, end: usize) {
+    // CHECK-NOT: alloca
+    // CHECK: call void @take_range([[USIZE:i[0-9]+]] %start, [[USIZE]] %end)
+    unsafe { take_range(start..end) }
+}
+
+// CHECK-LABEL: define { i32, i8 } @make_pair(i32 %a, i1 zeroext %b)
+#[no_mangle]
+pub fn make_pair(a: i32, b: bool) -> (i32, bool) {
+    // CHECK-NOT: alloca
+    (a, b)
+}
@@ -23,7 +23,7 @@ fn main() {
     let d = 42u8 - (42u8 + 1);
     //~^ ERROR const_err
     let _e = [5u8][1];
-    //~^ ERROR const_err
+    //~^ ERROR unconditional_panic
     black_box(b);
     black_box(c);
     black_box(d);
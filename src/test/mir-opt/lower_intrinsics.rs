@@ -0,0 +1,51 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Intrinsics with a MIR equivalent become that MIR operation, so that MIR
+// optimizations and the const evaluator see them as such.
+
+#![feature(core_intrinsics)]
+
+use std::cmp::Ordering;
+use std::intrinsics;
+
+fn size_and_align<T>() -> (usize, usize) {
+    unsafe { (intrinsics::size_of::<T>(), intrinsics::min_align_of::<T>()) }
+}
+
+fn compare(a: i32, b: i32) -> Ordering {
+    unsafe { intrinsics::three_way_compare(a, b) }
+}
+
+fn main() {
+    size_and_align::<u64>();
+    compare(1, 2);
+}
+
+// END RUST SOURCE
+// START rustc.size_and_align.LowerIntrinsics.after.mir
+//     bb0: {
+//         ...
+//         _1 = SizeOf(T);
+//         goto -> bb1;
+//     }
+//     bb1: {
+//         ...
+//         _2 = AlignOf(T);
+//         goto -> bb2;
+//     }
+// END rustc.size_and_align.LowerIntrinsics.after.mir
+// START rustc.compare.LowerIntrinsics.after.mir
+//     bb0: {
+//         ...
+//         _0 = Cmp(move _3, move _4);
+//         goto -> bb1;
+//     }
+// END rustc.compare.LowerIntrinsics.after.mir
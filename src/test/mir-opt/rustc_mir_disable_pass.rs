@@ -0,0 +1,31 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tests that `#[rustc_mir(disable_pass = "...")]` turns off a single pass on a single function.
+
+#![feature(rustc_attrs)]
+
+#[rustc_mir(disable_pass = "SimplifyBranches-initial")]
+fn disabled() {
+    if false {
+        println!("hello world!");
+    }
+}
+
+fn main() {
+    disabled();
+}
+
+// END RUST SOURCE
+// START rustc.disabled.SimplifyBranches-initial.after.mir
+// bb0: {
+//     switchInt(const false) -> [false: bb3, otherwise: bb2];
+// }
+// END rustc.disabled.SimplifyBranches-initial.after.mir
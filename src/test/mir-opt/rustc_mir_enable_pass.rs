@@ -0,0 +1,38 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z mir-opt-level=0
+
+// Tests that `#[rustc_mir(enable_pass = "...")]` runs a pass on a single function even though
+// the MIR optimization level would skip it.
+
+#![feature(rustc_attrs)]
+
+#[rustc_mir(enable_pass = "InstCombine")]
+fn enabled(x: [u8; 2]) -> u8 {
+    x[0]
+}
+
+fn not_enabled(x: [u8; 2]) -> u8 {
+    x[0]
+}
+
+fn main() {
+    enabled([1, 2]);
+    not_enabled([1, 2]);
+}
+
+// END RUST SOURCE
+// START rustc.enabled.InstCombine.after.mir
+//     _3 = const 2usize;
+// END rustc.enabled.InstCombine.after.mir
+// START rustc.not_enabled.InstCombine.after.mir
+//     _3 = Len(_1);
+// END rustc.not_enabled.InstCombine.after.mir
@@ -12,7 +12,7 @@
 
 const C: [u32; 5] = [0; 5];
 
-#[allow(const_err)]
+#[allow(unconditional_panic)]
 fn test() -> u32 {
     C[10]
 }
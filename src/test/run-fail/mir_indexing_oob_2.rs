@@ -12,7 +12,7 @@
 
 const C: &'static [u8; 5] = b"hello";
 
-#[allow(const_err)]
+#[allow(unconditional_panic)]
 fn test() -> u8 {
     C[10]
 }
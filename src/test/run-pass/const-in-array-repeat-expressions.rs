@@ -0,0 +1,50 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// RFC 2203: constants of non-`Copy` types can be used in repeat expressions,
+// both at runtime and during const evaluation.
+
+#![feature(const_in_array_repeat_expressions)]
+
+#[derive(Debug, PartialEq)]
+struct Bar(u32);
+
+const BAR: Bar = Bar(42);
+const NONE: Option<Box<u32>> = None;
+const EMPTY: Vec<u8> = Vec::new();
+const NAME: Option<&'static str> = Some("bar");
+
+struct Holder;
+impl Holder {
+    const BAR: Bar = Bar(7);
+}
+
+const ARR: [Bar; 3] = [BAR; 3];
+static STATIC_ARR: [Option<&'static str>; 4] = [NAME; 4];
+
+fn main() {
+    let arr: [Bar; 4] = [BAR; 4];
+    assert_eq!(arr, [Bar(42), Bar(42), Bar(42), Bar(42)]);
+
+    let mut boxes: [Option<Box<u32>>; 3] = [NONE; 3];
+    boxes[1] = Some(Box::new(1));
+    assert_eq!(boxes, [None, Some(Box::new(1)), None]);
+
+    let mut vecs: [Vec<u8>; 2] = [EMPTY; 2];
+    vecs[0].push(1);
+    assert!(vecs[1].is_empty());
+
+    let assoc: [Bar; 2] = [Holder::BAR; 2];
+    assert_eq!(assoc, [Bar(7), Bar(7)]);
+
+    assert_eq!(ARR, [Bar(42), Bar(42), Bar(42)]);
+    assert_eq!(STATIC_ARR, [Some("bar"); 4]);
+}
@@ -0,0 +1,30 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -C overflow-checks=off
+
+// Without overflow checks arithmetic wraps at runtime, so const propagation
+// must not report it, and must fold it to the wrapped value.
+
+#![deny(const_err)]
+
+fn main() {
+    let a: u8 = 255;
+    assert_eq!(a + 1, 0);
+
+    let b: u32 = 0;
+    assert_eq!(b - 1, 4294967295);
+
+    let c: i32 = 2147483647;
+    assert_eq!(c * 2, -2);
+
+    let d: i8 = -128;
+    assert_eq!(-d, -128);
+}
@@ -0,0 +1,59 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Zunleash-the-miri-inside-of-you
+
+// `align_offset` during const evaluation, for integer addresses and for pointers into
+// allocations. The flag lets the consts call the (non-const) `align_offset` method, which
+// reaches the `align_offset` lang item that const evaluation implements itself.
+
+#![feature(align_offset)]
+
+use std::usize;
+
+// Already aligned: no step needed, whatever the stride.
+const ALIGNED: usize = (16 as *const u16).align_offset(8);
+const ALIGNED_ZST: usize = (16 as *const ()).align_offset(8);
+// Zero-sized steps never move the pointer.
+const ZST: usize = (1 as *const ()).align_offset(4);
+// Odd strides always reach the alignment: 1 + 1 * 3 == 4.
+const ODD_STRIDE: usize = (1 as *const [u8; 3]).align_offset(4);
+// Even strides only do if the address is aligned to their largest power of two factor:
+// 2 + 3 * 2 == 8, 2 + 1 * 6 == 8 and 4 + 1 * 12 == 16...
+const EVEN_STRIDE: usize = (2 as *const u16).align_offset(8);
+const EVEN_STRIDE_ODD_FACTOR: usize = (2 as *const [u16; 3]).align_offset(8);
+const EVEN_STRIDE_FOUR: usize = (4 as *const [u32; 3]).align_offset(16);
+// ...which an odd address isn't.
+const EVEN_STRIDE_UNREACHABLE: usize = (1 as *const u16).align_offset(8);
+
+#[repr(C)]
+struct Aligned {
+    _int: u64,
+    bytes: [u8; 8],
+}
+
+// An allocation that is aligned enough only leaves the offset into it to consider: 9 + 7 == 16.
+const IN_ALIGNED_ALLOC: usize =
+    (&Aligned { _int: 0, bytes: [0; 8] }.bytes[1] as *const u8).align_offset(8);
+// In one that isn't, the answer depends on where it ends up, so CTFE gives up.
+const IN_UNDERALIGNED_ALLOC: usize = (&[0u8; 4] as *const [u8; 4] as *const u8).align_offset(8);
+
+fn main() {
+    assert_eq!(ALIGNED, 0);
+    assert_eq!(ALIGNED_ZST, 0);
+    assert_eq!(ZST, usize::MAX);
+    assert_eq!(ODD_STRIDE, 1);
+    assert_eq!(EVEN_STRIDE, 3);
+    assert_eq!(EVEN_STRIDE_ODD_FACTOR, 1);
+    assert_eq!(EVEN_STRIDE_FOUR, 1);
+    assert_eq!(EVEN_STRIDE_UNREACHABLE, usize::MAX);
+    assert_eq!(IN_ALIGNED_ALLOC, 7);
+    assert_eq!(IN_UNDERALIGNED_ALLOC, usize::MAX);
+}
@@ -0,0 +1,40 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Bounds checks on arrays and slices need `Len`, which is computed from the
+// layout for arrays and from the pointer metadata for slices.
+
+const ARRAY: [u16; 4] = [10, 20, 30, 40];
+const SLICE: &[u16] = &ARRAY;
+const STR: &[u8] = b"hello";
+
+const FROM_ARRAY: u16 = ARRAY[3];
+const FROM_SLICE: u16 = SLICE[1];
+const FROM_STR: u8 = STR[4];
+
+const fn second(s: &[u16]) -> u16 {
+    s[1]
+}
+
+const fn last(a: [u8; 3]) -> u8 {
+    a[2]
+}
+
+const SECOND: u16 = second(SLICE);
+const LAST: u8 = last([1, 2, 3]);
+
+fn main() {
+    assert_eq!(FROM_ARRAY, 40);
+    assert_eq!(FROM_SLICE, 20);
+    assert_eq!(FROM_STR, b'o');
+    assert_eq!(SECOND, 20);
+    assert_eq!(LAST, 3);
+}
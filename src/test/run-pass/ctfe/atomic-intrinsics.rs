@@ -0,0 +1,39 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Atomic intrinsics are plain memory accesses during const evaluation. Only
+// `static mut` initializers may take `&mut` references to arrays, so the
+// atomic lives there.
+
+#![feature(const_let, core_intrinsics)]
+
+use std::intrinsics::{
+    atomic_cxchg, atomic_load, atomic_store, atomic_umax, atomic_xadd, atomic_xchg,
+};
+
+static mut RESULTS: [usize; 7] = {
+    let mut cell = [5usize];
+    let ptr = &mut cell as *mut [usize; 1] as *mut usize;
+    unsafe {
+        let added = atomic_xadd(ptr, 3);
+        let swapped = atomic_xchg(ptr, 10);
+        let failed = atomic_cxchg(ptr, 3, 4);
+        let replaced = atomic_cxchg(ptr, 10, 12);
+        let maxed = atomic_umax(ptr, 20);
+        atomic_store(ptr, atomic_load(ptr) + 1);
+        [added, swapped, failed.0, failed.1 as usize, replaced.0, maxed, atomic_load(ptr)]
+    }
+};
+
+fn main() {
+    unsafe {
+        assert_eq!(RESULTS, [5, 8, 10, 0, 10, 12, 21]);
+    }
+}
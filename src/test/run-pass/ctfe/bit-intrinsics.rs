@@ -0,0 +1,35 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![feature(const_int_ops, reverse_bits, core_intrinsics)]
+
+use std::intrinsics;
+
+const ONES: u32 = 0b1011_0000u32.count_ones();
+const LEADING: u32 = 1u16.leading_zeros();
+const TRAILING: u32 = (-8i64).trailing_zeros();
+const SWAPPED: u32 = 0x12_34_56_78u32.swap_bytes();
+const REVERSED_U8: u8 = 0b0000_0110u8.reverse_bits();
+const REVERSED_I16: i16 = 1i16.reverse_bits();
+const REVERSED_U128: u128 = unsafe { intrinsics::bitreverse(1u128) };
+const CTLZ_NONZERO: u8 = unsafe { intrinsics::ctlz_nonzero(1u8) };
+
+fn main() {
+    assert_eq!(ONES, 3);
+    assert_eq!(LEADING, 15);
+    assert_eq!(TRAILING, 3);
+    assert_eq!(SWAPPED, 0x78_56_34_12);
+    assert_eq!(REVERSED_U8, 0b0110_0000);
+    assert_eq!(REVERSED_I16, ::std::i16::MIN);
+    assert_eq!(REVERSED_U128, 1 << 127);
+    assert_eq!(CTLZ_NONZERO, 7);
+    assert_eq!(REVERSED_U8, 0b0000_0110u8.reverse_bits());
+}
@@ -0,0 +1,41 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Constant indices and subslices produced by array patterns are evaluated
+// at compile time.
+
+#![feature(const_fn, const_let, slice_patterns)]
+
+const ARR: [u32; 4] = [1, 2, 3, 4];
+
+const fn first_last(arr: [u32; 4]) -> (u32, u32) {
+    let [a, _, _, d] = arr;
+    (a, d)
+}
+
+const fn middle(arr: [u32; 4]) -> [u32; 2] {
+    let [_, mid.., _] = arr;
+    mid
+}
+
+const FIRST_LAST: (u32, u32) = first_last(ARR);
+const MIDDLE: [u32; 2] = middle(ARR);
+
+const SECOND: usize = {
+    let [_, b] = MIDDLE;
+    b as usize
+};
+
+fn main() {
+    assert_eq!(FIRST_LAST, (1, 4));
+    assert_eq!(MIDDLE, [2, 3]);
+    let arr = [0u8; SECOND];
+    assert_eq!(arr.len(), 3);
+}
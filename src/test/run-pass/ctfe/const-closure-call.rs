@@ -0,0 +1,30 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(const_fn, const_let, const_trait_call)]
+
+const fn apply<F: Fn(u32) -> u32>(f: &F, x: u32) -> u32 {
+    f(x)
+}
+
+const fn add(n: u32, x: u32) -> u32 {
+    let add_n = |x: u32| x + n;
+    add_n(x)
+}
+
+const A: u32 = apply(&|x: u32| x * 2, 21);
+const B: u32 = (|x: u32, y: u32| x - y)(50, 8);
+const C: u32 = add(40, 2);
+
+fn main() {
+    assert_eq!(A, 42);
+    assert_eq!(B, 42);
+    assert_eq!(C, 42);
+}
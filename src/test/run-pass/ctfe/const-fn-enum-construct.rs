@@ -0,0 +1,99 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Enum values built inside a const fn are deaggregated into field writes
+// followed by `SetDiscriminant`, which must encode both tags and niches.
+
+#[derive(PartialEq, Eq)]
+enum Tagged {
+    A(u8),
+    B(u16),
+    C,
+}
+
+#[derive(PartialEq, Eq)]
+enum Niche {
+    Data(char),
+    Empty,
+    Other,
+}
+
+// `Wide` only uses the values 1 to 254, so the niche values of `Wrapping`
+// are 255 and, after wrapping around, 0.
+#[derive(PartialEq, Eq)]
+enum Wide {
+    Low = 1,
+    High = 254,
+}
+
+#[derive(PartialEq, Eq)]
+enum Wrapping {
+    Data(Wide),
+    Below,
+    Above,
+}
+
+const fn tagged(x: u16) -> Tagged {
+    Tagged::B(x)
+}
+
+const fn tagged_unit() -> Tagged {
+    Tagged::C
+}
+
+const fn niche() -> Niche {
+    Niche::Other
+}
+
+const fn data(c: char) -> Niche {
+    Niche::Data(c)
+}
+
+const fn above() -> Wrapping {
+    Wrapping::Above
+}
+
+const B: Tagged = tagged(7);
+const C: Tagged = tagged_unit();
+const OTHER: Niche = niche();
+const DATA: Niche = data('x');
+const ABOVE: Wrapping = above();
+
+fn main() {
+    match Tagged::B(7) {
+        B => {}
+        _ => panic!(),
+    }
+    match Tagged::A(7) {
+        B | C => panic!(),
+        _ => {}
+    }
+    match Niche::Other {
+        OTHER => {}
+        _ => panic!(),
+    }
+    match Niche::Empty {
+        OTHER | DATA => panic!(),
+        _ => {}
+    }
+    assert!(Niche::Data('x') == DATA);
+    assert_eq!(std::mem::size_of::<Wrapping>(), 1);
+    match Wrapping::Above {
+        ABOVE => {}
+        _ => panic!(),
+    }
+    match Wrapping::Below {
+        ABOVE => panic!(),
+        _ => {}
+    }
+    assert!(Wrapping::Data(Wide::Low) != ABOVE);
+    assert!(Wrapping::Data(Wide::High) != ABOVE);
+}
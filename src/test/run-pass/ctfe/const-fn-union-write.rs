@@ -0,0 +1,66 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Unions can be created and written to in const fn.
+
+#![feature(const_fn, const_fn_union, const_let)]
+
+union Transmute {
+    int: u32,
+    float: f32,
+}
+
+const fn to_bits(f: f32) -> u32 {
+    let mut t = Transmute { int: 0 };
+    t.float = f;
+    unsafe { t.int }
+}
+
+const fn from_bits(i: u32) -> f32 {
+    unsafe { Transmute { int: i }.float }
+}
+
+#[derive(Copy, Clone)]
+union Payload {
+    wide: u32,
+    narrow: u8,
+}
+
+#[derive(Copy, Clone)]
+struct Tagged {
+    tag: u8,
+    payload: Payload,
+}
+
+const fn narrow(n: u8) -> Tagged {
+    // Only the low byte gets overwritten; the rest keeps the old value.
+    let mut payload = Payload { wide: 0xAABBCC00 };
+    payload.narrow = n;
+    Tagged { tag: 1, payload }
+}
+
+const ONE: u32 = to_bits(1.0);
+const HALF: f32 = from_bits(0x3f000000);
+const TAGGED: Tagged = narrow(0x11);
+const WIDE: u32 = unsafe { TAGGED.payload.wide };
+
+fn main() {
+    assert_eq!(ONE, 0x3f800000);
+    assert_eq!(ONE, to_bits(1.0));
+    assert_eq!(HALF, 0.5);
+    assert_eq!(TAGGED.tag, 1);
+    assert_eq!(unsafe { TAGGED.payload.narrow }, 0x11);
+    if cfg!(target_endian = "little") {
+        assert_eq!(WIDE, 0xAABBCC11);
+    } else {
+        assert_eq!(WIDE, 0x11BBCC00);
+    }
+}
@@ -0,0 +1,43 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(const_fn, const_short_circuit)]
+
+const fn div_is_big(a: u32, b: u32) -> bool {
+    b != 0 && a / b > 10
+}
+
+const fn div_is_small(a: u32, b: u32) -> bool {
+    b == 0 || a / b < 10
+}
+
+const fn both(a: bool, b: bool, c: bool) -> bool {
+    (a && b) || (!a && c)
+}
+
+const BIG: bool = div_is_big(100, 0);
+const SMALL: bool = div_is_small(100, 0);
+const BOTH: [bool; 4] = [
+    both(true, true, false),
+    both(true, false, true),
+    both(false, true, false),
+    both(false, false, true),
+];
+
+static TAKEN: bool = div_is_small(1, 0) || div_is_big(1, 0);
+
+fn main() {
+    assert!(!BIG);
+    assert!(SMALL);
+    assert_eq!(BOTH, [true, false, false, true]);
+    assert!(TAKEN);
+    assert!(div_is_big(100, 2));
+    assert!(!div_is_small(100, 2));
+}
@@ -0,0 +1,38 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(const_fn, const_trait_call)]
+
+trait Double {
+    fn double(&self) -> u32;
+}
+
+struct S(u32);
+
+impl Double for S {
+    const fn double(&self) -> u32 {
+        self.0 * 2
+    }
+}
+
+const fn double_plus_one<T: Double>(t: &T) -> u32 {
+    t.double() + 1
+}
+
+const A: u32 = <S as Double>::double(&S(21));
+const B: u32 = double_plus_one(&S(10));
+static C: u32 = S(4).double();
+
+fn main() {
+    assert_eq!(A, 42);
+    assert_eq!(B, 21);
+    assert_eq!(C, 8);
+    assert_eq!(double_plus_one(&S(1)), 3);
+}
@@ -0,0 +1,48 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// `copy` and `copy_nonoverlapping` run during const evaluation. Only
+// `static mut` initializers may take `&mut` references to arrays, so
+// the destinations live there.
+
+#![feature(const_let, const_raw_ptr_deref, core_intrinsics)]
+
+use std::intrinsics::{copy, copy_nonoverlapping};
+
+static mut NONOVERLAPPING: [u8; 4] = {
+    let src = [1u8, 2, 3, 4];
+    let mut buf = [0u8; 4];
+    unsafe {
+        copy_nonoverlapping(
+            &src as *const [u8; 4] as *const u8,
+            &mut buf as *mut [u8; 4] as *mut u8,
+            3,
+        );
+    }
+    buf
+};
+
+static mut OVERLAPPING: [[u16; 2]; 3] = {
+    let mut buf = [[1u16, 2], [3, 4], [5, 6]];
+    let src = &buf[0] as *const [u16; 2] as *const u16;
+    let dest = &mut buf[1] as *mut [u16; 2] as *mut u16;
+    unsafe {
+        copy(src, dest, 4);
+    }
+    buf
+};
+
+fn main() {
+    unsafe {
+        assert_eq!(NONOVERLAPPING, [1, 2, 3, 0]);
+        assert_eq!(OVERLAPPING, [[1, 2], [1, 2], [3, 4]]);
+    }
+}
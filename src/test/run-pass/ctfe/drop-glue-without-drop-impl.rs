@@ -0,0 +1,31 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Dropping these values runs their drop glue, which never reaches a `Drop` impl.
+
+#![feature(const_fn, const_let)]
+
+const fn none_string() -> u32 {
+    let _x: Option<String> = None;
+    42
+}
+
+const fn none_in_tuple() -> u32 {
+    let _pair: (u32, Option<Vec<u8>>) = (7, None);
+    7
+}
+
+const NONE_STRING: u32 = none_string();
+const NONE_IN_TUPLE: u32 = none_in_tuple();
+
+fn main() {
+    assert_eq!(NONE_STRING, 42);
+    assert_eq!(NONE_IN_TUPLE, 7);
+}
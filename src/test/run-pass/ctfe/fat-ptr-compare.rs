@@ -0,0 +1,41 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Comparing fat pointers compares both the data pointer and the metadata.
+
+#![feature(const_fn, const_compare_raw_pointers)]
+
+use std::fmt::Debug;
+
+const fn slice_eq(a: &[u8], b: &[u8]) -> bool {
+    a as *const [u8] == b as *const [u8]
+}
+
+const fn slice_ne(a: &[u8], b: &[u8]) -> bool {
+    a as *const [u8] != b as *const [u8]
+}
+
+const fn obj_eq(a: &dyn Debug, b: &dyn Debug) -> bool {
+    a as *const dyn Debug == b as *const dyn Debug
+}
+
+const SLICE: &[u8] = &[1, 2, 3];
+const OBJ: &dyn Debug = &5u32;
+
+const SLICE_EQ: bool = slice_eq(SLICE, SLICE);
+const SLICE_NE: bool = slice_ne(SLICE, SLICE);
+const OBJ_EQ: bool = obj_eq(OBJ, OBJ);
+
+fn main() {
+    assert!(SLICE_EQ);
+    assert!(!SLICE_NE);
+    assert!(OBJ_EQ);
+}
@@ -0,0 +1,56 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Float casts in constants must not depend on the host. Compare against the
+// exact IEEE bit patterns as well as against the same casts done at runtime.
+
+#![allow(overflowing_literals)]
+
+const U64_MAX_F32: f32 = 18446744073709551615u64 as f32;
+const U64_MAX_F64: f64 = 18446744073709551615u64 as f64;
+const I64_MIN_F32: f32 = -9223372036854775808i64 as f32;
+// 2^53 + 1 is not representable and must round to even.
+const TIES_F64: f64 = 9007199254740993u64 as f64;
+// 2^24 + 1 likewise for f32.
+const TIES_F32: f32 = 16777217i32 as f32;
+const U128_MAX_F32: f32 = !0u128 as f32;
+const NARROW: f32 = 0.1f64 as f32;
+const WIDEN: f64 = 0.1f32 as f64;
+const TRUNC_POS: i32 = 2.9f64 as i32;
+const TRUNC_NEG: i32 = -2.9f32 as i32;
+const TO_U8: u8 = 255.9f32 as u8;
+
+#[inline(never)]
+fn id<T>(x: T) -> T { x }
+
+fn main() {
+    assert_eq!(U64_MAX_F32.to_bits(), 0x5f800000);
+    assert_eq!(U64_MAX_F64.to_bits(), 0x43f0000000000000);
+    assert_eq!(I64_MIN_F32.to_bits(), 0xdf000000);
+    assert_eq!(TIES_F64.to_bits(), 0x4340000000000000);
+    assert_eq!(TIES_F32.to_bits(), 0x4b800000);
+    assert_eq!(U128_MAX_F32, ::std::f32::INFINITY);
+    assert_eq!(NARROW.to_bits(), 0x3dcccccd);
+    assert_eq!(WIDEN.to_bits(), 0x3fb99999a0000000);
+    assert_eq!(TRUNC_POS, 2);
+    assert_eq!(TRUNC_NEG, -2);
+    assert_eq!(TO_U8, 255);
+
+    assert_eq!(U64_MAX_F32, id(18446744073709551615u64) as f32);
+    assert_eq!(U64_MAX_F64, id(18446744073709551615u64) as f64);
+    assert_eq!(I64_MIN_F32, id(-9223372036854775808i64) as f32);
+    assert_eq!(TIES_F64, id(9007199254740993u64) as f64);
+    assert_eq!(TIES_F32, id(16777217i32) as f32);
+    assert_eq!(NARROW, id(0.1f64) as f32);
+    assert_eq!(WIDEN, id(0.1f32) as f64);
+    assert_eq!(TRUNC_POS, id(2.9f64) as i32);
+    assert_eq!(TRUNC_NEG, id(-2.9f32) as i32);
+    assert_eq!(TO_U8, id(255.9f32) as u8);
+}
@@ -0,0 +1,29 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Float operations that raise IEEE 754 exceptions evaluate to the default
+// result of the exception, like they do at runtime.
+
+use std::f32;
+use std::f64;
+
+const OVERFLOW: f32 = f32::MAX * 2.0;
+const DIV_BY_ZERO: f64 = -1.0 / 0.0;
+const INVALID: f64 = f64::INFINITY - f64::INFINITY;
+const UNDERFLOW: f32 = f32::MIN_POSITIVE * f32::MIN_POSITIVE;
+const INEXACT: f32 = 1.0 / 3.0;
+
+fn main() {
+    assert_eq!(OVERFLOW, f32::INFINITY);
+    assert_eq!(DIV_BY_ZERO, f64::NEG_INFINITY);
+    assert!(INVALID.is_nan());
+    assert_eq!(UNDERFLOW, 0.0);
+    assert_eq!(INEXACT, 0.33333334);
+}
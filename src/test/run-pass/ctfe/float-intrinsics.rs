@@ -0,0 +1,42 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(core_intrinsics)]
+
+use std::intrinsics::*;
+
+const SQRT: f64 = unsafe { sqrtf64(2.0) };
+const SQRT_NEG: f32 = unsafe { sqrtf32(-1.0) };
+const SQRT_NEG_ZERO: f64 = unsafe { sqrtf64(-0.0) };
+const SQRT_NAN: f64 = unsafe { sqrtf64(-::std::f64::NAN) };
+const POWI: f64 = unsafe { powif64(1.5, 5) };
+const POWI_NEG: f32 = unsafe { powif32(2.0, -3) };
+const FABS: f32 = unsafe { fabsf32(-2.5) };
+const FLOOR: f64 = unsafe { floorf64(-2.5) };
+const CEIL: f32 = unsafe { ceilf32(-2.5) };
+const TRUNC: f64 = unsafe { truncf64(-2.7) };
+const ROUND: f64 = unsafe { roundf64(2.5) };
+const ROUND_NEG: f32 = unsafe { roundf32(-0.5) };
+
+fn main() {
+    assert_eq!(SQRT, 2.0f64.sqrt());
+    // The same NaN on every host
+    assert_eq!(SQRT_NEG.to_bits(), 0x7fc0_0000);
+    assert_eq!(SQRT_NEG_ZERO.to_bits(), (-0.0f64).to_bits());
+    assert_eq!(SQRT_NAN.to_bits(), 0x7ff8_0000_0000_0000);
+    assert_eq!(POWI, 7.59375);
+    assert_eq!(POWI_NEG, 0.125);
+    assert_eq!(FABS, 2.5);
+    assert_eq!(FLOOR, -3.0);
+    assert_eq!(CEIL, -2.0);
+    assert_eq!(TRUNC, -2.0);
+    assert_eq!(ROUND, 3.0);
+    assert_eq!(ROUND_NEG, -1.0);
+}
@@ -0,0 +1,46 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Const evaluation produces the same NaNs on every host: the canonical NaN
+// for invalid operations, and the payload of an input NaN otherwise.
+
+#![feature(const_fn, const_transmute)]
+
+use std::mem;
+
+const fn f32_from_bits(bits: u32) -> f32 {
+    unsafe { mem::transmute(bits) }
+}
+
+const fn f32_to_bits(f: f32) -> u32 {
+    unsafe { mem::transmute(f) }
+}
+
+const fn f64_from_bits(bits: u64) -> f64 {
+    unsafe { mem::transmute(bits) }
+}
+
+const fn f64_to_bits(f: f64) -> u64 {
+    unsafe { mem::transmute(f) }
+}
+
+const INVALID: u32 = f32_to_bits(0.0 / 0.0);
+const NEG_F32: u32 = f32_to_bits(-f32_from_bits(0x7fc0_0001));
+const NEG_F64: u64 = f64_to_bits(-f64_from_bits(0xfff8_0000_0000_0001));
+const DIV: u32 = f32_to_bits(f32_from_bits(0xffc0_0001) / 1.0);
+
+fn main() {
+    assert_eq!(INVALID, 0x7fc0_0000);
+    // Negation only flips the sign bit
+    assert_eq!(NEG_F32, 0xffc0_0001);
+    assert_eq!(NEG_F64, 0x7ff8_0000_0000_0001);
+    // The payload of the NaN operand is kept
+    assert_eq!(DIV, 0x7fc0_0001);
+}
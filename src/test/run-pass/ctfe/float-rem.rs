@@ -0,0 +1,53 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// `%` on floats in constants must agree bit-for-bit with the runtime `fmod`.
+
+use std::f64::{INFINITY, NAN};
+
+#[inline(never)]
+fn id<T>(x: T) -> T { x }
+
+macro_rules! check {
+    ($($name:ident = $l:expr, $r:expr;)*) => {
+        $(
+            const $name: f64 = $l % $r;
+            let rt = id($l) % id($r);
+            if $name.is_nan() {
+                assert!(rt.is_nan(), stringify!($name));
+            } else {
+                assert_eq!($name.to_bits(), rt.to_bits(), stringify!($name));
+            }
+        )*
+    }
+}
+
+fn main() {
+    check! {
+        SIMPLE = 5.5, 2.0;
+        NEG_LHS = -5.5, 2.0;
+        NEG_RHS = 5.5, -2.0;
+        EXACT_NEG = -4.0, 2.0;
+        EXACT_POS = 4.0, -2.0;
+        NEG_ZERO = -0.0, 1.0;
+        POS_ZERO = 0.0, -1.0;
+        INF_RHS = 1.5, INFINITY;
+        NEG_INF_RHS = -1.5, INFINITY;
+        INF_LHS = INFINITY, 1.0;
+        ZERO_RHS = 1.0, 0.0;
+        NAN_LHS = NAN, 1.0;
+        NAN_RHS = 1.0, NAN;
+        TINY = 1e300, 1e-300;
+    }
+
+    const F32_NEG: f32 = -4.0f32 % 2.0f32;
+    assert_eq!(F32_NEG.to_bits(), (id(-4.0f32) % id(2.0f32)).to_bits());
+    assert_eq!(F32_NEG.to_bits(), 0x80000000);
+}
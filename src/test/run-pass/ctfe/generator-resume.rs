@@ -0,0 +1,43 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Zunleash-the-miri-inside-of-you
+
+// Const evaluation steps through the state machine of a generator body. The flag
+// lets the `loop` and `match` below into the const fn.
+
+#![feature(const_fn, const_let, generators, generator_trait)]
+
+use std::ops::{Generator, GeneratorState};
+
+const fn sum_yielded() -> u32 {
+    let mut gen = || {
+        let mut i = 1;
+        while i < 4 {
+            yield i;
+            i += 1;
+        }
+        10
+    };
+    let mut sum = 0;
+    loop {
+        match unsafe { gen.resume() } {
+            GeneratorState::Yielded(n) => sum += n,
+            GeneratorState::Complete(n) => return sum + n,
+        }
+    }
+}
+
+const SUM: u32 = sum_yielded();
+
+fn main() {
+    assert_eq!(SUM, 16);
+    assert_eq!(sum_yielded(), 16);
+}
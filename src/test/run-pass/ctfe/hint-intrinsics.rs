@@ -0,0 +1,28 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(const_let, core_intrinsics)]
+
+use std::intrinsics::{assume, likely, unlikely};
+
+const LIKELY: bool = unsafe { likely(true) };
+const UNLIKELY: bool = unsafe { unlikely(true) };
+
+const ASSUMED: u32 = {
+    let x = 7;
+    unsafe { assume(x > 3) };
+    x
+};
+
+fn main() {
+    assert!(LIKELY);
+    assert!(UNLIKELY);
+    assert_eq!(ASSUMED, 7);
+}
@@ -0,0 +1,34 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![feature(const_int_ops)]
+
+const LEFT: u32 = 0x10000b3u32.rotate_left(8);
+const RIGHT: u32 = 0xb301u32.rotate_right(8);
+const LEFT_FULL: u8 = 0x12u8.rotate_left(8);
+const RIGHT_LONG: u16 = 0x1234u16.rotate_right(20);
+const SIGNED: i8 = (-128i8).rotate_left(1);
+const WIDE: u128 = 1u128.rotate_right(1);
+
+fn main() {
+    assert_eq!(LEFT, 0xb301);
+    assert_eq!(RIGHT, 0x10000b3);
+    assert_eq!(LEFT_FULL, 0x12);
+    assert_eq!(RIGHT_LONG, 0x4123);
+    assert_eq!(SIGNED, 1);
+    assert_eq!(WIDE, 1 << 127);
+
+    // The same values at runtime
+    let n = 8;
+    assert_eq!(0x10000b3u32.rotate_left(n), LEFT);
+    assert_eq!(0x1234u16.rotate_right(n + 12), RIGHT_LONG);
+    assert_eq!((-128i8).rotate_left(n - 7), SIGNED);
+}
@@ -0,0 +1,32 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// The prefetch intrinsics are not implemented by the compile-time evaluator itself,
+// it runs their bodies in `core::intrinsics::fallback` instead.
+
+#![feature(const_let, core_intrinsics)]
+
+use std::intrinsics::{prefetch_read_data, prefetch_write_data};
+use std::intrinsics::{prefetch_read_instruction, prefetch_write_instruction};
+
+const VALUE: u8 = {
+    let value = 42u8;
+    unsafe {
+        prefetch_read_data(&value as *const u8, 3);
+        prefetch_write_data(&value as *const u8, 2);
+        prefetch_read_instruction(&value as *const u8, 1);
+        prefetch_write_instruction(&value as *const u8, 0);
+    }
+    value
+};
+
+fn main() {
+    assert_eq!(VALUE, 42);
+}
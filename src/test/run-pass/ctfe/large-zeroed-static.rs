@@ -0,0 +1,25 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Large constants, and arrays of values with padding, are evaluated without
+// tracking the definedness of each byte separately.
+
+static BIG: [u8; 1 << 20] = [0; 1 << 20];
+
+static PADDED: [(u8, u16); 1000] = [(1, 2); 1000];
+
+const NESTED: [[u32; 64]; 64] = [[7; 64]; 64];
+
+fn main() {
+    assert!(BIG.iter().all(|&b| b == 0));
+    assert!(PADDED.iter().all(|&p| p == (1, 2)));
+    assert_eq!(NESTED[63][63], 7);
+}
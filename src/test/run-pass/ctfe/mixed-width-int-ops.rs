@@ -0,0 +1,58 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Comparisons and bitwise ops on integers of different widths, as MIR
+// optimizations can produce them, evaluate by widening the narrower operand.
+
+#![feature(rustc_attrs, const_fn)]
+
+#[rustc_custom_mir = "
+    bb0: {
+        _0 = BitAnd(_1, const 255u8);
+        return;
+    }
+"]
+const fn low_byte(_x: u16) -> u16 {
+    0
+}
+
+#[rustc_custom_mir = "
+    bb0: {
+        _0 = Lt(_1, const 200u8);
+        return;
+    }
+"]
+const fn below_200(_x: u64) -> bool {
+    false
+}
+
+#[rustc_custom_mir = "
+    bb0: {
+        _0 = Gt(_1, const -1i64);
+        return;
+    }
+"]
+const fn non_negative(_x: i8) -> bool {
+    false
+}
+
+const LOW: u16 = low_byte(0x1234);
+const BELOW: bool = below_200(199);
+const ABOVE: bool = below_200(0x1_0000_0000);
+const MINUS_ONE: bool = non_negative(-1);
+const ZERO: bool = non_negative(0);
+
+fn main() {
+    assert_eq!(LOW, 0x34);
+    assert!(BELOW);
+    assert!(!ABOVE);
+    assert!(!MINUS_ONE);
+    assert!(ZERO);
+}
@@ -0,0 +1,36 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Statics whose initializers take the address of each other must end up
+// pointing at the other static, not at a copy of it.
+
+struct Node {
+    value: u32,
+    next: Option<&'static Node>,
+}
+
+static A: Node = Node { value: 1, next: Some(&B) };
+static B: Node = Node { value: 2, next: Some(&A) };
+
+static C: &'static Node = &D;
+static D: Node = Node { value: 3, next: Some(&D) };
+
+fn main() {
+    let b = A.next.unwrap();
+    assert_eq!(b.value, 2);
+    assert!(b as *const Node == &B as *const Node);
+    let a = b.next.unwrap();
+    assert!(a as *const Node == &A as *const Node);
+    assert_eq!(a.value, 1);
+
+    assert!(C as *const Node == &D as *const Node);
+    assert!(D.next.unwrap() as *const Node == &D as *const Node);
+    assert_eq!(C.next.unwrap().value, 3);
+}
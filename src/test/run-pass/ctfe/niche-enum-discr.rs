@@ -0,0 +1,51 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Constants of niche-encoded enums can be used as patterns, which requires
+// reading their discriminant during const evaluation.
+
+#[derive(PartialEq, Eq)]
+enum Niche {
+    Data(bool),
+    First,
+    Second,
+}
+
+const DATA: Niche = Niche::Data(true);
+const FIRST: Niche = Niche::First;
+const SECOND: Niche = Niche::Second;
+
+const NONE: Option<&u8> = None;
+const NESTED: Option<Option<bool>> = Some(None);
+
+fn classify(n: Niche) -> u8 {
+    match n {
+        DATA => 0,
+        FIRST => 1,
+        SECOND => 2,
+        Niche::Data(false) => 3,
+    }
+}
+
+fn main() {
+    assert_eq!(classify(Niche::Data(true)), 0);
+    assert_eq!(classify(Niche::First), 1);
+    assert_eq!(classify(Niche::Second), 2);
+    assert_eq!(classify(Niche::Data(false)), 3);
+    match Some(&1u8) {
+        NONE => panic!(),
+        Some(_) => {}
+    }
+    match Some(Some(false)) {
+        NESTED => panic!(),
+        _ => {}
+    }
+}
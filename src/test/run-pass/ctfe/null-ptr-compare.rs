@@ -0,0 +1,32 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Pointers to allocations are never null, so comparing them with a null
+// pointer is fine during constant evaluation.
+
+#![feature(const_compare_raw_pointers)]
+
+use std::ptr;
+
+const REF_IS_NULL: bool = &1 as *const i32 == ptr::null();
+const REF_IS_NOT_NULL: bool = &1 as *const i32 != ptr::null();
+const NULL_IS_REF: bool = 0 as *const i32 == &1 as *const i32;
+const NULL_IS_NULL: bool = ptr::null::<i32>() == ptr::null();
+const SLICE_IS_NULL: bool = &[1u8, 2, 3] as *const [u8] as *const u8 == ptr::null();
+const ZST_IS_NULL: bool = &() as *const () == ptr::null();
+
+fn main() {
+    assert!(!REF_IS_NULL);
+    assert!(REF_IS_NOT_NULL);
+    assert!(!NULL_IS_REF);
+    assert!(NULL_IS_NULL);
+    assert!(!SLICE_IS_NULL);
+    assert!(!ZST_IS_NULL);
+}
@@ -0,0 +1,33 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![feature(const_int_wrapping, core_intrinsics)]
+
+use std::intrinsics;
+
+const ADD: (u8, bool) = unsafe { intrinsics::add_with_overflow(200u8, 100u8) };
+const SUB: (i32, bool) = unsafe { intrinsics::sub_with_overflow(5i32, 7i32) };
+const MUL: (i16, bool) = unsafe { intrinsics::mul_with_overflow(-300i16, 200i16) };
+
+const WRAPPING_ADD: u8 = 200u8.wrapping_add(100);
+const WRAPPING_SUB: u32 = 0u32.wrapping_sub(1);
+const WRAPPING_MUL: i8 = (-128i8).wrapping_mul(-1);
+const WRAPPING_USIZE: usize = ::std::usize::MAX.wrapping_add(2);
+
+fn main() {
+    assert_eq!(ADD, (44, true));
+    assert_eq!(SUB, (-2, false));
+    assert_eq!(MUL, (-300i16).overflowing_mul(200));
+    assert_eq!(WRAPPING_ADD, 44);
+    assert_eq!(WRAPPING_SUB, ::std::u32::MAX);
+    assert_eq!(WRAPPING_MUL, -128);
+    assert_eq!(WRAPPING_USIZE, 1);
+}
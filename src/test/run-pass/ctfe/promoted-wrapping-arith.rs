@@ -0,0 +1,32 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -C overflow-checks=off
+
+// Without overflow checks, the arithmetic in promoted constants is not checked
+// in MIR either, so evaluating it must wrap around like it does at runtime.
+
+fn add() -> &'static u8 {
+    &(255 + 1)
+}
+
+fn sub() -> &'static u32 {
+    &(0 - 1)
+}
+
+fn mul() -> &'static i32 {
+    &(2147483647 * 2)
+}
+
+fn main() {
+    assert_eq!(*add(), 0);
+    assert_eq!(*sub(), 4294967295);
+    assert_eq!(*mul(), -2);
+}
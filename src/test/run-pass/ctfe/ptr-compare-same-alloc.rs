@@ -0,0 +1,28 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Pointers into the same allocation can be compared during const evaluation.
+
+#![feature(const_compare_raw_pointers)]
+
+const BYTES: &[u8; 2] = &[1, 2];
+const FIRST: *const u8 = BYTES as *const [u8; 2] as *const u8;
+const SECOND: *const u8 = &BYTES[1];
+
+const SAME: bool = FIRST == FIRST;
+const DIFFERENT: bool = FIRST != SECOND;
+const NOT_SAME: bool = FIRST == SECOND;
+
+fn main() {
+    assert!(SAME);
+    assert!(DIFFERENT);
+    assert!(!NOT_SAME);
+}
@@ -0,0 +1,49 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// `ptr_guaranteed_eq`/`ptr_guaranteed_ne` answer what they can during const
+// evaluation, and fall back to `false` for pointers into different allocations.
+
+#![feature(core_intrinsics)]
+
+use std::intrinsics::{ptr_guaranteed_eq, ptr_guaranteed_ne};
+
+const BYTES: &[u8; 2] = &[1, 2];
+const FIRST: *const u8 = BYTES as *const [u8; 2] as *const u8;
+const SECOND: *const u8 = &BYTES[1];
+const OTHER: *const u8 = &3;
+
+const SAME_EQ: bool = unsafe { ptr_guaranteed_eq(FIRST, FIRST) };
+const SAME_NE: bool = unsafe { ptr_guaranteed_ne(FIRST, FIRST) };
+const OFFSET_EQ: bool = unsafe { ptr_guaranteed_eq(FIRST, SECOND) };
+const OFFSET_NE: bool = unsafe { ptr_guaranteed_ne(FIRST, SECOND) };
+const OTHER_EQ: bool = unsafe { ptr_guaranteed_eq(FIRST, OTHER) };
+const OTHER_NE: bool = unsafe { ptr_guaranteed_ne(FIRST, OTHER) };
+const INT_EQ: bool = unsafe { ptr_guaranteed_eq(4 as *const u8, 4 as *const u8) };
+const INT_NE: bool = unsafe { ptr_guaranteed_ne(4 as *const u8, 8 as *const u8) };
+
+fn main() {
+    assert!(SAME_EQ && !SAME_NE);
+    assert!(!OFFSET_EQ && OFFSET_NE);
+    // Unknowable at compile time.
+    assert!(!OTHER_EQ && !OTHER_NE);
+    assert!(INT_EQ && INT_NE);
+
+    // At runtime, the answer is always known.
+    let a = [1u8, 2];
+    let b = 3u8;
+    unsafe {
+        assert!(ptr_guaranteed_eq(&a[0], &a[0]));
+        assert!(ptr_guaranteed_ne(&a[0], &a[1]));
+        assert!(ptr_guaranteed_ne(&a[0], &b));
+        assert!(!ptr_guaranteed_eq(&a[0], &b));
+    }
+}
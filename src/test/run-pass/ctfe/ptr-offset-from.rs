@@ -0,0 +1,29 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(core_intrinsics)]
+
+use std::intrinsics::{arith_offset, ptr_offset_from};
+
+const ARRAY: &[u16; 4] = &[1, 2, 3, 4];
+const START: *const u16 = ARRAY as *const [u16; 4] as *const u16;
+const END: *const u16 = unsafe { arith_offset(START, 4) };
+const BEFORE: *const u16 = unsafe { arith_offset(START, -1) };
+
+const LEN: isize = unsafe { ptr_offset_from(END, START) };
+const BACKWARDS: isize = unsafe { ptr_offset_from(START, END) };
+const WRAPPED: isize = unsafe { ptr_offset_from(BEFORE, START) };
+
+fn main() {
+    assert_eq!(LEN, 4);
+    assert_eq!(BACKWARDS, -4);
+    assert_eq!(WRAPPED, -1);
+    assert_eq!(unsafe { ptr_offset_from(END, START) }, LEN);
+}
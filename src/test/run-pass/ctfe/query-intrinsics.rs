@@ -0,0 +1,40 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(core_intrinsics)]
+
+use std::intrinsics::{discriminant_value, needs_drop, type_id, type_name};
+
+#[allow(dead_code)]
+#[repr(i8)]
+enum Signed {
+    A = -2,
+    B = 5,
+}
+
+const NAME: &str = unsafe { type_name::<Option<u32>>() };
+const ID: u64 = unsafe { type_id::<u32>() };
+const NEEDS_DROP_STRING: bool = unsafe { needs_drop::<String>() };
+const NEEDS_DROP_U32: bool = unsafe { needs_drop::<(u32, &str)>() };
+const DISCR_SOME: u64 = unsafe { discriminant_value(&Some(1u8)) };
+const DISCR_NEGATIVE: u64 = unsafe { discriminant_value(&Signed::A) };
+const DISCR_STRUCT: u64 = unsafe { discriminant_value(&(1u8, 2u8)) };
+
+fn main() {
+    assert!(NAME.ends_with("option::Option<u32>"));
+    assert_eq!(ID, unsafe { type_id::<u32>() });
+    assert!(NEEDS_DROP_STRING);
+    assert!(!NEEDS_DROP_U32);
+    assert_eq!(DISCR_SOME, 1);
+    assert_eq!(DISCR_NEGATIVE, unsafe { discriminant_value(&Signed::A) });
+    assert_eq!(DISCR_NEGATIVE, -2i64 as u64);
+    assert_eq!(DISCR_STRUCT, 0);
+    assert_eq!(NAME, unsafe { type_name::<Option<u32>>() });
+}
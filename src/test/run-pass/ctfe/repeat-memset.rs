@@ -0,0 +1,27 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Repeat expressions whose element has identical bytes are filled in one go,
+// all others copy the first element (including its relocations).
+
+static ONES: [u8; 16 * 1024 * 1024] = [0xff; 16 * 1024 * 1024];
+static SPLAT: [u32; 1024] = [0x2a2a_2a2a; 1024];
+static NEG: [i64; 100] = [-1; 100];
+static MIXED: [u16; 100] = [0x0102; 100];
+static REFS: [&u8; 100] = [&5; 100];
+
+fn main() {
+    assert!(ONES.iter().all(|&b| b == 0xff));
+    assert!(SPLAT.iter().all(|&x| x == 0x2a2a_2a2a));
+    assert!(NEG.iter().all(|&x| x == -1));
+    assert!(MIXED.iter().all(|&x| x == 0x0102));
+    assert!(REFS.iter().all(|&r| *r == 5));
+}
@@ -0,0 +1,41 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![feature(core_intrinsics)]
+
+use std::intrinsics::{saturating_add, saturating_sub};
+
+const ADD_U8: u8 = unsafe { saturating_add(200u8, 100u8) };
+const SUB_U8: u8 = unsafe { saturating_sub(100u8, 200u8) };
+const ADD_I8_MAX: i8 = unsafe { saturating_add(100i8, 100i8) };
+const ADD_I8_MIN: i8 = unsafe { saturating_add(-100i8, -100i8) };
+const SUB_I16_MAX: i16 = unsafe { saturating_sub(1i16, -32767i16) };
+const SUB_I16_MIN: i16 = unsafe { saturating_sub(-2i16, 32767i16) };
+const ADD_I128: i128 = unsafe { saturating_add(::std::i128::MAX, 1) };
+const NO_OVERFLOW: u32 = unsafe { saturating_sub(7u32, 3u32) };
+
+fn main() {
+    assert_eq!(ADD_U8, 255);
+    assert_eq!(SUB_U8, 0);
+    assert_eq!(ADD_I8_MAX, 127);
+    assert_eq!(ADD_I8_MIN, -128);
+    assert_eq!(SUB_I16_MAX, 32767);
+    assert_eq!(SUB_I16_MIN, -32768);
+    assert_eq!(ADD_I128, ::std::i128::MAX);
+    assert_eq!(NO_OVERFLOW, 4);
+
+    // The same operations at runtime
+    unsafe {
+        assert_eq!(saturating_add(200u8, 100u8), ADD_U8);
+        assert_eq!(saturating_sub(-2i16, 32767i16), SUB_I16_MIN);
+        assert_eq!(saturating_add(-100i8, -100i8), ADD_I8_MIN);
+    }
+}
@@ -0,0 +1,51 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(repr_simd, platform_intrinsics)]
+#![allow(non_camel_case_types)]
+
+#[repr(simd)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct i32x4(i32, i32, i32, i32);
+
+#[repr(simd)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct u32x2(u32, u32);
+
+extern "platform-intrinsic" {
+    fn simd_add<T>(x: T, y: T) -> T;
+    fn simd_shl<T>(x: T, y: T) -> T;
+    fn simd_lt<T, U>(x: T, y: T) -> U;
+    fn simd_extract<T, E>(x: T, idx: u32) -> E;
+    fn simd_insert<T, E>(x: T, idx: u32, val: E) -> T;
+    fn simd_shuffle2<T, U>(x: T, y: T, idx: [u32; 2]) -> U;
+}
+
+const A: i32x4 = i32x4(1, 2, 3, 4);
+const B: i32x4 = i32x4(10, -20, 30, -40);
+
+const SUM: i32x4 = unsafe { simd_add(A, B) };
+const SHIFTED: i32x4 = unsafe { simd_shl(A, i32x4(1, 2, 3, 4)) };
+const LESS: i32x4 = unsafe { simd_lt(A, B) };
+const THIRD: i32 = unsafe { simd_extract(B, 2) };
+const INSERTED: i32x4 = unsafe { simd_insert(A, 1, 7) };
+const SHUFFLED: u32x2 = unsafe {
+    simd_shuffle2(u32x2(1, 2), u32x2(3, 4), [3, 0])
+};
+
+fn main() {
+    assert_eq!(SUM, i32x4(11, -18, 33, -36));
+    assert_eq!(SHIFTED, i32x4(2, 8, 24, 64));
+    assert_eq!(LESS, i32x4(-1, 0, -1, 0));
+    assert_eq!(THIRD, 30);
+    assert_eq!(INSERTED, i32x4(1, 7, 3, 4));
+    assert_eq!(SHUFFLED, u32x2(4, 1));
+    assert_eq!(SUM, unsafe { simd_add(A, B) });
+}
@@ -0,0 +1,41 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// The dynamic size and alignment of unsized values come from their metadata.
+
+#![feature(core_intrinsics)]
+
+use std::fmt::Debug;
+use std::intrinsics::{min_align_of_val, size_of_val};
+
+#[repr(align(8))]
+#[derive(Debug)]
+struct Aligned(u16);
+
+const SLICE: &[u16] = &[1, 2, 3];
+const OBJECT: &dyn Debug = &Aligned(4);
+
+const SLICE_SIZE: usize = unsafe { size_of_val(SLICE) };
+const SLICE_ALIGN: usize = unsafe { min_align_of_val(SLICE) };
+const STR_SIZE: usize = unsafe { size_of_val("hello") };
+const OBJECT_SIZE: usize = unsafe { size_of_val(OBJECT) };
+const OBJECT_ALIGN: usize = unsafe { min_align_of_val(OBJECT) };
+const SIZED: usize = unsafe { size_of_val(&0u32) };
+
+fn main() {
+    assert_eq!(SLICE_SIZE, 6);
+    assert_eq!(SLICE_ALIGN, 2);
+    assert_eq!(STR_SIZE, 5);
+    assert_eq!(OBJECT_SIZE, 8);
+    assert_eq!(OBJECT_ALIGN, 8);
+    assert_eq!(SIZED, 4);
+    assert_eq!(OBJECT_SIZE, std::mem::size_of_val(OBJECT));
+}
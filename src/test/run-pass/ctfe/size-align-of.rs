@@ -0,0 +1,32 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+use std::mem;
+
+#[repr(C, align(16))]
+struct Aligned(u8, u32);
+
+const fn size_and_align<T>() -> (usize, usize) {
+    (mem::size_of::<T>(), mem::align_of::<T>())
+}
+
+const U64: (usize, usize) = size_and_align::<u64>();
+const ALIGNED: (usize, usize) = size_and_align::<Aligned>();
+const UNIT: (usize, usize) = size_and_align::<()>();
+const ARRAY: (usize, usize) = size_and_align::<[u16; 3]>();
+
+fn main() {
+    assert_eq!(U64, (mem::size_of::<u64>(), mem::align_of::<u64>()));
+    assert_eq!(ALIGNED, (16, 16));
+    assert_eq!(UNIT, (0, 1));
+    assert_eq!(ARRAY, (6, 2));
+    assert_eq!(size_and_align::<Aligned>(), ALIGNED);
+}
@@ -0,0 +1,34 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Self tail calls replace the frame of the caller, so they are not limited by the
+// number of stack frames const evaluation allows.
+
+#![feature(const_fn, const_short_circuit)]
+
+const fn count_down(n: u64) -> bool {
+    n == 0 || count_down(n - 1)
+}
+
+const fn has_digit(n: u64, digit: u64) -> bool {
+    n % 10 == digit || (n >= 10 && has_digit(n / 10, digit))
+}
+
+const DEEP: bool = count_down(10_000);
+const SEVEN: bool = has_digit(1_234_567, 7);
+const FIVE: bool = has_digit(1_234_567, 5);
+const NINE: bool = has_digit(1_234_567, 9);
+
+fn main() {
+    assert!(DEEP);
+    assert!(SEVEN);
+    assert!(FIVE);
+    assert!(!NINE);
+}
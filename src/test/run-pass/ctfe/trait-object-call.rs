@@ -0,0 +1,53 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(const_fn, const_let, const_trait_call)]
+
+trait Shape {
+    fn sides(&self) -> u32;
+}
+
+struct Triangle;
+
+struct Polygon(u32);
+
+impl Shape for Triangle {
+    const fn sides(&self) -> u32 {
+        3
+    }
+}
+
+impl Shape for Polygon {
+    const fn sides(&self) -> u32 {
+        self.0
+    }
+}
+
+const fn sides(shape: &dyn Shape) -> u32 {
+    shape.sides()
+}
+
+// Unsizes `Triangle` twice, the second time reusing its vtable.
+const fn total() -> u32 {
+    let shapes: [&dyn Shape; 3] = [&Triangle, &Polygon(5), &Triangle];
+    sides(shapes[0]) + sides(shapes[1]) + sides(shapes[2])
+}
+
+const TOTAL: u32 = total();
+const DIRECT: u32 = sides(&Polygon(7));
+const SHAPE: &dyn Shape = &Polygon(6);
+const THROUGH_CONST: u32 = SHAPE.sides();
+
+fn main() {
+    assert_eq!(TOTAL, 11);
+    assert_eq!(DIRECT, 7);
+    assert_eq!(THROUGH_CONST, 6);
+    assert_eq!(sides(SHAPE), 6);
+}
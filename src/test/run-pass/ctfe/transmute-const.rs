@@ -0,0 +1,40 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![feature(const_fn, const_transmute)]
+
+use std::mem;
+
+#[repr(transparent)]
+struct Foo(u32);
+
+const TRANSMUTED_U32: u32 = unsafe { mem::transmute(Foo(3)) };
+
+const fn float_bits(f: f32) -> u32 {
+    unsafe { mem::transmute(f) }
+}
+
+const fn pair(x: u64) -> (u32, u32) {
+    unsafe { mem::transmute(x) }
+}
+
+const ONE_BITS: u32 = float_bits(1.0);
+const PAIR: (u32, u32) = pair(0x0000_0001_0000_0002);
+const BYTES: [u8; 4] = unsafe { mem::transmute(0x01010101u32) };
+const TRUE: bool = unsafe { mem::transmute(1u8) };
+
+fn main() {
+    assert_eq!(TRANSMUTED_U32, 3);
+    assert_eq!(ONE_BITS, 0x3f800000);
+    assert_eq!(PAIR, pair(0x0000_0001_0000_0002));
+    assert_eq!(BYTES, [1, 1, 1, 1]);
+    assert!(TRUE);
+}
@@ -0,0 +1,44 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// `write_bytes` runs during const evaluation and defines every byte it writes.
+
+#![feature(const_let, const_raw_ptr_deref, core_intrinsics)]
+
+use std::intrinsics::write_bytes;
+
+union MaybeUninit {
+    uninit: (),
+    init: [u32; 3],
+}
+
+static mut FILLED: [u32; 3] = {
+    let mut buf = [1u32, 2, 3];
+    unsafe {
+        write_bytes(&mut buf as *mut [u32; 3] as *mut u32, 0xAB, 2);
+    }
+    buf
+};
+
+static mut ZEROED: [[u32; 3]; 1] = {
+    let mut buf = [unsafe { MaybeUninit { uninit: () }.init }];
+    unsafe {
+        write_bytes(&mut buf as *mut [[u32; 3]; 1] as *mut u32, 0, 3);
+    }
+    buf
+};
+
+fn main() {
+    unsafe {
+        assert_eq!(FILLED, [0xABABABAB, 0xABABABAB, 3]);
+        assert_eq!(ZEROED, [[0, 0, 0]]);
+    }
+}
@@ -0,0 +1,69 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// ignore-wasm32-bare compiled with panic=abort by default
+
+// Check the drop glue of boxed slices and trait objects: every element is
+// dropped exactly once, also when one of them panics, and the allocation is
+// freed with the size taken from the metadata.
+
+use std::cell::Cell;
+use std::panic;
+
+thread_local!(static DROPS: Cell<usize> = Cell::new(0));
+
+fn drops() -> usize {
+    DROPS.with(|d| d.get())
+}
+
+struct D(u64, bool);
+
+impl Drop for D {
+    fn drop(&mut self) {
+        DROPS.with(|d| d.set(d.get() + 1));
+        if self.1 {
+            panic!("panic in drop");
+        }
+    }
+}
+
+trait Trait {}
+impl Trait for D {}
+impl Trait for [D; 3] {}
+
+fn main() {
+    let slice: Box<[D]> = vec![D(0, false), D(1, false), D(2, false)].into_boxed_slice();
+    drop(slice);
+    assert_eq!(drops(), 3);
+
+    let empty: Box<[D]> = Vec::new().into_boxed_slice();
+    drop(empty);
+    assert_eq!(drops(), 3);
+
+    let obj: Box<Trait> = Box::new(D(3, false));
+    drop(obj);
+    assert_eq!(drops(), 4);
+
+    let obj: Box<Trait> = Box::new([D(4, false), D(5, false), D(6, false)]);
+    drop(obj);
+    assert_eq!(drops(), 7);
+
+    // The elements after the one that panics are still dropped.
+    let slice: Box<[D]> = vec![D(7, true), D(8, false), D(9, false)].into_boxed_slice();
+    let res = panic::catch_unwind(panic::AssertUnwindSafe(move || drop(slice)));
+    assert!(res.is_err());
+    assert_eq!(drops(), 10);
+
+    let obj: Box<Trait> = Box::new([D(10, false), D(11, true), D(12, false)]);
+    let res = panic::catch_unwind(panic::AssertUnwindSafe(move || drop(obj)));
+    assert!(res.is_err());
+    assert_eq!(drops(), 13);
+}
@@ -0,0 +1,75 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Check that exhaustiveness checking copes with matches over big enums and
+// with combinations of integer ranges.
+
+#![feature(exhaustive_integer_patterns)]
+
+use std::i8;
+
+macro_rules! letters {
+    ($($l:ident)*) => {
+        #[derive(Clone, Copy, PartialEq, Debug)]
+        enum Letter { $($l),* }
+
+        const LETTERS: &[Letter] = &[$(Letter::$l),*];
+
+        fn same(pair: (Letter, Letter)) -> bool {
+            match pair {
+                $((Letter::$l, Letter::$l) => true,)*
+                _ => false,
+            }
+        }
+
+        fn is_letter(l: Letter) -> bool {
+            match l {
+                $(Letter::$l)|* => true,
+            }
+        }
+    }
+}
+
+letters!(
+    A0 B0 C0 D0 E0 F0 G0 H0 I0 J0 K0 L0 M0 N0 O0 P0 Q0 R0 S0 T0 U0 V0 W0 X0 Y0 Z0
+    A1 B1 C1 D1 E1 F1 G1 H1 I1 J1 K1 L1 M1 N1 O1 P1 Q1 R1 S1 T1 U1 V1 W1 X1 Y1 Z1
+    A2 B2 C2 D2 E2 F2 G2 H2 I2 J2 K2 L2 M2 N2 O2 P2 Q2 R2 S2 T2 U2 V2 W2 X2 Y2 Z2
+    A3 B3 C3 D3 E3 F3 G3 H3 I3 J3 K3 L3 M3 N3 O3 P3 Q3 R3 S3 T3 U3 V3 W3 X3 Y3 Z3
+);
+
+fn classify(x: u8, y: u8, z: i8) -> u32 {
+    match (x, y, z) {
+        (0..=63, 0..=63, _) => 0,
+        (0..=63, 64..=255, -128..=-1) => 1,
+        (0..=63, 64..=255, 0..=127) => 2,
+        (64..=127, _, _) => 3,
+        (128..=255, 0, _) => 4,
+        (128..=255, 1..=255, i8::MIN..=0) => 5,
+        (128..=255, 1..=255, 1..=i8::MAX) => 6,
+    }
+}
+
+fn main() {
+    for &a in LETTERS {
+        assert!(is_letter(a));
+        for &b in LETTERS {
+            assert_eq!(same((a, b)), a == b);
+        }
+    }
+
+    assert_eq!(classify(0, 0, 0), 0);
+    assert_eq!(classify(63, 64, -1), 1);
+    assert_eq!(classify(63, 255, 0), 2);
+    assert_eq!(classify(100, 7, 7), 3);
+    assert_eq!(classify(128, 0, 5), 4);
+    assert_eq!(classify(200, 1, -128), 5);
+    assert_eq!(classify(255, 255, 127), 6);
+}
@@ -0,0 +1,78 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// ignore-wasm32-bare compiled with panic=abort by default
+
+#![feature(generators, generator_trait)]
+
+use std::any::Any;
+use std::ops::Generator;
+use std::panic;
+use std::sync::atomic::{AtomicUsize, ATOMIC_USIZE_INIT, Ordering};
+
+static A: AtomicUsize = ATOMIC_USIZE_INIT;
+
+struct B;
+
+impl Drop for B {
+    fn drop(&mut self) {
+        A.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn bool_true() -> bool {
+    true
+}
+
+fn message(payload: Box<Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(s) => *s,
+        Err(payload) => payload.downcast_ref::<&str>().unwrap().to_string(),
+    }
+}
+
+fn main() {
+    let upvar = B;
+    let mut foo = move || {
+        drop(&upvar);
+        let _first = B;
+        yield;
+        let _second = B;
+        if bool_true() {
+            panic!("boom");
+        }
+        yield;
+        let _third = B;
+    };
+
+    unsafe { foo.resume(); }
+    assert_eq!(A.load(Ordering::SeqCst), 0);
+
+    // Unwinding drops the upvar and the two locals that were initialized,
+    // but not the one that was never reached.
+    let res = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        unsafe { foo.resume() }
+    }));
+    assert_eq!(message(res.unwrap_err()), "boom");
+    assert_eq!(A.load(Ordering::SeqCst), 3);
+
+    // The generator is now poisoned, resuming it again panics without
+    // touching any of its state.
+    let res = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        unsafe { foo.resume() }
+    }));
+    assert_eq!(message(res.unwrap_err()), "generator resumed after panicking");
+    assert_eq!(A.load(Ordering::SeqCst), 3);
+
+    // Dropping a poisoned generator does not drop anything twice.
+    drop(foo);
+    assert_eq!(A.load(Ordering::SeqCst), 3);
+}
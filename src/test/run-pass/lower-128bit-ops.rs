@@ -0,0 +1,29 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// ignore-emscripten
+
+// compile-flags: -Z lower_128bit_ops=yes -C debug_assertions=yes
+
+fn neg(x: i128) -> i128 {
+    -x
+}
+
+fn arith(x: i128, y: u128) -> (i128, u128) {
+    (((x * 3 - 1) << 2) >> 1, ((y + 7) * 2) / 3 % 1000)
+}
+
+fn main() {
+    assert_eq!(neg(5), -5);
+    assert_eq!(neg(-(1 << 100)), 1 << 100);
+    assert_eq!(neg(i128::max_value()), i128::min_value() + 1);
+    assert_eq!(arith(-7, 1 << 70), (-44, 287));
+}
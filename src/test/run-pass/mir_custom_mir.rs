@@ -0,0 +1,50 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tests building function bodies from textual MIR with `#[rustc_custom_mir]`.
+
+#![feature(rustc_attrs)]
+
+#[rustc_custom_mir = "
+    fn clamp_add(_1: i32, _2: i32) -> i32 {
+        let mut _3: bool;
+        bb0: {
+            _0 = Add(_1, _2);
+            _3 = Gt(_0, const 10i32);
+            switchInt(move _3) -> [false: bb2, otherwise: bb1];
+        }
+        bb1: {
+            _0 = const 10i32;
+            goto -> bb2;
+        }
+        bb2: {
+            return;
+        }
+    }
+"]
+fn clamp_add(_a: i32, _b: i32) -> i32 {
+    unreachable!()
+}
+
+#[rustc_custom_mir = "
+    bb0: {
+        _0 = (_1.1: u8);
+        return;
+    }
+"]
+fn second(_t: (bool, u8)) -> u8 {
+    unreachable!()
+}
+
+fn main() {
+    assert_eq!(clamp_add(2, 3), 5);
+    assert_eq!(clamp_add(7, 8), 10);
+    assert_eq!(second((true, 42)), 42);
+}
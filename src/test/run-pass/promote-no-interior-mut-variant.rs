@@ -0,0 +1,37 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Borrows of constants whose type could contain an `UnsafeCell`, but whose
+// value does not, can be promoted. This includes associated constants, once
+// the impl providing them is known.
+
+use std::cell::Cell;
+
+const NONE: Option<Cell<i32>> = None;
+
+trait Tr {
+    const C: Option<Cell<i32>>;
+}
+
+impl Tr for () {
+    const C: Option<Cell<i32>> = None;
+}
+
+const REF_NONE: &Option<Cell<i32>> = &NONE;
+const REF_ASSOC: &Option<Cell<i32>> = &<() as Tr>::C;
+
+fn main() {
+    let a: &'static Option<Cell<i32>> = &None::<Cell<i32>>;
+    let b: &'static Option<Cell<i32>> = &NONE;
+    let c: &'static Option<Cell<i32>> = &<() as Tr>::C;
+    assert!(a.is_none() && b.is_none() && c.is_none());
+    assert!(REF_NONE.is_none() && REF_ASSOC.is_none());
+}
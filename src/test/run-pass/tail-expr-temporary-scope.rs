@@ -0,0 +1,55 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// compile-flags: -Z tail-expr-temporary-scope
+
+// Check that with `-Z tail-expr-temporary-scope` the temporaries of a block's
+// tail expression are dropped before the locals of the block.
+
+use std::cell::RefCell;
+
+struct Noisy<'a>(&'a RefCell<Vec<&'static str>>, &'static str);
+
+impl<'a> Noisy<'a> {
+    fn len(&self) -> usize {
+        self.1.len()
+    }
+}
+
+impl<'a> Drop for Noisy<'a> {
+    fn drop(&mut self) {
+        self.0.borrow_mut().push(self.1);
+    }
+}
+
+fn tail_of_fn(log: &RefCell<Vec<&'static str>>) -> usize {
+    let _local = Noisy(log, "fn local");
+    Noisy(log, "fn temporary").len()
+}
+
+fn tail_of_block(log: &RefCell<Vec<&'static str>>) -> usize {
+    let len = {
+        let _local = Noisy(log, "block local");
+        Noisy(log, "block temporary").len()
+    };
+    log.borrow_mut().push("after block");
+    len
+}
+
+fn main() {
+    let log = RefCell::new(vec![]);
+    assert_eq!(tail_of_fn(&log), 12);
+    assert_eq!(*log.borrow(), ["fn temporary", "fn local"]);
+
+    let log = RefCell::new(vec![]);
+    assert_eq!(tail_of_block(&log), 15);
+    assert_eq!(*log.borrow(), ["block temporary", "block local", "after block"]);
+}
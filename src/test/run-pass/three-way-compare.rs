@@ -0,0 +1,55 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![feature(core_intrinsics)]
+
+use std::cmp::Ordering::{self, Less, Equal, Greater};
+use std::intrinsics::three_way_compare;
+
+fn cmp<T: Ord>(a: T, b: T) -> Ordering {
+    a.cmp(&b)
+}
+
+// Derived comparisons compare fields and discriminants with `Ord::cmp` and
+// `PartialOrd::partial_cmp`, which are `three_way_compare` for these types.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
+struct Version(u16, i8, char);
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum Level { Low, Mid, High }
+
+fn main() {
+    assert_eq!(cmp(-128i8, 127i8), Less);
+    assert_eq!(cmp(255u8, 0u8), Greater);
+    assert_eq!(cmp(-1i64, -1i64), Equal);
+    assert_eq!(cmp(0u128, !0u128), Less);
+    assert_eq!(cmp(::std::i128::MIN, 1i128), Less);
+    assert_eq!(cmp('b', 'a'), Greater);
+    assert_eq!(cmp(3usize, 3usize), Equal);
+
+    unsafe {
+        assert_eq!(three_way_compare(true, false), Greater);
+        assert_eq!(three_way_compare(false, true), Less);
+        assert_eq!(three_way_compare(-5i32, 7i32), Less);
+        assert_eq!(three_way_compare(7u32, 7u32), Equal);
+    }
+
+    assert_eq!(cmp(Version(1, -1, 'a'), Version(1, 2, 'a')), Less);
+    assert_eq!(cmp(Version(2, 0, 'b'), Version(2, 0, 'a')), Greater);
+    assert_eq!(Version(0, 0, 'z').partial_cmp(&Version(0, 0, 'z')), Some(Equal));
+    assert!(Version(0, -5, 'z') < Version(0, 5, 'a'));
+    assert_eq!(cmp(Level::High, Level::Low), Greater);
+    assert_eq!(Level::Low.partial_cmp(&Level::Mid), Some(Less));
+
+    let mut v = vec![3i16, -7, 12, 0, -7, 5];
+    v.sort();
+    assert_eq!(v, [-7, -7, 0, 3, 5, 12]);
+}
@@ -38,3 +38,8 @@ pub unsafe fn test_simd_add(a: Foo, b: Foo) -> Foo {
     simd_add(a, b)
     //~^ ERROR `simd_add` intrinsic: expected SIMD input type, found non-SIMD `Foo`
 }
+
+pub unsafe fn test_three_way_compare(a: f64, b: f64) -> std::cmp::Ordering {
+    intrinsics::three_way_compare(a, b)
+    //~^ ERROR `three_way_compare` intrinsic: expected basic integer, `char` or `bool` type
+}
@@ -0,0 +1,34 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// `#![no_std]` crates get the `core` path, which they might not have in scope.
+
+#![feature(nll)]
+#![no_std]
+#![crate_type = "lib"]
+
+use core::cell::Cell;
+
+pub struct Token;
+
+pub struct Parser {
+    count: Cell<u32>,
+    token: Token,
+}
+
+impl Parser {
+    pub fn take_count(&mut self) -> Cell<u32> {
+        self.count //~ ERROR cannot move out of borrowed content
+    }
+
+    pub fn take_token(&mut self) -> Token {
+        self.token //~ ERROR cannot move out of borrowed content
+    }
+}
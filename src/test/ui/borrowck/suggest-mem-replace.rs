@@ -0,0 +1,36 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![feature(nll)]
+
+struct Token;
+
+struct Parser {
+    name: String,
+    next: Option<Box<Parser>>,
+    token: Token,
+}
+
+impl Parser {
+    fn take_next(&mut self) -> Option<Box<Parser>> {
+        self.next //~ ERROR cannot move out of borrowed content
+    }
+
+    fn take_name(&mut self) -> String {
+        self.name //~ ERROR cannot move out of borrowed content
+    }
+
+    fn take_token(&mut self) -> Token {
+        self.token //~ ERROR cannot move out of borrowed content
+    }
+}
+
+fn main() {}
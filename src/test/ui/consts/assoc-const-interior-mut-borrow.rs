@@ -0,0 +1,28 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Associated constants are only promoted if the value provided by the impl
+// has no interior mutability.
+
+use std::cell::Cell;
+
+trait Tr {
+    const C: Option<Cell<i32>>;
+}
+
+impl Tr for () {
+    const C: Option<Cell<i32>> = Some(Cell::new(1));
+}
+
+const REF: &Option<Cell<i32>> = &<() as Tr>::C; //~ ERROR E0492
+
+fn main() {
+}
@@ -14,13 +14,10 @@ pub const A: i8 = -std::i8::MIN;
 //~^ ERROR this constant cannot be used
 pub const B: i8 = A;
 //~^ ERROR const_err
-//~| ERROR const_err
 pub const C: u8 = A as u8;
 //~^ ERROR const_err
-//~| ERROR const_err
 pub const D: i8 = 50 - A;
 //~^ ERROR const_err
-//~| ERROR const_err
 
 fn main() {
     let _ = (A, B, C, D);
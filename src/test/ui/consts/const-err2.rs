@@ -8,9 +8,9 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-// needed because negating int::MIN will behave differently between
-// optimized compilation and unoptimized compilation and thus would
-// lead to different lints being emitted
+// -O turns off overflow checks, so the arithmetic below wraps at runtime
+// and must not be reported; only the out-of-bounds index really panics.
+// With overflow checks the assertions MIR building emits get linted instead.
 // compile-flags: -O
 
 #![feature(rustc_attrs)]
@@ -23,15 +23,15 @@ fn black_box<T>(_: T) {
 
 fn main() {
     let a = -std::i8::MIN;
-    //~^ ERROR const_err
+    // wraps to `i8::MIN`
     let b = 200u8 + 200u8 + 200u8;
-    //~^ ERROR const_err
+    // wraps to `88`
     let c = 200u8 * 4;
-    //~^ ERROR const_err
+    // wraps to `32`
     let d = 42u8 - (42u8 + 1);
-    //~^ ERROR const_err
+    // wraps to `255`
     let _e = [5u8][1];
-    //~^ ERROR const_err
+    //~^ ERROR unconditional_panic
     black_box(a);
     black_box(b);
     black_box(c);
@@ -0,0 +1,20 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Atomic intrinsics only work on integers, as in codegen.
+
+#![feature(core_intrinsics)]
+
+use std::intrinsics::atomic_load;
+
+const BYTES: [u8; 3] = unsafe { atomic_load(&[1u8, 2, 3] as *const [u8; 3] as *mut [u8; 3]) };
+//~^ ERROR this constant cannot be used
+
+fn main() {}
@@ -0,0 +1,27 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Const evaluation stops with an error once it exceeds the step limit, which
+// counts the terminators executed: here the two calls.
+
+#![feature(const_fn, const_eval_limit)]
+#![const_eval_limit="1"]
+
+const fn inner() -> u32 {
+    42
+}
+
+const fn answer() -> u32 {
+    inner()
+}
+
+fn main() {
+    let _: [u8; answer()]; //~ ERROR could not evaluate constant expression
+}
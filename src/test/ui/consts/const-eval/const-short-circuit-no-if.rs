@@ -0,0 +1,40 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// `const_short_circuit` only allows the branches of `&&` and `||`, not
+// arbitrary control flow.
+
+#![feature(const_fn, const_short_circuit)]
+
+const fn both(a: bool, b: bool) -> bool {
+    a && b
+}
+
+const fn pick(a: bool) -> u32 {
+    if a { 1 } else { 2 } //~ ERROR unimplemented expression type
+}
+
+const fn pick_match(a: bool) -> u32 {
+    match a {
+        true => 1, //~ ERROR unimplemented expression type
+        false => 2,
+    }
+}
+
+const fn pick_and(a: bool, b: bool) -> u32 {
+    if a && b { 1 } else { 2 } //~ ERROR unimplemented expression type
+}
+
+fn main() {
+    both(true, false);
+    pick(true);
+    pick_match(true);
+    pick_and(true, false);
+}
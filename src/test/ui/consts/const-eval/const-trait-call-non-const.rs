@@ -0,0 +1,38 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Calling a trait method from a const fn is only allowed when the impl
+// selected at evaluation time declares the method `const fn`.
+
+#![feature(const_fn, const_trait_call)]
+
+trait Answer {
+    fn answer() -> u32;
+}
+
+struct Const;
+struct NotConst;
+
+impl Answer for Const {
+    const fn answer() -> u32 { 42 }
+}
+
+impl Answer for NotConst {
+    fn answer() -> u32 { 42 }
+}
+
+const fn get<T: Answer>() -> u32 {
+    T::answer()
+}
+
+fn main() {
+    let _: [u8; get::<Const>()] = [0; 42];
+    let _: [u8; get::<NotConst>()]; //~ ERROR could not evaluate constant expression
+}
@@ -0,0 +1,28 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// `copy_nonoverlapping` reports overlapping ranges during const evaluation.
+
+#![feature(const_let, const_raw_ptr_deref, core_intrinsics)]
+
+use std::intrinsics::copy_nonoverlapping;
+
+static mut OVERLAPPING: [u8; 4] = {
+    //~^ ERROR could not evaluate static initializer
+    let mut buf = [1u8, 2, 3, 4];
+    let src = &buf[0] as *const u8;
+    let dest = &mut buf[1] as *mut u8;
+    unsafe {
+        copy_nonoverlapping(src, dest, 2);
+    }
+    buf
+};
+
+fn main() {}
@@ -0,0 +1,36 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Copying through raw pointers reads and writes memory just like dereferencing them,
+// so it is gated the same way.
+
+#![feature(const_fn, const_let, core_intrinsics)]
+
+use std::intrinsics::{copy, copy_nonoverlapping, write_bytes};
+
+static mut COPY: u8 = {
+    let src = 1u8;
+    let mut dst = 0u8;
+    unsafe { copy(&src as *const u8, &mut dst as *mut u8, 1) };
+    //~^ ERROR dereferencing raw pointers in statics is unstable
+    dst
+};
+
+const unsafe fn copy_byte(src: *const u8, dst: *mut u8) {
+    copy_nonoverlapping(src, dst, 1)
+    //~^ ERROR dereferencing raw pointers in constant functions is unstable
+}
+
+const unsafe fn zero_byte(dst: *mut u8) {
+    write_bytes(dst, 0, 1)
+    //~^ ERROR dereferencing raw pointers in constant functions is unstable
+}
+
+fn main() {}
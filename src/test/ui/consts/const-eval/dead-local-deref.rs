@@ -0,0 +1,32 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(const_fn, const_let, const_raw_ptr_deref)]
+
+// The storage of a local is released when it goes out of scope, so pointers to
+// it cannot be used afterwards.
+
+const fn dangling() -> *const u8 {
+    let x = 42u8;
+    &x as *const u8
+}
+
+const VALUE: u8 = unsafe { *dangling() };
+//~^ ERROR this constant cannot be used
+
+// The same holds for the locals of constant and static initializers themselves.
+const BLOCK: u8 = unsafe { *{ let x = 42u8; &x as *const u8 } };
+//~^ ERROR this constant cannot be used
+
+static STATIC: u8 = unsafe { *{ let x = 42u8; &x as *const u8 } };
+//~^ ERROR could not evaluate static initializer
+
+fn main() {
+}
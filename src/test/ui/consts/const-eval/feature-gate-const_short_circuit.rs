@@ -0,0 +1,22 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Without `const_short_circuit`, `&&` and `||` in constant contexts evaluate
+// both of their operands.
+
+#![feature(const_fn)]
+
+const fn div_is_big(a: u32, b: u32) -> bool {
+    b != 0 && a / b > 10
+}
+
+fn main() {
+    let _: [u8; div_is_big(1, 0) as usize]; //~ ERROR could not evaluate constant expression
+}
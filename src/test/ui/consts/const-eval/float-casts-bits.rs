@@ -0,0 +1,47 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-pass
+
+// The bits of float casts in constants are computed by the compiler, not by the host's
+// floating point unit. Check them while compiling, so that a host which rounds
+// differently (e.g. x87 extended precision) fails here instead of silently producing
+// different constants than the target would at runtime.
+
+#![allow(overflowing_literals, dead_code)]
+
+union F32Bits {
+    f: f32,
+    bits: u32,
+}
+
+union F64Bits {
+    f: f64,
+    bits: u64,
+}
+
+// 2^24 + 1 and 2^53 + 1 are not representable and round to even.
+const TIES_F32: u32 = unsafe { F32Bits { f: 16777217i32 as f32 }.bits };
+const TIES_F64: u64 = unsafe { F64Bits { f: 9007199254740993u64 as f64 }.bits };
+const U64_MAX_F32: u32 = unsafe { F32Bits { f: 18446744073709551615u64 as f32 }.bits };
+const I64_MIN_F64: u64 = unsafe { F64Bits { f: -9223372036854775808i64 as f64 }.bits };
+const NARROW: u32 = unsafe { F32Bits { f: 0.1f64 as f32 }.bits };
+const WIDEN: u64 = unsafe { F64Bits { f: 0.1f32 as f64 }.bits };
+const TRUNC_NEG: i32 = -2.9f32 as i32;
+
+const CHECK_TIES_F32: [(); 1] = [(); (TIES_F32 == 0x4b800000) as usize];
+const CHECK_TIES_F64: [(); 1] = [(); (TIES_F64 == 0x4340000000000000) as usize];
+const CHECK_U64_MAX_F32: [(); 1] = [(); (U64_MAX_F32 == 0x5f800000) as usize];
+const CHECK_I64_MIN_F64: [(); 1] = [(); (I64_MIN_F64 == 0xc3e0000000000000) as usize];
+const CHECK_NARROW: [(); 1] = [(); (NARROW == 0x3dcccccd) as usize];
+const CHECK_WIDEN: [(); 1] = [(); (WIDEN == 0x3fb99999a0000000) as usize];
+const CHECK_TRUNC_NEG: [(); 1] = [(); (TRUNC_NEG == -2) as usize];
+
+fn main() {}
@@ -0,0 +1,32 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(core_intrinsics)]
+
+use std::intrinsics::float_to_int_unchecked;
+
+const TOO_LARGE: u8 = unsafe { float_to_int_unchecked(300.5f32) };
+//~^ ERROR this constant cannot be used
+
+const NAN: i32 = unsafe { float_to_int_unchecked(::std::f64::NAN) };
+//~^ ERROR this constant cannot be used
+
+const IN_RANGE: i8 = unsafe { float_to_int_unchecked(-2.9f64) };
+
+// Typeck does not restrict the types, only codegen and const evaluation do.
+const FROM_INT: u8 = unsafe { float_to_int_unchecked(5u32) };
+//~^ ERROR this constant cannot be used
+
+const TO_FLOAT: f32 = unsafe { float_to_int_unchecked(5.0f64) };
+//~^ ERROR this constant cannot be used
+
+fn main() {
+    assert_eq!(IN_RANGE, -2);
+}
@@ -0,0 +1,30 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Zunleash-the-miri-inside-of-you
+
+#![feature(const_fn, const_let, const_transmute)]
+
+use std::mem::transmute;
+
+const fn first(x: &u8) -> usize {
+    *x as usize
+}
+
+// The references are compatible in the signature, but `first` gets a slice where it
+// expects a thin pointer.
+const fn call() -> usize {
+    let f: fn(&[u8]) -> usize = unsafe { transmute(first as fn(&u8) -> usize) };
+    f(&[1, 2]) //~ WARN skipping const checks
+}
+
+fn main() {
+    let _ = [0; call()]; //~ ERROR could not evaluate repeat length
+}
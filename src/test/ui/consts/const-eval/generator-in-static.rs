@@ -0,0 +1,24 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-pass
+
+#![feature(generators)]
+
+// Validating the static reads the state of the generator to find out which of its
+// suspension states it is in. The local saved across the `yield` is still uninitialized,
+// since the generator has not been resumed yet.
+static GEN: &(dyn Sync) = &|| {
+    let x = 1;
+    yield;
+    drop(x);
+};
+
+fn main() {}
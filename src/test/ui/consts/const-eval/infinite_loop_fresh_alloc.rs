@@ -0,0 +1,29 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// The loop takes the address of a new local in every iteration, so its state
+// only repeats if allocations are compared by content rather than by id.
+
+#![feature(const_let)]
+#![allow(unused)]
+
+fn main() {
+    let _ = [(); {
+        //~^ WARNING Constant evaluating a complex constant, this might take some time
+        //~| ERROR could not evaluate repeat length
+        let mut n = 0i32;
+        let mut p = &n as *const i32;
+        loop { //~ ERROR constant contains unimplemented expression type
+            let y = n;
+            p = &y;
+            n = y / 1;
+        }
+    }];
+}
@@ -0,0 +1,27 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#[repr(u8)]
+#[derive(Copy, Clone)]
+enum Enum {
+    A = 0,
+    B = 1,
+}
+union TransmuteEnum {
+    a: u8,
+    b: Enum,
+}
+
+const BAD: u8 = unsafe { TransmuteEnum { a: 3 }.b } as u8;
+//~^ ERROR this constant cannot be used
+
+fn main() {
+}
@@ -24,6 +24,4 @@ fn main() {
     println!("{} {}", X, Y);
     //~^ ERROR erroneous constant used
     //~| ERROR erroneous constant used
-    //~| ERROR E0080
-    //~| ERROR E0080
 }
@@ -25,7 +25,6 @@ impl<A: Unsigned, B: Unsigned> Unsigned for Sum<A,B> {
 
 fn foo<T>(_: T) -> &'static u8 {
     &Sum::<U8,U8>::MAX //~ ERROR erroneous constant used
-//~| ERROR E0080
 }
 
 fn main() {
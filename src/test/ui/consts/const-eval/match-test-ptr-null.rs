@@ -15,7 +15,7 @@ fn main() {
     let _: [u8; 0] = [4; { //~ ERROR could not evaluate repeat length
         match &1 as *const i32 as usize { //~ ERROR casting pointers to integers in constants
             0 => 42, //~ ERROR constant contains unimplemented expression type
-            //~^ NOTE "pointer arithmetic or comparison" needs an rfc before being allowed
+            //~^ NOTE pointer arithmetic or comparison is not supported in constants
             n => n,
         }
     }];
@@ -0,0 +1,27 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// normalize-stderr-test "allocation \d+" -> "allocation N"
+
+// The `offset` intrinsic may only compute pointers into the allocation it starts in, or
+// one past its end.
+
+#![feature(core_intrinsics, const_raw_ptr_deref)]
+
+use std::intrinsics::offset;
+
+const SECOND: u8 = unsafe { *offset(&[1u8, 2] as *const [u8; 2] as *const u8, 1) };
+const END: *const u8 = unsafe { offset(&0u32 as *const u32 as *const u8, 4) };
+const PAST_END: *const u8 = unsafe { offset(&0u32 as *const u32 as *const u8, 5) };
+//~^ ERROR this constant cannot be used
+
+fn main() {
+    assert_eq!(SECOND, 2);
+}
@@ -0,0 +1,26 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Only comparisons of pointers with null have a result that does not depend
+// on where allocations are placed, all others are rejected.
+
+#![feature(const_compare_raw_pointers)]
+
+const IS_NULL: bool = &1 as *const i32 == 0 as *const i32;
+
+const IS_FOUR: bool = &1 as *const i32 == 4 as *const i32;
+//~^ ERROR this constant cannot be used
+
+const IS_NOT_EIGHT: bool = 8 as *const i32 != &1 as *const i32;
+//~^ ERROR this constant cannot be used
+
+fn main() {
+    assert!(!IS_NULL);
+}
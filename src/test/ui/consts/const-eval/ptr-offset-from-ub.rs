@@ -0,0 +1,27 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(core_intrinsics)]
+
+use std::intrinsics::{arith_offset, ptr_offset_from};
+
+const ARRAY: &[u16; 4] = &[1, 2, 3, 4];
+const START: *const u16 = ARRAY as *const [u16; 4] as *const u16;
+const BYTES: *const u8 = START as *const u8;
+
+const UNIT: *const () = &();
+
+const ZST: isize = unsafe { ptr_offset_from(UNIT, UNIT) };
+//~^ ERROR this constant cannot be used
+
+const INEXACT: isize = unsafe { ptr_offset_from(arith_offset(BYTES, 3) as *const u16, START) };
+//~^ ERROR this constant cannot be used
+
+fn main() {}
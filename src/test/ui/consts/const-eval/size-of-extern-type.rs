@@ -0,0 +1,32 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// The size of an extern type is unknown, also during constant evaluation.
+
+#![feature(extern_types, core_intrinsics, const_raw_ptr_deref)]
+#![allow(dead_code)]
+
+use std::intrinsics::{min_align_of_val, size_of_val};
+
+extern {
+    type Opaque;
+}
+
+struct Tail(u8, Opaque);
+
+const BYTE: &u8 = &0;
+
+const SIZE: usize = unsafe { size_of_val(&*(BYTE as *const u8 as *const Opaque)) };
+//~^ ERROR this constant cannot be used
+
+const ALIGN: usize = unsafe { min_align_of_val(&*(BYTE as *const u8 as *const Tail)) };
+//~^ ERROR this constant cannot be used
+
+fn main() {}
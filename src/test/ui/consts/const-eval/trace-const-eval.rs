@@ -0,0 +1,25 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-pass
+// compile-flags: -Z trace-const-eval=outer
+
+#![feature(const_fn)]
+
+// Only `outer` is traced, together with `inner`, which it calls, but not the constant
+// that calls `outer`.
+
+const fn inner() -> u8 { 1 }
+
+const fn outer() -> u8 { inner() }
+
+const VALUE: u8 = outer();
+
+fn main() {}
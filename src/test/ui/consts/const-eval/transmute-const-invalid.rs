@@ -0,0 +1,21 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Values produced by `transmute` during const evaluation are validated.
+
+#![feature(const_transmute)]
+
+use std::mem;
+
+static BAD_BOOL: bool = unsafe { mem::transmute(3u8) };
+//~^ ERROR this static likely exhibits undefined behavior
+
+fn main() {}
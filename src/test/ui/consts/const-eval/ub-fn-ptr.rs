@@ -0,0 +1,25 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+union Foo {
+    a: &'static u8,
+    b: fn(),
+    c: usize,
+}
+
+// A function pointer must point to a function
+const DATA_AS_FN: fn() = unsafe { Foo { a: &42 }.b };
+//~^ ERROR this constant likely exhibits undefined behavior
+
+// An integer might be a function's address, that's the user's problem
+const INT_AS_FN: fn() = unsafe { Foo { c: 1337 }.b };
+
+fn main() {
+}
@@ -0,0 +1,30 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+union Foo {
+    a: &'static u8,
+    b: &'static u16,
+}
+
+// A reference must be aligned for its pointee type
+const UNALIGNED: &u16 = unsafe { Foo { a: &[0u8; 4][1] }.b };
+//~^ ERROR this constant likely exhibits undefined behavior
+
+union Bar {
+    a: &'static u32,
+    b: &'static [u32; 2],
+}
+
+// A reference must point to memory that is entirely in bounds
+const OUT_OF_BOUNDS: (u8, [&[u32; 2]; 1]) = (0, [unsafe { Bar { a: &0 }.b }]);
+//~^ ERROR this constant likely exhibits undefined behavior
+
+fn main() {
+}
@@ -0,0 +1,41 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::num::NonZeroU8;
+
+union TransmuteNonZero {
+    a: u8,
+    b: NonZeroU8,
+}
+
+// `NonZero` types may not be zero
+const ZERO: NonZeroU8 = unsafe { TransmuteNonZero { a: 0 }.b };
+//~^ ERROR this constant likely exhibits undefined behavior
+
+union TransmuteBool {
+    a: u8,
+    b: bool,
+}
+
+// `bool` must be 0 or 1
+const BAD_BOOL: (u8, bool) = (0, unsafe { TransmuteBool { a: 2 }.b });
+//~^ ERROR this constant likely exhibits undefined behavior
+
+union TransmuteChar {
+    a: u32,
+    b: char,
+}
+
+// Surrogates are not valid code points
+const SURROGATE: char = unsafe { TransmuteChar { a: 0xD800 }.b };
+//~^ ERROR this constant likely exhibits undefined behavior
+
+fn main() {
+}
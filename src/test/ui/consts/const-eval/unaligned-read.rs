@@ -0,0 +1,30 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// normalize-stderr-test "allocation \d+" -> "allocation N"
+
+#![feature(const_raw_ptr_deref, core_intrinsics)]
+
+use std::intrinsics::arith_offset;
+
+const WORDS: &[u32; 2] = &[1, 2];
+const WORD_BYTES: *const u8 = WORDS as *const [u32; 2] as *const u8;
+const BYTES: &[u8; 8] = &[0; 8];
+const BYTES_START: *const u8 = BYTES as *const [u8; 8] as *const u8;
+
+// The allocation is aligned, but the pointer is not
+const UNALIGNED_OFFSET: u32 = unsafe { *(arith_offset(WORD_BYTES, 2) as *const u32) };
+//~^ ERROR this constant cannot be used
+
+// The allocation itself is not aligned enough
+const UNALIGNED_ALLOCATION: u32 = unsafe { *(BYTES_START as *const u32) };
+//~^ ERROR this constant cannot be used
+
+fn main() {}
@@ -0,0 +1,34 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(core_intrinsics)]
+
+use std::intrinsics;
+
+const INEXACT: u32 = unsafe { intrinsics::exact_div(7, 2) };
+//~^ ERROR this constant cannot be used
+
+const SHL: u8 = unsafe { intrinsics::unchecked_shl(1u8, 8) };
+//~^ ERROR this constant cannot be used
+
+const DIV: i8 = unsafe { intrinsics::unchecked_div(-128i8, -1) };
+//~^ ERROR this constant cannot be used
+
+const ZERO: u16 = unsafe { intrinsics::unchecked_rem(3, 0) };
+//~^ ERROR this constant cannot be used
+
+const EXACT_ZERO: u32 = unsafe { intrinsics::exact_div(1, 0) };
+//~^ ERROR this constant cannot be used
+
+const EXACT: u32 = unsafe { intrinsics::exact_div(8, 2) };
+
+fn main() {
+    assert_eq!(EXACT, 4);
+}
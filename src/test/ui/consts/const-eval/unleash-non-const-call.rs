@@ -0,0 +1,22 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-pass
+// compile-flags: -Zunleash-the-miri-inside-of-you
+
+// With the const checks skipped, const evaluation runs non-const fns as well.
+
+fn double(x: usize) -> usize {
+    x * 2
+}
+
+fn main() {
+    let _: [u8; 8] = [0; double(4)]; //~ WARN skipping const checks
+}
@@ -0,0 +1,25 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Zunwind-in-const-eval
+
+// The failed bounds check unwinds through the cleanup block of `get`, which drops
+// `_guard`, so the error is reported without the frame of `get`.
+
+#![feature(const_fn, const_let)]
+
+const fn get(idx: usize) -> usize {
+    let _guard: Option<String> = None;
+    [1, 2, 3][idx]
+}
+
+fn main() {
+    let _ = [0; get(3)]; //~ ERROR could not evaluate repeat length
+}
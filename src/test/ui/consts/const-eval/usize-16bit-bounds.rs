@@ -0,0 +1,52 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: --crate-type rlib --target msp430-none-elf --emit=metadata
+// normalize-stderr-test "allocation \d+" -> "allocation N"
+
+// Negative pointer offsets and pointer differences wrap around at the
+// 16-bit pointer width of the target instead of at 64 bits, and no access
+// may span more than half of the 16-bit address space.
+
+#![feature(no_core, lang_items, intrinsics, const_raw_ptr_deref)]
+#![no_core]
+
+#[lang = "sized"]
+trait Sized {}
+
+#[lang = "copy"]
+trait Copy {}
+
+impl Copy for u8 {}
+
+extern "rust-intrinsic" {
+    fn arith_offset<T>(dst: *const T, offset: isize) -> *const T;
+    fn copy<T>(src: *const T, dst: *mut T, count: usize);
+    fn ptr_offset_from<T>(ptr: *const T, base: *const T) -> isize;
+}
+
+const BASE: *const u8 = &[1u8, 2, 3, 4] as *const [u8; 4] as *const u8;
+const MINUS_TWO: isize = 0xFFFE_u16 as isize;
+
+const LOW: *const u8 = 0x10 as *const u8;
+const HIGH: *const u8 = 0xFFF0 as *const u8;
+
+// The distance from `HIGH` to `LOW` wraps around at 16 bits.
+pub const WRAPPED: [u8; unsafe { ptr_offset_from(LOW, HIGH) } as usize] = [0; 0x20];
+
+// `BASE - 2 + 3` is `BASE + 1`.
+pub const AFTER: [u8; unsafe { *arith_offset(arith_offset(BASE, MINUS_TWO), 3) } as usize] = [0, 0];
+
+// `BASE - 2` is at offset `0xFFFE`, so reading it is out of bounds.
+pub const BEFORE: u8 = unsafe { *arith_offset(BASE, MINUS_TWO) };
+//~^ ERROR this constant cannot be used
+
+pub const TOO_BIG: () = unsafe { copy(BASE, BASE as *mut u8, 0x8000) };
+//~^ ERROR this constant cannot be used
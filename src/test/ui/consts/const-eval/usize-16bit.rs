@@ -0,0 +1,30 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// compile-pass
+// compile-flags: --crate-type rlib --target msp430-none-elf --emit=metadata
+
+// Constants are evaluated with the pointer width of the target, so on a
+// 16-bit target `usize` truncates and wraps at 16 bits.
+
+#![feature(no_core, lang_items)]
+#![no_core]
+
+#[lang = "sized"]
+trait Sized {}
+
+#[lang = "copy"]
+trait Copy {}
+
+pub const WRAPPED: usize = 0x1_0001_u32 as usize;
+pub const ONE: [u8; WRAPPED] = [0];
+pub const TWO: [u8; 0x2_0002_u64 as usize] = [0, 0];
+pub const THREE: [u8; 0xFFFF_0003_u32 as isize as usize] = [0, 0, 0];
@@ -0,0 +1,28 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Zvalidate-const-eval-writes
+
+// With the flag, an invalid value is rejected as soon as it is written, even if the final
+// value of the static would pass validation.
+
+union Transmute {
+    int: u8,
+    b: bool,
+}
+
+static BAD_BOOL: u8 = unsafe {
+    //~^ ERROR could not evaluate static initializer
+    Transmute { int: 3 }.b as u8
+};
+
+static GOOD_BOOL: u8 = unsafe { Transmute { int: 1 }.b as u8 };
+
+fn main() {}
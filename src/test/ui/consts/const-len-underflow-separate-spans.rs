@@ -20,6 +20,4 @@ fn main() {
     let a: [i8; LEN] = unimplemented!();
 //~^ ERROR E0080
 //~| ERROR E0080
-//~| ERROR E0080
-//~| ERROR E0080
 }
@@ -0,0 +1,19 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// The length of a constant slice is known during const propagation, so
+// out-of-bounds indexing into it is detected at compile time.
+
+const SLICE: &[u8] = &[1, 2, 3];
+
+fn main() {
+    SLICE[2];
+    SLICE[5]; //~ ERROR index out of bounds: the len is 3 but the index is 5
+}
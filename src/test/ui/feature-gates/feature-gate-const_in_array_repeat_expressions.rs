@@ -0,0 +1,21 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Test that using a non-`Copy` constant in a repeat expression is gated.
+
+struct Bar;
+
+const BAR: Bar = Bar;
+
+fn main() {
+    let _: [Bar; 2] = [BAR; 2];
+    //~^ ERROR the trait bound `Bar: std::marker::Copy` is not satisfied
+}
@@ -0,0 +1,27 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Indexing with a compile-time known index that is out of bounds panics
+// unconditionally, also when the index is the result of some arithmetic.
+
+fn main() {
+    let array = [1u8, 2, 3];
+    array[1 + 2]; //~ ERROR index out of bounds: the len is 3 but the index is 3
+    array[2 * 2 - 2];
+    allowed();
+}
+
+// Same as above, but the lint is allowed. The array is a local, so the
+// index is known to const propagation and would be linted otherwise.
+#[allow(unconditional_panic)]
+fn allowed() -> u8 {
+    let array = [1u8, 2, 3];
+    array[4]
+}
@@ -0,0 +1,37 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// check that conflicts due to a closure using a field of a captured variable
+// point out that the whole variable is captured
+
+#![feature(nll)]
+
+struct Container {
+    items: Vec<u32>,
+    len: usize,
+}
+
+fn mutable_capture(mut c: Container) {
+    let f = || c.items.push(1);
+    let n = &c.len; //~ ERROR
+    f.use_ref();
+}
+
+fn shared_capture(mut c: Container) {
+    let f = || c.items.len();
+    c.len = 1; //~ ERROR
+    f.use_ref();
+}
+
+fn main() {}
+
+trait Fake { fn use_mut(&mut self) { } fn use_ref(&self) { }  }
+impl<T> Fake for T { }
@@ -0,0 +1,29 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-pass
+
+// Without `-Z move-path-depth-limit`, moves are tracked precisely however deeply the
+// moved place is nested, so moving out of sibling fields is fine.
+
+#![allow(warnings)]
+#![feature(nll)]
+
+struct N<T> { a: T, b: T }
+
+type Deep = N<N<N<N<N<N<N<N<N<N<N<N<N<N<N<N<N<N<String>>>>>>>>>>>>>>>>>>;
+
+fn split(x: Deep) -> (String, String) {
+    let a = x.a.a.a.a.a.a.a.a.a.a.a.a.a.a.a.a.a.a;
+    let b = x.a.a.a.a.a.a.a.a.a.a.a.a.a.a.a.a.a.b;
+    (a, b)
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Moves out of places nested deeper than `-Z move-path-depth-limit` are
+// treated as moves out of their ancestor at the limit.
+
+// compile-flags: -Z move-path-depth-limit=2
+
+#![allow(warnings)]
+#![feature(nll)]
+
+struct A { b: B }
+struct B { c: C }
+struct C { d: String, e: String }
+
+fn main() {
+    let x = A { b: B { c: C { d: String::new(), e: String::new() } } };
+    let d = x.b.c.d;
+    let e = x.b.c.e;
+    //~^ ERROR use of moved value: `x.b.c.e` [E0382]
+}